@@ -0,0 +1,210 @@
+//! Synthetic memory images of a python 2.7 client with a UI tree, laid out like the real one.
+
+use pyevereader::eve_process::process::{MemoryRegion, ProcessHandle, RegionKind, ScanOptions};
+use pyevereader::eve_process::py_struct::*;
+use std::mem::{offset_of, size_of, size_of_val};
+
+/// The scans view whole structs at every 8 bytes, up to the last ones of a region.
+const REGION_SLACK: usize = 64;
+
+struct ImageBuilder {
+    start: u64,
+    data: Vec<u8>,
+}
+
+impl ImageBuilder {
+    fn new(start: u64) -> Self {
+        ImageBuilder { start, data: vec![] }
+    }
+
+    fn alloc(&mut self, size: usize) -> u64 {
+        let addr = self.start + self.data.len() as u64;
+        self.data.resize(self.data.len() + size.next_multiple_of(16), 0);
+        addr
+    }
+
+    fn write(&mut self, addr: u64, bytes: &[u8]) {
+        let offset = (addr - self.start) as usize;
+        self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn write_u64(&mut self, addr: u64, value: u64) {
+        self.write(addr, &value.to_le_bytes());
+    }
+
+    fn object(&mut self, ob_type: u64, size: usize) -> u64 {
+        let addr = self.alloc(size);
+        self.write_u64(addr + offset_of!(CPyObject, ob_refcnt) as u64, 1);
+        self.write_u64(addr + offset_of!(CPyObject, ob_type) as u64, ob_type);
+        addr
+    }
+
+    fn type_object(&mut self, type_type: u64, name: &str) -> u64 {
+        let tp_name = self.alloc(name.len() + 1);
+        self.write(tp_name, name.as_bytes());
+        let addr = self.object(type_type, size_of::<CPyTypeObject>());
+        self.write_u64(addr + offset_of!(CPyTypeObject, tp_name) as u64, tp_name);
+        addr
+    }
+
+    fn region(self) -> MemoryRegion {
+        let size = self.data.len();
+        let mut data = self.data;
+        data.resize(size + REGION_SLACK, 0);
+        MemoryRegion {
+            start: self.start,
+            size,
+            data: data.into(),
+            handle: ProcessHandle::None,
+            protection: ScanOptions::PAGE_READWRITE,
+            kind: RegionKind::Private,
+        }
+    }
+}
+
+struct Types {
+    dict: u64,
+    str: u64,
+    int: u64,
+    float: u64,
+    list: u64,
+    ui_root: u64,
+    container: u64,
+}
+
+impl Types {
+    fn new(image: &mut ImageBuilder) -> (u64, Self) {
+        let type_type = image.type_object(0, "type");
+        image.write_u64(type_type + offset_of!(CPyObject, ob_type) as u64, type_type);
+        let types = Types {
+            dict: image.type_object(type_type, "dict"),
+            str: image.type_object(type_type, "str"),
+            int: image.type_object(type_type, "int"),
+            float: image.type_object(type_type, "float"),
+            list: image.type_object(type_type, "list"),
+            ui_root: image.type_object(type_type, "UIRoot"),
+            container: image.type_object(type_type, "Container"),
+        };
+        // the size of dicts tells the interpreter version apart
+        let basicsize = types.dict + offset_of!(CPyTypeObject, tp_basicsize) as u64;
+        image.write_u64(basicsize, size_of::<CPyDictObject>() as u64);
+        (type_type, types)
+    }
+
+    fn str(&self, image: &mut ImageBuilder, text: &str) -> u64 {
+        let sval = offset_of!(CPyStringObject, ob_sval);
+        let addr = image.object(self.str, sval + text.len() + 1);
+        image.write_u64(addr + offset_of!(CPyVarObject, ob_size) as u64, text.len() as u64);
+        image.write(addr + sval as u64, text.as_bytes());
+        addr
+    }
+
+    fn int(&self, image: &mut ImageBuilder, value: i32) -> u64 {
+        let addr = image.object(self.int, size_of::<CPyIntObject>());
+        image.write(addr + offset_of!(CPyIntObject, ob_ival) as u64, &value.to_le_bytes());
+        addr
+    }
+
+    fn float(&self, image: &mut ImageBuilder, value: f64) -> u64 {
+        let addr = image.object(self.float, size_of::<CPyFloatObject>());
+        image.write(addr + offset_of!(CPyFloatObject, ob_fval) as u64, &value.to_le_bytes());
+        addr
+    }
+
+    fn list(&self, image: &mut ImageBuilder, items: &[u64]) -> u64 {
+        let array = image.alloc(size_of_val(items));
+        for (i, item) in items.iter().enumerate() {
+            image.write_u64(array + (i * size_of::<u64>()) as u64, *item);
+        }
+        let addr = image.object(self.list, size_of::<CPyListObject>());
+        image.write_u64(addr + offset_of!(CPyVarObject, ob_size) as u64, items.len() as u64);
+        image.write_u64(addr + offset_of!(CPyListObject, ob_item) as u64, array);
+        image.write_u64(addr + offset_of!(CPyListObject, allocated) as u64, items.len() as u64);
+        addr
+    }
+
+    /// A dict with the entries in consecutive slots, the parser does not look at the hashes.
+    fn dict(&self, image: &mut ImageBuilder, entries: &[(&str, u64)]) -> u64 {
+        let slots = (entries.len() * 2).next_power_of_two().max(8);
+        let table = image.alloc(slots * size_of::<CPyDictEntry>());
+        for (i, (key, value)) in entries.iter().enumerate() {
+            let entry = table + (i * size_of::<CPyDictEntry>()) as u64;
+            let key = self.str(image, key);
+            image.write_u64(entry + offset_of!(CPyDictEntry, me_hash) as u64, i as u64 + 1);
+            image.write_u64(entry + offset_of!(CPyDictEntry, me_key) as u64, key);
+            image.write_u64(entry + offset_of!(CPyDictEntry, me_value) as u64, *value);
+        }
+        let addr = image.object(self.dict, size_of::<CPyDictObject>());
+        image.write_u64(addr + offset_of!(CPyDictObject, ma_fill) as u64, entries.len() as u64);
+        image.write_u64(addr + offset_of!(CPyDictObject, ma_used) as u64, entries.len() as u64);
+        image.write_u64(addr + offset_of!(CPyDictObject, ma_mask) as u64, slots as u64 - 1);
+        image.write_u64(addr + offset_of!(CPyDictObject, ma_table) as u64, table);
+        addr
+    }
+
+    fn instance(&self, image: &mut ImageBuilder, ob_type: u64, attributes: u64) -> u64 {
+        let addr = image.object(ob_type, size_of::<CPyCustomObject>());
+        image.write_u64(addr + offset_of!(CPyCustomObject, attributes) as u64, attributes);
+        addr
+    }
+
+    /// A UI object with `fanout` children down to `depth` levels, returns it and its dict.
+    fn ui_node(&self, image: &mut ImageBuilder, ob_type: u64, name: &str, fanout: usize, depth: usize) -> (u64, u64) {
+        let children: Vec<u64> = (0..if depth > 0 { fanout } else { 0 })
+            .map(|i| self.ui_node(image, self.container, &format!("{}_{}", name, i), fanout, depth - 1).0)
+            .collect();
+        let entries = [
+            ("_name", self.str(image, name)),
+            ("_setText", self.str(image, &format!("<b>{}</b>", name))),
+            ("_displayX", self.int(image, 4)),
+            ("_displayY", self.int(image, 8)),
+            ("_displayWidth", self.int(image, 120)),
+            ("_displayHeight", self.int(image, 24)),
+            ("_opacity", self.float(image, 1.0)),
+            ("children", self.list(image, &children)),
+        ];
+        let dict = self.dict(image, &entries);
+        (self.instance(image, ob_type, dict), dict)
+    }
+}
+
+pub struct Fixture {
+    pub regions: Vec<MemoryRegion>,
+    pub type_type: u64,
+    pub ui_root_type: u64,
+    pub ui_root: u64,
+    /// Instance dict of the UI root.
+    pub ui_root_dict: u64,
+}
+
+impl Fixture {
+    /// A UI tree with `fanout` children per node and `depth` levels below the root, after
+    /// `filler` bytes of unrelated memory.
+    pub fn new(fanout: usize, depth: usize, filler: usize) -> Self {
+        // behind the image, the address lookup misses the last region
+        let mut filler_region = ImageBuilder::new(0x2000_0000);
+        filler_region.alloc(filler);
+        // xorshift noise, so that the scans do not only see zeros
+        let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+        for chunk in filler_region.data.chunks_exact_mut(8) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            chunk.copy_from_slice(&state.to_le_bytes());
+        }
+        let mut image = ImageBuilder::new(0x1000_0000);
+        let (type_type, types) = Types::new(&mut image);
+        let (ui_root, ui_root_dict) = types.ui_node(&mut image, types.ui_root, "root", fanout, depth);
+        Fixture {
+            regions: vec![image.region(), filler_region.region()],
+            type_type,
+            ui_root_type: types.ui_root,
+            ui_root,
+            ui_root_dict,
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.regions.iter().map(|region| region.size as u64).sum()
+    }
+}
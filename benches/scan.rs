@@ -0,0 +1,47 @@
+//! Scan and parse hot paths against a synthetic client image, run with `cargo bench`.
+
+mod fixture;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use fixture::Fixture;
+use pyevereader::eve_process::eve_process::EVEProcess;
+
+/// 3 children per node and 6 levels, about 1100 UI objects.
+fn fixture() -> Fixture {
+    Fixture::new(3, 6, 32 << 20)
+}
+
+fn scan(c: &mut Criterion) {
+    let fixture = fixture();
+    let mut process = EVEProcess::from_regions(fixture.regions.clone());
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(20);
+    group.throughput(Throughput::Bytes(fixture.size()));
+    group.bench_function("init", |b| b.iter(|| assert!(process.init().is_some())));
+    group.bench_function("search_type", |b| {
+        b.iter(|| process.search_type(black_box("UIRoot"), Some(fixture.type_type)))
+    });
+    group.bench_function("search_ui_root", |b| {
+        b.iter(|| process.search_ui_root(Some(fixture.ui_root_type)))
+    });
+    group.finish();
+}
+
+fn parse(c: &mut Criterion) {
+    let fixture = fixture();
+    let mut process = EVEProcess::from_regions(fixture.regions.clone());
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("dict", |b| {
+        b.iter(|| {
+            let dict = process.read_object(black_box(fixture.ui_root_dict)).unwrap();
+            process.parse_dict(&dict).unwrap()
+        })
+    });
+    group.bench_function("ui_tree", |b| {
+        b.iter(|| process.parse_ui_tree(black_box(fixture.ui_root)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scan, parse);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+/* C interface of pyevereader, built with `cargo build --release --features ffi`. */
+#ifndef PYEVEREADER_H
+#define PYEVEREADER_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct PyEveSession PyEveSession;
+
+/* Opens and initializes the EVE client with the given pid, NULL on failure. */
+PyEveSession *pyevereader_attach(uint32_t pid);
+void pyevereader_detach(PyEveSession *session);
+
+/* Returned strings are UTF-8 JSON and must be released with pyevereader_free_string. */
+char *pyevereader_read_ui_json(PyEveSession *session);
+char *pyevereader_read_json(PyEveSession *session, const char *reader);
+
+/* Message of the last failure on the calling thread, NULL if there was none. */
+char *pyevereader_last_error(void);
+void pyevereader_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,170 @@
+use crate::eve_process::eve_process::{EVEProcess, PyObjectNode};
+use crate::eve_process::process::MemoryRegion;
+use crate::eve_process::ui_tree::{PyValue, UiTree};
+use crate::eve_process::ui_watcher::UiUpdate;
+use crate::readers::{self, Reader};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+
+/// An attached client for async code. Scans, region syncs, reads and object parses run on
+/// tokio's blocking pool, so they do not stall the runtime.
+///
+/// Clones share the client, their calls run one at a time.
+#[doc(alias = "AsyncProcess")]
+#[derive(Debug, Clone)]
+pub struct AsyncClient {
+    process: Arc<Mutex<EVEProcess>>,
+}
+
+/// Opens and initializes the client `pid`, or the first client found.
+pub async fn attach_async(pid: Option<u32>) -> io::Result<AsyncClient> {
+    let process = blocking(move || {
+        let pid = match pid {
+            Some(pid) => pid,
+            None => {
+                let client = EVEProcess::list_clients()?.into_iter().next();
+                client.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no EVE client running"))?.pid
+            }
+        };
+        let mut process = EVEProcess::open(pid)?;
+        process
+            .init()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "python type object not found"))?;
+        Ok(process)
+    })
+    .await?;
+    Ok(AsyncClient::new(process))
+}
+
+async fn blocking<T, F>(f: F) -> io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    task::spawn_blocking(f).await.map_err(io::Error::other)?
+}
+
+impl AsyncClient {
+    /// Wraps an initialized client.
+    pub fn new(process: EVEProcess) -> Self {
+        AsyncClient {
+            process: Arc::new(Mutex::new(process)),
+        }
+    }
+
+    /// Runs `f` on the client on the blocking pool.
+    pub async fn with_process<T, F>(&self, f: F) -> io::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut EVEProcess) -> io::Result<T> + Send + 'static,
+    {
+        let process = self.process.clone();
+        blocking(move || f(&mut process.lock().unwrap_or_else(|e| e.into_inner()))).await
+    }
+
+    pub async fn ui_tree(&self) -> io::Result<UiTree> {
+        self.with_process(|process| {
+            process
+                .ui_tree()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no UI root found"))
+        })
+        .await
+    }
+
+    /// Parses the UI tree below the `UIRoot` at `ui_root_addr`.
+    pub async fn parse_ui_tree(&self, ui_root_addr: u64) -> io::Result<UiTree> {
+        self.with_process(move |process| {
+            process
+                .parse_ui_tree(ui_root_addr)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("no UI tree at 0x{:X}", ui_root_addr)))
+        })
+        .await
+    }
+
+    /// Lists the regions again and reads the new and changed ones, see
+    /// [`Process::refresh_regions`](crate::eve_process::process::Process::refresh_regions).
+    pub async fn refresh_regions(&self) -> io::Result<usize> {
+        self.with_process(|process| Ok(process.process.refresh_regions())).await
+    }
+
+    /// See [`Process::sync_dirty_pages`](crate::eve_process::process::Process::sync_dirty_pages).
+    pub async fn sync_dirty_pages(&self) -> io::Result<usize> {
+        self.with_process(|process| process.process.sync_dirty_pages()).await
+    }
+
+    /// See [`Process::resync_pages`](crate::eve_process::process::Process::resync_pages).
+    pub async fn resync_pages(&self, addrs: Vec<u64>) -> io::Result<usize> {
+        self.with_process(move |process| process.process.resync_pages(addrs)).await
+    }
+
+    /// Reads the regions that are not synced yet, see
+    /// [`Process::sync_remaining_regions`](crate::eve_process::process::Process::sync_remaining_regions).
+    pub async fn sync_remaining_regions(&self) -> io::Result<()> {
+        self.with_process(|process| {
+            process.process.sync_remaining_regions();
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn read_memory(&self, addr: u64, size: usize) -> io::Result<MemoryRegion> {
+        self.with_process(move |process| process.process.read_memory(addr, size)).await
+    }
+
+    pub async fn read_object(&self, addr: u64) -> io::Result<PyObjectNode> {
+        self.with_process(move |process| process.read_object(addr)).await
+    }
+
+    /// Follows an attribute path like `children[2]._name` from the object at `addr`, see
+    /// [`EVEProcess::follow`].
+    pub async fn follow(&self, addr: u64, path: &str) -> io::Result<PyObjectNode> {
+        let path = path.to_string();
+        self.with_process(move |process| process.follow(addr, &path)).await
+    }
+
+    /// Reads the object at `addr` as a [`PyValue`], see [`EVEProcess::materialize`].
+    pub async fn materialize(&self, addr: u64, depth: usize) -> io::Result<PyValue> {
+        self.with_process(move |process| process.materialize(addr, depth)).await
+    }
+
+    /// Reads a fresh UI tree and runs `R` on it.
+    pub async fn read<R: Reader + Send + 'static>(&self) -> io::Result<Option<R>> {
+        Ok(self.ui_tree().await?.read::<R>())
+    }
+
+    /// Reads a fresh UI tree and runs the reader called `name`, see [`readers::read_json`].
+    pub async fn read_json(&self, name: &str) -> io::Result<Option<serde_json::Value>> {
+        readers::read_json(&self.ui_tree().await?, name)
+    }
+
+    /// Reads the UI tree every `interval` like a
+    /// [`UiWatcher`](crate::eve_process::ui_watcher::UiWatcher), as long as the stream is kept.
+    ///
+    /// The reads run on a thread of the blocking pool, which is held until the stream is dropped.
+    pub fn ui_events(&self, interval: Duration) -> impl Stream<Item = UiUpdate> {
+        let (updates, receiver) = mpsc::channel(4);
+        let process = self.process.clone();
+        task::spawn_blocking(move || {
+            let mut previous: Option<UiTree> = None;
+            while !updates.is_closed() {
+                let started = Instant::now();
+                let tree = process.lock().unwrap_or_else(|e| e.into_inner()).ui_tree();
+                if let Some(tree) = tree {
+                    let changes = previous.as_ref().map(|previous| tree.diff(previous)).unwrap_or_default();
+                    previous = Some(tree.clone());
+                    if updates.blocking_send(UiUpdate { tree, changes }).is_err() {
+                        break;
+                    }
+                }
+                thread::sleep(interval.saturating_sub(started.elapsed()));
+            }
+        });
+        ReceiverStream::new(receiver)
+    }
+}
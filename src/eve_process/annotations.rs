@@ -0,0 +1,118 @@
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tracing::warn;
+
+lazy_static! {
+    /// None until first used, the error of a file that failed to load is kept to not read it again.
+    static ref ANNOTATIONS: RwLock<Option<io::Result<Annotations>>> = RwLock::new(None);
+}
+
+/// Labels for addresses of interesting structures, kept in a JSON file of hex addresses like
+/// `{"0x1F2A3B40": "overview_scroll"}`.
+///
+/// Addresses only stay valid while the client runs, labels of a previous session have to be
+/// removed or updated by hand.
+#[derive(Debug, Clone, Default)]
+pub struct Annotations {
+    path: PathBuf,
+    labels: BTreeMap<u64, String>,
+}
+
+impl Annotations {
+    /// `%APPDATA%\pyevereader\annotations.json`, or the working directory without `APPDATA`.
+    pub fn default_path() -> PathBuf {
+        match env::var_os("APPDATA") {
+            Some(app_data) => Path::new(&app_data).join("pyevereader").join("annotations.json"),
+            None => PathBuf::from("annotations.json"),
+        }
+    }
+
+    /// Loads the labels of `path`, none if the file does not exist yet.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let labels = match fs::read(&path) {
+            Ok(data) => {
+                let hex: BTreeMap<String, String> = serde_json::from_slice(&data)?;
+                hex.into_iter()
+                    .map(|(addr, label)| {
+                        let digits = addr.trim_start_matches("0x").trim_start_matches("0X");
+                        u64::from_str_radix(digits, 16)
+                            .map(|addr| (addr, label))
+                            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("invalid address {:?}", addr)))
+                    })
+                    .collect::<io::Result<_>>()?
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Annotations { path, labels })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let hex: BTreeMap<String, &String> =
+            self.labels.iter().map(|(addr, label)| (format!("0x{:X}", addr), label)).collect();
+        fs::write(&self.path, serde_json::to_string_pretty(&hex)?)
+    }
+
+    pub fn get(&self, addr: u64) -> Option<&str> {
+        self.labels.get(&addr).map(String::as_str)
+    }
+
+    /// Labels `addr` and saves the file, an empty label removes the annotation.
+    pub fn annotate(&mut self, addr: u64, label: &str) -> io::Result<()> {
+        if label.is_empty() {
+            self.labels.remove(&addr);
+        } else {
+            self.labels.insert(addr, label.to_string());
+        }
+        self.save()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &str)> {
+        self.labels.iter().map(|(addr, label)| (*addr, label.as_str()))
+    }
+}
+
+/// Runs `f` on the process wide annotations, loaded from [`Annotations::default_path`] on first use.
+/// Fails without running `f` if they could not be loaded, so that a damaged file is not overwritten.
+fn with_global<T>(f: impl FnOnce(&mut Annotations) -> io::Result<T>) -> io::Result<T> {
+    let mut global = ANNOTATIONS.write().unwrap_or_else(|e| e.into_inner());
+    let loaded = global.get_or_insert_with(|| {
+        let path = Annotations::default_path();
+        Annotations::load(&path).inspect_err(|e| warn!("failed to load annotations from {}: {}", path.display(), e))
+    });
+    match loaded {
+        Ok(annotations) => f(annotations),
+        Err(e) => Err(io::Error::new(e.kind(), e.to_string())),
+    }
+}
+
+/// Makes the annotations of `path` the process wide ones, used by [`annotate`] and [`annotation`].
+pub fn use_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let annotations = Annotations::load(path)?;
+    *ANNOTATIONS.write().unwrap_or_else(|e| e.into_inner()) = Some(Ok(annotations));
+    Ok(())
+}
+
+/// Labels `addr` in the process wide annotations, which show up in debug output and the
+/// explorers, and saves them. An empty label removes the annotation.
+pub fn annotate(addr: u64, label: &str) -> io::Result<()> {
+    with_global(|annotations| annotations.annotate(addr, label))
+}
+
+/// The process wide label of `addr`, none while the annotations failed to load.
+pub fn annotation(addr: u64) -> Option<String> {
+    // debug output and the explorers ask for every object they show
+    if let Some(loaded) = ANNOTATIONS.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return loaded.as_ref().ok()?.get(addr).map(str::to_string);
+    }
+    with_global(|annotations| Ok(annotations.get(addr).map(str::to_string))).ok().flatten()
+}
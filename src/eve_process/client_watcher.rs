@@ -0,0 +1,91 @@
+use crate::eve_process::eve_process::{EVEProcess, StrEncoding};
+use crate::eve_process::process::ScanOptions;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Reported by [`ClientWatcher::poll`] with the pid of the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientEvent {
+    /// A client was found and initialized again after a restart.
+    Attached(u32),
+    Detached(u32),
+}
+
+/// Waits for a client to exit and attaches to it again once a client of the same executable
+/// is running, see [`EVEProcess::watch`].
+#[derive(Debug)]
+pub struct ClientWatcher {
+    /// The attached client, `None` from its exit until the next one is initialized.
+    pub process: Option<EVEProcess>,
+    /// How long to wait for an exit, or between looking for a new client.
+    pub interval: Duration,
+    path: String,
+    str_encoding: StrEncoding,
+}
+
+impl EVEProcess {
+    /// Watches this client for restarts. It should be initialized, so that the watcher only
+    /// hands out initialized clients.
+    pub fn watch(self, interval: Duration) -> ClientWatcher {
+        ClientWatcher {
+            path: self.process.path.clone(),
+            str_encoding: self.str_encoding,
+            process: Some(self),
+            interval,
+        }
+    }
+}
+
+impl ClientWatcher {
+    /// Waits up to the interval for the client to exit, or looks for a new one once, returns
+    /// what changed. Clients that are not running processes, like dumps, never exit.
+    pub fn poll(&mut self) -> Option<ClientEvent> {
+        let started = Instant::now();
+        let event = match &self.process {
+            Some(process) => match process.process.wait_for_exit(self.interval) {
+                Ok(true) => {
+                    let pid = process.process.pid;
+                    self.process = None;
+                    Some(ClientEvent::Detached(pid))
+                }
+                Ok(false) => None,
+                Err(error) => {
+                    debug!("can not wait for the exit of {}: {}", process.process.pid, error);
+                    None
+                }
+            },
+            None => self.reattach().map(|process| {
+                let pid = process.process.pid;
+                self.process = Some(process);
+                ClientEvent::Attached(pid)
+            }),
+        };
+        if event.is_none() {
+            thread::sleep(self.interval.saturating_sub(started.elapsed()));
+        }
+        event
+    }
+
+    /// A client of the watched executable, if one is running and its python types are found,
+    /// which takes a while after it started.
+    fn reattach(&self) -> Option<EVEProcess> {
+        let client = EVEProcess::list_clients().ok()?.into_iter().find(|client| client.path == self.path)?;
+        let options = ScanOptions::for_process(&client);
+        let mut process = EVEProcess::from_process(client, &options);
+        process.str_encoding = self.str_encoding;
+        process.init()?;
+        Some(process)
+    }
+
+    /// Polls until `callback` returns `false`, passing the client after attaching.
+    pub fn run<F: FnMut(ClientEvent, Option<&mut EVEProcess>) -> bool>(&mut self, mut callback: F) {
+        loop {
+            if let Some(event) = self.poll() {
+                if !callback(event, self.process.as_mut()) {
+                    return;
+                }
+            }
+        }
+    }
+}
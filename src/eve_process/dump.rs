@@ -0,0 +1,119 @@
+//! The memory dump format of [`Process::write_dump`] and [`Process::save_snapshot`]: the pid,
+//! a table of the regions, then the region data in the same order, all little endian. Snapshots
+//! are the same gzip compressed, uncompressed dumps are read from the file as a [`DumpFile`].
+
+use crate::eve_process::process::{MemoryRegion, Process, ProcessHandle, RegionKind};
+use crate::eve_process::source::{DumpFile, DumpRange};
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"PYEVDUMP";
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+/// 1 had no protections in the region table.
+const VERSION: u32 = 2;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Writes the regions that are synced or can be synced now.
+pub(crate) fn write<W: Write>(process: &Process, out: &mut W) -> io::Result<()> {
+    process.sync_remaining_regions();
+    let regions: Vec<_> = process.regions.iter().filter(|region| region.is_synced()).collect();
+    out.write_all(MAGIC)?;
+    out.write_all(&VERSION.to_le_bytes())?;
+    out.write_all(&process.pid.to_le_bytes())?;
+    out.write_all(&(regions.len() as u64).to_le_bytes())?;
+    for region in &regions {
+        out.write_all(&region.start.to_le_bytes())?;
+        out.write_all(&(region.size as u64).to_le_bytes())?;
+        out.write_all(&region.protection.to_le_bytes())?;
+    }
+    for region in &regions {
+        out.write_all(&region.bytes()[..region.size])?;
+    }
+    Ok(())
+}
+
+/// Reads a dump, compressed or not, returning the pid and the regions.
+pub(crate) fn read<R: BufRead>(mut input: R) -> io::Result<(u32, Vec<MemoryRegion>)> {
+    if input.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        return read_uncompressed(MultiGzDecoder::new(input));
+    }
+    read_uncompressed(input)
+}
+
+/// Opens an uncompressed dump to be read when needed, None for compressed ones.
+pub(crate) fn open(path: &Path) -> io::Result<Option<DumpFile>> {
+    let mut input = BufReader::new(File::open(path)?);
+    if input.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        return Ok(None);
+    }
+    let (pid, ranges) = read_table(&mut input)?;
+    let file = input.into_inner();
+    let len = file.metadata()?.len();
+    if ranges.iter().any(|range| range.offset.checked_add(range.size).is_none_or(|end| end > len)) {
+        return Err(invalid("memory dump is truncated"));
+    }
+    Ok(Some(DumpFile::new(pid, file, ranges)))
+}
+
+/// The pid and the ranges of a dump, with the offsets of their data.
+fn read_table<R: Read>(input: &mut R) -> io::Result<(u32, Vec<DumpRange>)> {
+    let mut magic = [0u8; 8];
+    input.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a pyevereader memory dump"));
+    }
+    let mut word = [0u8; 4];
+    let mut read_u32 = |input: &mut R| input.read_exact(&mut word).map(|_| u32::from_le_bytes(word));
+    let version = read_u32(input)?;
+    if !(1..=VERSION).contains(&version) {
+        return Err(invalid("unsupported memory dump version"));
+    }
+    let pid = read_u32(input)?;
+    let mut quad = [0u8; 8];
+    let mut read_u64 = |input: &mut R| input.read_exact(&mut quad).map(|_| u64::from_le_bytes(quad));
+    let count = read_u64(input)?;
+    // nothing is allocated ahead from the header, a damaged one ends in an unexpected EOF
+    let mut ranges = vec![];
+    for _ in 0..count {
+        let start = read_u64(input)?;
+        let size = read_u64(input)?;
+        let protection = if version >= 2 { read_u32(input)? } else { 0 };
+        ranges.push(DumpRange {
+            start,
+            size,
+            offset: 0,
+            protection,
+            kind: RegionKind::Unknown,
+        });
+    }
+    let entry_size = if version >= 2 { 20 } else { 16 };
+    let mut offset = (MAGIC.len() + 16) as u64 + count * entry_size;
+    for range in &mut ranges {
+        range.offset = offset;
+        offset = offset.checked_add(range.size).ok_or_else(|| invalid("memory dump is truncated"))?;
+    }
+    Ok((pid, ranges))
+}
+
+fn read_uncompressed<R: Read>(mut input: R) -> io::Result<(u32, Vec<MemoryRegion>)> {
+    let (pid, ranges) = read_table(&mut input)?;
+    let mut regions = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        let mut data = vec![];
+        input.by_ref().take(range.size).read_to_end(&mut data)?;
+        if data.len() as u64 != range.size {
+            return Err(invalid("memory dump is truncated"));
+        }
+        regions.push(MemoryRegion {
+            protection: range.protection,
+            ..MemoryRegion::new(range.start, range.size as usize, ProcessHandle::File, Some(data))?
+        });
+    }
+    Ok((pid, regions))
+}
@@ -1,12 +1,19 @@
-use crate::eve_process::process::{MemoryRegion, Process};
+use crate::eve_process::annotations::annotation;
+use crate::eve_process::process::{MemoryRegion, Process, RegionKind, ScanOptions};
 use crate::eve_process::py_struct::*;
+use crate::eve_process::scan::{CancellationToken, ProgressCallback};
+use crate::eve_process::ui_tree::UiTree;
+use encoding_rs::GBK;
 use lazy_static::lazy_static;
-use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::io;
-use std::rc::{Rc, Weak};
-use tracing::debug;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+use tracing::{debug, warn};
 
 lazy_static! {
     static ref py_builtin_types: Vec<&'static str> = vec!["dict", "UIRoot"];
@@ -18,7 +25,7 @@ pub enum Index {
     Index(usize),
 }
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct PyObjectNode {
     pub base_addr: u64,
     pub region: MemoryRegion,
@@ -27,98 +34,246 @@ pub struct PyObjectNode {
     pub child: HashMap<Index, Weak<PyObjectNode>>,
 }
 
+impl fmt::Debug for PyObjectNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("PyObjectNode");
+        debug.field("base_addr", &format_args!("0x{:X}", self.base_addr));
+        if let Some(label) = annotation(self.base_addr) {
+            debug.field("annotation", &label);
+        }
+        debug
+            .field("region", &self.region)
+            .field("ob_type", &self.ob_type)
+            .field("tp_name", &self.tp_name)
+            .field("child", &self.child)
+            .finish()
+    }
+}
+
+/// How the bytes of `str` objects are decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrEncoding {
+    /// UTF-8 where the bytes are valid UTF-8 and GBK otherwise, the Serenity client has both.
+    #[default]
+    Auto,
+    Utf8,
+    Gbk,
+}
+
+impl StrEncoding {
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            StrEncoding::Auto => match std::str::from_utf8(bytes) {
+                Ok(text) => text.to_string(),
+                Err(_) => GBK.decode_without_bom_handling(bytes).0.into_owned(),
+            },
+            StrEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            StrEncoding::Gbk => GBK.decode_without_bom_handling(bytes).0.into_owned(),
+        }
+    }
+}
+
+impl FromStr for StrEncoding {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(StrEncoding::Auto),
+            "utf8" | "utf-8" => Ok(StrEncoding::Utf8),
+            "gbk" | "gb2312" | "cp936" => Ok(StrEncoding::Gbk),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown encoding {:?}", s))),
+        }
+    }
+}
+
+/// Interpreter version of the client, which decides the object layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PyVersion {
+    #[default]
+    Py2,
+    /// 3.6 to 3.10.
+    Py3,
+}
+
+impl PyVersion {
+    /// Tells the versions apart by the size of dict objects, which shrank with the compact dicts
+    /// of 3.6, in 64 and 32-bit targets.
+    pub fn from_dict_basicsize(basicsize: isize) -> Option<Self> {
+        match basicsize {
+            248 | 124 => Some(PyVersion::Py2),
+            48 | 32 => Some(PyVersion::Py3),
+            _ => None,
+        }
+    }
+
+    /// The version with the layouts of Python `major.minor`, None for unsupported ones.
+    pub fn from_release(major: u8, minor: u8) -> Option<Self> {
+        match (major, minor) {
+            (2, 7) => Some(PyVersion::Py2),
+            (3, 6..=10) => Some(PyVersion::Py3),
+            _ => None,
+        }
+    }
+
+    /// Parses `sys.version` strings as returned by `Py_GetVersion`, like
+    /// `2.7.18 (default, Apr 20 2020, 19:27:10) [MSC v.1500 64 bit (AMD64)]`.
+    pub fn parse_version_string(version: &str) -> Option<(u8, u8)> {
+        let (release, build) = version.split_once(" (")?;
+        if !build.contains(") [") {
+            return None;
+        }
+        let mut numbers = release.split('.');
+        let major = numbers.next()?.parse().ok()?;
+        let minor = numbers.next()?.parse().ok()?;
+        Some((major, minor))
+    }
+
+    pub fn layout(self) -> PyLayout {
+        match self {
+            PyVersion::Py2 => PyLayout::PY2,
+            PyVersion::Py3 => PyLayout::PY3,
+        }
+    }
+}
+
+/// Reads `size` bytes at an address for the scan filters, see [`EVEProcess::scan_reader`].
+type ScanReader = fn(&Process, u64, usize) -> io::Result<MemoryRegion>;
+
 #[derive(Debug)]
 pub struct EVEProcess {
     pub process: Process,
-    pub objects: HashMap<u64, Rc<PyObjectNode>>,
+    pub objects: HashMap<u64, Arc<PyObjectNode>>,
     pub py_type: Weak<PyObjectNode>,
-    pub ui_root: Weak<PyObjectNode>
-}
-
-macro_rules! par_map_regions {
-    ($T:ty, $process:expr, $pyobj_filter:expr) => {
-        $process
-            .regions
-            .par_iter()
-            .map_with({ &$process }, |s, region| -> Vec<u64> {
-                (0..region.size as u64)
-                    .step_by(8)
-                    .filter_map({
-                        |offset| -> Option<u64> {
-                            let base_addr = region.start + offset;
-                            let data = region.view_bytes_as::<$T>(offset as usize, Some(8)).unwrap();
-                            $pyobj_filter(*s, base_addr, data)
-                        }
-                    })
-                    .collect()
-            })
-            .reduce(|| vec![], |a, b| a.into_iter().chain(b).collect::<Vec<_>>())
-            .into_iter()
-            .collect()
-    };
-
-    ($T:ty, $default:expr, $process:expr, $pyobj_filter:expr) => {
-        $process
-            .regions
-            .par_iter()
-            .map_with({ &$process }, |s, region| {
-                (0..region.size as u64)
-                    .step_by(8)
-                    .filter_map({
-                        |offset| -> Option<u64> {
-                            let base_addr = region.start + offset;
-                            let data = region.view_bytes_as::<$T>(offset as usize, 8).unwrap();
-                            $pyobj_filter(*s, base_addr, data)
-                        }
-                    })
-                    .collect()
-            })
-            .reduce(|| $default, |a, b| a.into_iter().chain(b).collect())
-            .into_iter()
-            .collect();
-    };
+    pub ui_root: Weak<PyObjectNode>,
+    pub(crate) tp_names: Mutex<HashMap<u64, String>>,
+    pub str_encoding: StrEncoding,
+    /// Detected by [`EVEProcess::init`].
+    pub py_version: PyVersion,
+    /// The layouts of `py_version`, set along with it. Can be replaced for interpreters built
+    /// with other layouts.
+    pub layout: PyLayout,
+    /// Checked by the scans of `init`, `search_type` and the like, which end early with what
+    /// they found so far once it is cancelled. `init` finds nothing then.
+    pub cancel: CancellationToken,
+    /// Told how far the scans of `init`, `search_type` and the like got.
+    pub progress: Option<ProgressCallback>,
+    /// Runs the scans instead of the global rayon pool. Opening a client already reads its
+    /// memory, run that in the pool with [`ThreadPool::install`] as well.
+    pub thread_pool: Option<Arc<ThreadPool>>,
+    /// Bytes the scans may hold at once. With a budget they read the regions from the source
+    /// in chunks through reused buffers instead of scanning the synced ones, see
+    /// [`EVEProcess::open_streaming`]. Processes without a source ignore it.
+    pub memory_budget: Option<usize>,
 }
 
 #[profiling::all_functions]
 impl EVEProcess {
+    /// Running EVE clients, without reading their memory.
+    pub fn list_clients() -> io::Result<Vec<Process>> {
+        Process::list(None, Some("*exefile*"), Some("*星战前夜*"))
+    }
+
+    pub(crate) fn from_process(proc: Process, options: &ScanOptions) -> EVEProcess {
+        Self::wrap(proc.enum_memory_regions_with(options).sync_memory_regions())
+    }
+
+    /// An `EVEProcess` reading only `regions` instead of a running client.
+    pub fn from_regions(regions: Vec<MemoryRegion>) -> EVEProcess {
+        Self::wrap(Process::from_regions(regions))
+    }
+
+    /// An `EVEProcess` reading a dump written by [`Process::write_dump`] instead of a running client.
+    pub fn open_dump<P: AsRef<Path>>(path: P) -> io::Result<EVEProcess> {
+        Ok(Self::wrap(Process::open_dump(path)?))
+    }
+
+    /// An `EVEProcess` reading a Windows minidump of a client, with the same memory as a live one.
+    pub fn open_minidump<P: AsRef<Path>>(path: P) -> io::Result<EVEProcess> {
+        Ok(Self::wrap(Process::from_minidump_with(path, &ScanOptions::default())?))
+    }
+
+    fn wrap(proc: Process) -> EVEProcess {
+        let layout = PyLayout {
+            pointer: proc.pointer_width,
+            ..Default::default()
+        };
+        EVEProcess {
+            process: proc,
+            objects: Default::default(),
+            py_type: Default::default(),
+            ui_root: Default::default(),
+            tp_names: Default::default(),
+            str_encoding: Default::default(),
+            py_version: Default::default(),
+            layout,
+            cancel: Default::default(),
+            progress: None,
+            thread_pool: None,
+            memory_budget: None,
+        }
+    }
+
+    /// Scans with a pool of its own of `threads` threads, see [`EVEProcess::thread_pool`].
+    pub fn use_threads(&mut self, threads: usize) -> io::Result<()> {
+        let pool = ThreadPoolBuilder::new().num_threads(threads).build().map_err(io::Error::other)?;
+        self.thread_pool = Some(Arc::new(pool));
+        Ok(())
+    }
+
     pub fn list() -> io::Result<Vec<EVEProcess>> {
-        let p: Vec<_> = Process::list(None, Some("*exefile*"), Some("*星战前夜*"))?
+        let p: Vec<_> = Self::list_clients()?
             .into_iter()
-            .map(|proc| -> EVEProcess {
-                let proc = proc.enum_memory_regions();
-                let proc = proc.sync_memory_regions();
-                EVEProcess {
-                    process: proc,
-                    objects: Default::default(),
-                    py_type: Default::default(),
-                    ui_root: Default::default(),
-                }
+            .map(|proc| {
+                let options = ScanOptions::for_process(&proc);
+                Self::from_process(proc, &options)
             })
             .collect();
         Ok(p)
     }
+
+    /// Opens the EVE client with the given pid, with the [`ScanOptions`] suited to it.
+    pub fn open(pid: u32) -> io::Result<EVEProcess> {
+        let proc = Process::list(Some(pid), Some("*exefile*"), Some("*星战前夜*"))?.remove(0);
+        let options = ScanOptions::for_process(&proc);
+        Ok(Self::from_process(proc, &options))
+    }
+
+    /// Opens the EVE client with the given pid, reading the memory `options` selects.
+    pub fn open_with(pid: u32, options: &ScanOptions) -> io::Result<EVEProcess> {
+        let proc = Process::list(Some(pid), Some("*exefile*"), Some("*星战前夜*"))?.remove(0);
+        Ok(Self::from_process(proc, options))
+    }
+
+    /// Opens the EVE client with the given pid without copying its memory, the scans read it
+    /// holding at most `memory_budget` bytes at once. Nothing is synced, reads go to the client.
+    pub fn open_streaming(pid: u32, memory_budget: usize) -> io::Result<EVEProcess> {
+        let mut proc = Process::list(Some(pid), Some("*exefile*"), Some("*星战前夜*"))?.remove(0);
+        proc.scan_options = ScanOptions::for_process(&proc);
+        let mut process = Self::wrap(proc);
+        process.memory_budget = Some(memory_budget);
+        Ok(process)
+    }
+
     pub fn init(&mut self) -> Option<u64> {
+        let read = self.scan_reader();
         // find python type type candidates,
         // where ob_type should be it's addr and tp_name should be "type"
-        let type_candidates: HashSet<_> = par_map_regions!(
-            CPyTypeObject,
-            self.process,
-            ({
-                |proc: &Process, base_addr, data: &CPyTypeObject| -> Option<u64> {
-                    if data.ob_base.ob_type == base_addr {
-                        let tp_name_p = data.tp_name;
-                        if let Ok(ref tp_name_bytes) = proc.read_cache(tp_name_p, 4).borrow() {
-                            if let Ok(tp_name) = tp_name_bytes.view_bytes(0, 4) {
-                                if tp_name.eq(b"type") {
-                                    return Some(base_addr);
-                                }
+        let type_candidates: HashSet<_> = self
+            .collect_objects(|proc: &Process, base_addr, data: &CPyTypeObject| -> Option<u64> {
+                if data.ob_base.ob_type == base_addr {
+                    let tp_name_p = data.tp_name;
+                    if let Ok(ref tp_name_bytes) = read(proc, tp_name_p, 4).borrow() {
+                        if let Ok(tp_name) = tp_name_bytes.view_bytes(0, 4) {
+                            if tp_name.eq(b"type") {
+                                return Some(base_addr);
                             }
                         }
                     }
-                    None
                 }
+                None
             })
-        );
+            .into_iter()
+            .collect();
         // find addrs of some python builtin types with type type candidates,
         // can be used to filter out false type candidates
         let mut verified_type_candidates: HashMap<u64, HashMap<&str, u64>> = HashMap::default();
@@ -127,6 +282,11 @@ impl EVEProcess {
         'candidate: for &tp_candidate in type_candidates.iter() {
             for &tp_name in py_builtin_types.iter() {
                 let found = self.search_type(tp_name, Some(tp_candidate));
+                // partial results would verify the wrong candidates
+                if self.cancel.is_cancelled() {
+                    debug!("init cancelled");
+                    return None;
+                }
                 if found.len() == 0 {
                     debug!(
                         "{} not found for type candidate: {}, skipped.",
@@ -156,51 +316,60 @@ impl EVEProcess {
                     == py_builtin_types.len()
             {
                 debug!("Found verified type candidate: {}", tp_candidate);
+                // read before touching the objects, a client exiting now leaves them as they were
+                let type_data = self.read_type_object(tp_candidate).ok()?;
+                let mut tp_data = verified_type_candidates[&tp_candidate]
+                    .values()
+                    .map(|&tp_addr| Ok((tp_addr, self.read_type_object(tp_addr)?)))
+                    .collect::<io::Result<HashMap<_, _>>>()
+                    .ok()?;
                 self.objects = Default::default();
-                let py_type = Rc::new(PyObjectNode {
+                let py_type = Arc::new(PyObjectNode {
                     base_addr: tp_candidate,
                     region: MemoryRegion {
                         start: tp_candidate,
                         size: size_of::<CPyTypeObject>(),
-                        data: self
-                            .process
-                            .read_cache(tp_candidate, size_of::<CPyTypeObject>())
-                            .unwrap()
-                            .data,
-                        handle: self.process.handle,
+                        data: type_data.into(),
+                        handle: self.process.handle.clone(),
+                        protection: 0,
+                        kind: RegionKind::Unknown,
                     },
                     ob_type: Default::default(),
                     tp_name: "type".to_string(),
                     child: Default::default(),
                 });
                 self.objects.insert(tp_candidate, py_type.clone());
-                self.py_type = Rc::downgrade(&py_type);
+                self.py_type = Arc::downgrade(&py_type);
                 for (&tp_name, &tp_addr) in
                     verified_type_candidates.get(&tp_candidate).unwrap().iter()
                 {
                     
-                    let tp_obj = Rc::new(PyObjectNode {
+                    let tp_obj = Arc::new(PyObjectNode {
                         base_addr: tp_addr,
                         region: MemoryRegion {
                             start: tp_addr,
                             size: size_of::<CPyTypeObject>(),
-                            data: self
-                                .process
-                                .read_cache(tp_addr, size_of::<CPyTypeObject>())
-                                .unwrap()
-                                .data,
-                            handle: self.process.handle,
+                            data: tp_data.remove(&tp_addr).unwrap_or_default().into(),
+                            handle: self.process.handle.clone(),
+                            protection: 0,
+                            kind: RegionKind::Unknown,
                         },
-                        ob_type: Rc::downgrade(&py_type),
+                        ob_type: Arc::downgrade(&py_type),
                         tp_name: tp_name.to_string(),
                         child: Default::default(),
                     });
                     self.objects.insert(tp_addr, tp_obj.clone());
                     if tp_name.eq("UIRoot") {
-                        self.ui_root = Rc::downgrade(&tp_obj);
+                        self.ui_root = Arc::downgrade(&tp_obj);
                     }
                 }
                 verified_type_addr = tp_candidate;
+                let dict_type = verified_type_candidates[&tp_candidate]["dict"];
+                self.py_version = self.detect_py_version(dict_type);
+                self.layout = PyLayout {
+                    pointer: self.layout.pointer,
+                    ..self.py_version.layout()
+                };
                 break;
             }
         }
@@ -211,76 +380,150 @@ impl EVEProcess {
         }
     }
 
+    /// The type object at `addr`, widened for 32-bit targets.
+    fn read_type_object(&self, addr: u64) -> io::Result<Vec<u8>> {
+        let width = self.process.pointer_width;
+        let raw = self.scan_reader()(&self.process, addr, width.size_of::<CPyTypeObject>())?;
+        Ok(width.widen::<CPyTypeObject>(raw.bytes()))
+    }
+
+    /// Goes by the python module and the version string in its data when the module list is
+    /// known, by the size of dict objects otherwise.
+    fn detect_py_version(&self, dict_type: u64) -> PyVersion {
+        if let Some((major, minor)) = self.python_release() {
+            match PyVersion::from_release(major, minor) {
+                Some(version) => {
+                    debug!("detected python {}.{}", major, minor);
+                    return version;
+                }
+                None => warn!("unsupported python {}.{}, probing the dict layout", major, minor),
+            }
+        }
+        let width = self.process.pointer_width;
+        let basicsize = self.scan_reader()(&self.process, dict_type, width.size_of::<CPyTypeObject>())
+            .and_then(|region| {
+                width.read::<CPyTypeObject>(region.bytes()).map(|tp| tp.tp_basicsize).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "type object is truncated")
+                })
+            });
+        match basicsize.as_ref().map(|&basicsize| PyVersion::from_dict_basicsize(basicsize)) {
+            Ok(Some(version)) => {
+                debug!("detected python version {:?} by its dict layout", version);
+                version
+            }
+            _ => {
+                warn!("unknown dict layout ({:?}), assuming python 2", basicsize);
+                PyVersion::Py2
+            }
+        }
+    }
+
+    /// Release of the loaded python module, from the `Py_GetVersion` buffer in its data or else
+    /// its name.
+    fn python_release(&self) -> Option<(u8, u8)> {
+        let modules = self.process.modules().ok()?;
+        let module = modules.iter().find(|module| module.python_release().is_some())?;
+        let end = module.base + module.size as u64;
+        let from_data = self
+            .process
+            .regions
+            .iter()
+            .filter(|region| region.start >= module.base && region.start < end)
+            .filter_map(|region| self.process.region_data(region).ok())
+            .flat_map(|data| data.split(|&byte| byte == 0))
+            .filter(|string| string.len() < 250 && string.first().is_some_and(u8::is_ascii_digit))
+            .find_map(|string| PyVersion::parse_version_string(std::str::from_utf8(string).ok()?));
+        from_data.or_else(|| module.python_release())
+    }
+
     pub fn search_type(&self, tp_name: &str, tp_addr: Option<u64>) -> Vec<u64> {
         
         let tp_candidate = tp_addr.unwrap_or_else(|| {match self.py_type.upgrade() {
             Some(type_obj) => { type_obj.base_addr }
             _ => {panic!("Invalid type addr.")}
         }});
-        par_map_regions!(
-            CPyTypeObject,
-            self.process,
-            ({
-                |proc: &Process, base_addr, data: &CPyTypeObject| -> Option<u64> {
-                    if data.ob_base.ob_type == tp_candidate {
-                        let tp_name_p = data.tp_name;
-                        if let Ok(ref tp_name_bytes) =
-                            proc.read_cache(tp_name_p, tp_name.len()).borrow()
-                        {
-                            if let Ok(tp_name_read) = tp_name_bytes.view_bytes(0, tp_name.len()) {
-                                if tp_name.as_bytes().eq(tp_name_read) {
-                                    return Some(base_addr);
-                                }
-                            }
+        let read = self.scan_reader();
+        self.collect_objects_of_type(tp_candidate, |proc: &Process, base_addr, data: &CPyTypeObject| -> Option<u64> {
+            if data.ob_base.ob_type == tp_candidate {
+                let tp_name_p = data.tp_name;
+                if let Ok(ref tp_name_bytes) =
+                    read(proc, tp_name_p, tp_name.len()).borrow()
+                {
+                    if let Ok(tp_name_read) = tp_name_bytes.view_bytes(0, tp_name.len()) {
+                        if tp_name.as_bytes().eq(tp_name_read) {
+                            return Some(base_addr);
                         }
                     }
-                    None
                 }
-            })
-        )
+            }
+            None
+        })
     }
 
     pub fn search_ui_root(&self, tp_addr: Option<u64>) -> Vec<u64> {
-        let tp_addr = tp_addr.unwrap_or_else(|| {
+        let tp_addr = self.ui_root_type(tp_addr);
+        self.collect_objects_of_type(tp_addr, Self::ui_root_filter(tp_addr, self.scan_reader()))
+    }
+
+    /// Passes `UIRoot` instances to `sink` as they are found, until it returns `false`, see
+    /// [`EVEProcess::scan_objects`].
+    pub fn scan_ui_roots<S: Fn(u64) -> bool + Sync>(&self, tp_addr: Option<u64>, sink: S) -> bool {
+        let tp_addr = self.ui_root_type(tp_addr);
+        self.scan_objects_of_type(tp_addr, Self::ui_root_filter(tp_addr, self.scan_reader()), sink)
+    }
+
+    /// `tp_addr`, or the `UIRoot` type found by `init`.
+    fn ui_root_type(&self, tp_addr: Option<u64>) -> u64 {
+        tp_addr.unwrap_or_else(|| {
             match self.ui_root.upgrade() {
                 Some(ui_root) => { ui_root.base_addr }
                 _ => {panic!("Invalid UIRoot addr.")}
             }
-        });
-        par_map_regions!(
-            CPyCustomObject,
-            self.process,
-            ({
-                |proc: &Process, base_addr, data: &CPyCustomObject| -> Option<u64> {
-                    if data.ob_base.ob_type == tp_addr {
-                        let attr_p = data.attributes;
-                        if let Ok(ref tp_name_bytes) =
-                            proc.read_cache(attr_p, size_of::<CPyDictObject>()).borrow()
-                        {
-                            if let Ok(attr_dict) = tp_name_bytes.view_bytes_as::<CPyDictObject>(0, None) {
-                                if let Ok(attr_dict_data) = proc.read_cache(attr_dict.ob_base.ob_type, size_of::<CPyTypeObject>()).borrow() {
-                                    if let Ok(attr_dict_type) = attr_dict_data.view_bytes_as::<CPyTypeObject>(0, None) {
-                                        if let Ok(attr_dict_type_name) = proc.read_cache(attr_dict_type.tp_name, 4).borrow() {
-                                            if attr_dict_type_name.view_bytes(0, 4).unwrap_or("".as_bytes()).eq("dict".as_bytes()) {
-                                                return Some(base_addr);
-                                            }
-                                        }
+        })
+    }
+
+    /// How the scans read what the candidates point to: from the synced regions, or from the
+    /// client under a memory budget, where syncing the regions would defeat the budget.
+    fn scan_reader(&self) -> ScanReader {
+        match self.memory_budget {
+            Some(_) => Process::read_memory,
+            None => Process::read_cache,
+        }
+    }
+
+    /// Matches instances of the `UIRoot` type at `tp_addr` whose attributes are a dict.
+    fn ui_root_filter(tp_addr: u64, read: ScanReader) -> impl Fn(&Process, u64, &CPyCustomObject) -> Option<u64> {
+        move |proc: &Process, base_addr, data: &CPyCustomObject| -> Option<u64> {
+            let width = proc.pointer_width;
+            if data.ob_base.ob_type == tp_addr {
+                let attr_p = data.attributes;
+                if let Ok(ref tp_name_bytes) =
+                    read(proc, attr_p, width.size_of::<CPyDictObject>()).borrow()
+                {
+                    if let Some(attr_dict) = width.read::<CPyDictObject>(tp_name_bytes.bytes()) {
+                        if let Ok(attr_dict_data) = read(proc, attr_dict.ob_base.ob_type, width.size_of::<CPyTypeObject>()).borrow() {
+                            if let Some(attr_dict_type) = width.read::<CPyTypeObject>(attr_dict_data.bytes()) {
+                                if let Ok(attr_dict_type_name) = read(proc, attr_dict_type.tp_name, 4).borrow() {
+                                    if attr_dict_type_name.view_bytes(0, 4).unwrap_or("".as_bytes()).eq("dict".as_bytes()) {
+                                        return Some(base_addr);
                                     }
                                 }
                             }
                         }
                     }
-                    None
                 }
-            })
-        )
+            }
+            None
+        }
     }
     
-    pub fn parse_ui_tree(&mut self, ui_root_addr: u64) -> Option<PyObjectNode> {
-        let region = self.process.read_cache(ui_root_addr, size_of::<CPyCustomObject>()).ok()?;
-        let py_obj_view = region.view_bytes_as::<CPyCustomObject>(0, None).ok()?;
-
-        
-        todo!()
+    pub fn parse_ui_tree(&mut self, ui_root_addr: u64) -> Option<UiTree> {
+        match self.parse_ui_node(ui_root_addr, (0, 0), 0, &mut HashSet::new()) {
+            Ok(root) => Some(UiTree { root }),
+            Err(e) => {
+                debug!("failed to parse UI tree at 0x{:X}: {}", ui_root_addr, e);
+                None
+            }
+        }
     }
 }
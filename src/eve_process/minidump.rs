@@ -0,0 +1,278 @@
+//! Reads the memory of Windows minidumps, as written by Task Manager, procdump or
+//! `MiniDumpWriteDump`. Only the streams holding memory and the process id are looked at.
+
+use crate::eve_process::process::RegionKind;
+use crate::eve_process::source::{DumpFile, DumpRange};
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const SIGNATURE: u32 = 0x504D_444D; // "MDMP"
+const MEMORY_LIST_STREAM: u32 = 5;
+const MEMORY_64_LIST_STREAM: u32 = 9;
+const MISC_INFO_STREAM: u32 = 15;
+const MEMORY_INFO_LIST_STREAM: u32 = 16;
+const MISC1_PROCESS_ID: u32 = 0x1;
+const MEM_COMMIT: u32 = 0x1000;
+
+struct Reader {
+    input: BufReader<File>,
+    len: u64,
+}
+
+impl Reader {
+    fn seek(&mut self, offset: u64) -> io::Result<()> {
+        self.input.seek(SeekFrom::Start(offset)).map(|_| ())
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let mut buf = [0; 4];
+        self.input.read_exact(&mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let mut buf = [0; 8];
+        self.input.read_exact(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Guards the allocation of `count` entries of `entry_size` bytes against damaged headers.
+    fn check_count(&self, count: u64, entry_size: u64) -> io::Result<()> {
+        if count.saturating_mul(entry_size) > self.len {
+            return Err(invalid("minidump is truncated"));
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Committed ranges, their protection and `MEM_*` type, from the memory info list.
+fn read_protections(reader: &mut Reader, rva: u64) -> io::Result<Vec<(u64, u64, u32, u32)>> {
+    reader.seek(rva)?;
+    let header_size = reader.u32()? as u64;
+    let entry_size = reader.u32()? as u64;
+    let count = reader.u64()?;
+    reader.check_count(count, entry_size)?;
+    (0..count)
+        .map(|i| {
+            reader.seek(rva + header_size + i * entry_size)?;
+            let base = reader.u64()?;
+            let _allocation_base = reader.u64()?;
+            let _allocation_protect = reader.u32()?;
+            let _alignment = reader.u32()?;
+            let size = reader.u64()?;
+            let state = reader.u32()?;
+            let protect = reader.u32()?;
+            let mem_type = reader.u32()?;
+            Ok((base, size, if state == MEM_COMMIT { protect } else { 0 }, mem_type))
+        })
+        .collect()
+}
+
+/// Opens the minidump at `path`, to be read when needed.
+pub(crate) fn open(path: &Path) -> io::Result<DumpFile> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut reader = Reader {
+        input: BufReader::new(file),
+        len,
+    };
+    if reader.u32()? != SIGNATURE {
+        return Err(invalid("not a minidump"));
+    }
+    let _version = reader.u32()?;
+    let stream_count = reader.u32()? as u64;
+    let directory_rva = reader.u32()? as u64;
+    reader.check_count(stream_count, 12)?;
+    reader.seek(directory_rva)?;
+    let streams = (0..stream_count)
+        .map(|_| Ok((reader.u32()?, reader.u32()?, reader.u32()? as u64)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let stream = |kind: u32| streams.iter().find(|(stream_type, _, _)| *stream_type == kind).map(|(_, _, rva)| *rva);
+
+    let mut pid = 0;
+    if let Some(rva) = stream(MISC_INFO_STREAM) {
+        reader.seek(rva)?;
+        let _size = reader.u32()?;
+        let flags = reader.u32()?;
+        let process_id = reader.u32()?;
+        if flags & MISC1_PROCESS_ID != 0 {
+            pid = process_id;
+        }
+    }
+
+    // full dumps list their memory in one block after the descriptors, smaller ones each range on its own
+    let mut ranges = vec![];
+    if let Some(rva) = stream(MEMORY_64_LIST_STREAM) {
+        reader.seek(rva)?;
+        let count = reader.u64()?;
+        let mut data_rva = reader.u64()?;
+        reader.check_count(count, 16)?;
+        for _ in 0..count {
+            let start = reader.u64()?;
+            let size = reader.u64()?;
+            ranges.push((start, size, data_rva));
+            data_rva = data_rva.checked_add(size).ok_or_else(|| invalid("minidump is truncated"))?;
+        }
+    } else if let Some(rva) = stream(MEMORY_LIST_STREAM) {
+        reader.seek(rva)?;
+        let count = reader.u32()? as u64;
+        reader.check_count(count, 16)?;
+        for _ in 0..count {
+            let start = reader.u64()?;
+            let size = reader.u32()? as u64;
+            let data_rva = reader.u32()? as u64;
+            ranges.push((start, size, data_rva));
+        }
+    } else {
+        return Err(invalid("minidump holds no memory"));
+    }
+    if ranges.iter().any(|(_, size, data_rva)| data_rva.checked_add(*size).is_none_or(|end| end > len)) {
+        return Err(invalid("minidump is truncated"));
+    }
+
+    let mut protections = match stream(MEMORY_INFO_LIST_STREAM) {
+        Some(rva) => read_protections(&mut reader, rva)?,
+        None => vec![],
+    };
+    protections.sort_by_key(|(base, _, _, _)| *base);
+    let ranges = ranges
+        .into_iter()
+        .map(|(start, size, offset)| {
+            let index = protections.partition_point(|(base, _, _, _)| *base <= start);
+            let (protection, mem_type) = index
+                .checked_sub(1)
+                .map(|index| protections[index])
+                .filter(|(base, region_size, _, _)| start < base + region_size)
+                .map_or((0, 0), |(_, _, protect, mem_type)| (protect, mem_type));
+            DumpRange {
+                start,
+                size,
+                offset,
+                protection,
+                kind: RegionKind::from_mem_type(mem_type),
+            }
+        })
+        .collect();
+    Ok(DumpFile::new(pid, reader.input.into_inner(), ranges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eve_process::process::ScanOptions;
+    use crate::eve_process::source::{MemorySource, PartialRead};
+    use std::path::PathBuf;
+
+    const PAGE_EXECUTE_READ: u32 = 0x20;
+
+    /// A dump of process 4242 with a read-write range and an executable one, each listed with
+    /// its protection.
+    fn minidump() -> Vec<u8> {
+        let mut dump = vec![];
+        let u32 = |dump: &mut Vec<u8>, value: u32| dump.extend(value.to_le_bytes());
+        let u64 = |dump: &mut Vec<u8>, value: u64| dump.extend(value.to_le_bytes());
+        // header, then the directory of three streams right after it
+        for value in [SIGNATURE, 0xA793, 3, 32, 0, 0] {
+            u32(&mut dump, value);
+        }
+        u64(&mut dump, 0);
+        let streams = [
+            (MISC_INFO_STREAM, 12, 68),
+            (MEMORY_64_LIST_STREAM, 48, 80),
+            (MEMORY_INFO_LIST_STREAM, 112, 128),
+        ];
+        for value in streams.into_iter().flat_map(|(stream, size, rva)| [stream, size, rva]) {
+            u32(&mut dump, value);
+        }
+        for value in [12, MISC1_PROCESS_ID, 4242] {
+            u32(&mut dump, value);
+        }
+        // the data of all ranges follows the memory info list at 240
+        for value in [2, 240, 0x1000_0000, 16, 0x2000_0000, 8] {
+            u64(&mut dump, value);
+        }
+        for value in [16, 48] {
+            u32(&mut dump, value);
+        }
+        u64(&mut dump, 2);
+        let infos = [
+            (0x1000_0000, 0x1000, ScanOptions::PAGE_READWRITE, 0x20000),
+            (0x2000_0000, 0x1000, PAGE_EXECUTE_READ, 0x1000000),
+        ];
+        for (base, size, protect, mem_type) in infos {
+            u64(&mut dump, base);
+            u64(&mut dump, base);
+            u64(&mut dump, protect as u64);
+            u64(&mut dump, size);
+            for value in [MEM_COMMIT, protect, mem_type, 0] {
+                u32(&mut dump, value);
+            }
+        }
+        assert_eq!(dump.len(), 240);
+        dump.extend(0..16u8);
+        dump.extend([0xEE; 8]);
+        dump
+    }
+
+    fn write(name: &str, data: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("pyevereader-{}-{}.dmp", name, std::process::id()));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_the_ranges_of_the_memory_64_list() {
+        let path = write("memory64", &minidump());
+        let dump = open(&path);
+        std::fs::remove_file(&path).unwrap();
+        let dump = dump.unwrap();
+        assert_eq!(dump.pid, 4242);
+
+        let regions = dump.regions(&ScanOptions::default());
+        assert_eq!(regions.len(), 1);
+        assert_eq!((regions[0].start, regions[0].size), (0x1000_0000, 16));
+        assert_eq!(regions[0].protection, ScanOptions::PAGE_READWRITE);
+        assert_eq!(regions[0].kind, RegionKind::Private);
+        let regions = dump.regions(&ScanOptions::all_readable());
+        assert_eq!(regions.len(), 2);
+        assert_eq!((regions[1].start, regions[1].size, regions[1].kind), (0x2000_0000, 8, RegionKind::Image));
+
+        let mut buf = [0; 4];
+        dump.read(0x1000_0004, &mut buf).unwrap();
+        assert_eq!(buf, [4, 5, 6, 7]);
+        dump.read(0x2000_0004, &mut buf).unwrap();
+        assert_eq!(buf, [0xEE; 4]);
+        let mut buf = [0; 8];
+        let error = dump.read(0x1000_000C, &mut buf).unwrap_err();
+        assert_eq!(PartialRead::of(&error).map(|partial| &partial.data[..]), Some(&[12, 13, 14, 15][..]));
+        assert!(dump.read(0x1800_0000, &mut buf).is_err());
+    }
+
+    #[test]
+    fn rejects_other_files_and_truncated_dumps() {
+        let dump = minidump();
+        let path = write("truncated", &dump[..dump.len() - 1]);
+        let truncated = open(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(truncated.unwrap_err().to_string(), "minidump is truncated");
+
+        // a range size that wraps the offsets of the ones after it
+        let mut wrapping = dump.clone();
+        wrapping[104..112].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+        let path = write("wrapping", &wrapping);
+        let wrapping = open(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(wrapping.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let path = write("other", b"PK\x03\x04 not a minidump");
+        let other = open(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(other.unwrap_err().to_string(), "not a minidump");
+    }
+}
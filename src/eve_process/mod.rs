@@ -1,5 +1,21 @@
+pub mod annotations;
+pub mod client_watcher;
+mod dump;
 pub mod process;
 pub mod module;
+pub mod pattern;
+mod minidump;
+mod platform;
 pub mod eve_process;
 pub mod py_struct;
-mod pyobject_parser;
+pub mod query_cache;
+pub mod scan;
+pub mod screen;
+pub mod session;
+pub mod snapshot_diff;
+pub mod source;
+pub mod type_stats;
+pub mod ui_diff;
+pub mod ui_tree;
+pub mod ui_watcher;
+mod pyobject_parser;
\ No newline at end of file
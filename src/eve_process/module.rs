@@ -1,7 +1,28 @@
-use winapi::shared::minwindef::HMODULE;
-
-
-#[derive(Debug)]
+/// A DLL or executable loaded into a process.
+#[derive(Debug, Clone)]
 pub struct Module {
+    pub name: String,
+    pub base: u64,
+    pub size: usize,
+}
+
+impl Module {
+    /// Wine's own modules, like `winex11.drv`, which are not there on Windows.
+    pub fn is_wine(&self) -> bool {
+        self.name.to_ascii_lowercase().starts_with("wine")
+    }
 
-}
\ No newline at end of file
+    /// Major and minor version of a Python runtime module, like `python27.dll`, `python310.dll` or
+    /// `libpython3.8.so.1.0`.
+    pub fn python_release(&self) -> Option<(u8, u8)> {
+        let name = self.name.to_ascii_lowercase();
+        let rest = name.strip_prefix("lib").unwrap_or(&name).strip_prefix("python")?;
+        let digits = rest.split(|c: char| !c.is_ascii_digit() && c != '.').next()?.trim_end_matches('.');
+        let (major, minor) = match digits.split_once('.') {
+            Some((major, minor)) => (major, minor.split('.').next()?),
+            None if digits.len() >= 2 => digits.split_at(1),
+            None => return None,
+        };
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+}
@@ -0,0 +1,131 @@
+use crate::eve_process::process::Process;
+use rayon::prelude::*;
+use std::io;
+use std::str::FromStr;
+
+/// A byte signature like `48 8B ?? ?? 89`, where `?` or `??` matches any byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    bytes: Vec<Option<u8>>,
+}
+
+impl FromStr for Pattern {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+        let bytes = s
+            .split_whitespace()
+            .map(|token| match token {
+                "?" | "??" => Ok(None),
+                _ if token.len() == 2 => {
+                    u8::from_str_radix(token, 16).map(Some).map_err(|_| invalid(format!("invalid byte {:?}", token)))
+                }
+                _ => Err(invalid(format!("invalid byte {:?}", token))),
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        if bytes.iter().all(Option::is_none) {
+            return Err(invalid(format!("pattern {:?} has no fixed byte", s)));
+        }
+        Ok(Pattern { bytes })
+    }
+}
+
+impl Pattern {
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn matches_at(&self, data: &[u8]) -> bool {
+        self.bytes.iter().zip(data).all(|(byte, &actual)| byte.is_none_or(|byte| byte == actual))
+    }
+
+    /// Offsets of all matches in `data`, overlapping ones included.
+    pub fn find_in(&self, data: &[u8]) -> Vec<usize> {
+        let Some(last) = data.len().checked_sub(self.len()) else {
+            return vec![];
+        };
+        // candidates are where the first fixed byte is
+        let (anchor, first) = self.bytes.iter().enumerate().find_map(|(i, byte)| Some((i, (*byte)?))).unwrap();
+        data[anchor..=last + anchor]
+            .iter()
+            .enumerate()
+            .filter(|&(_, &byte)| byte == first)
+            .map(|(offset, _)| offset)
+            .filter(|&offset| self.matches_at(&data[offset..]))
+            .collect()
+    }
+}
+
+impl Process {
+    /// Addresses where `pattern` matches the synced regions, sorted. Matches spanning two regions
+    /// are not found. Code is only scanned when the regions were enumerated with its protections,
+    /// like with [`ScanOptions::all_readable`](crate::eve_process::process::ScanOptions::all_readable).
+    pub fn scan_pattern(&self, pattern: &str) -> io::Result<Vec<u64>> {
+        let pattern = pattern.parse::<Pattern>()?;
+        let mut hits: Vec<u64> = self
+            .regions
+            .par_iter()
+            .flat_map_iter(|region| {
+                let data = self.region_data(region).map(|data| &data[..region.size]).unwrap_or_default();
+                pattern.find_in(data).into_iter().map(|offset| region.start + offset as u64)
+            })
+            .collect();
+        hits.sort_unstable();
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eve_process::process::ScanOptions;
+    use crate::eve_process::source::MockMemory;
+
+    #[test]
+    fn parses_wildcards() {
+        let pattern: Pattern = "48 8b ? ?? 89".parse().unwrap();
+        assert_eq!(pattern.bytes, [Some(0x48), Some(0x8B), None, None, Some(0x89)]);
+        assert_eq!(pattern.len(), 5);
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        for invalid in ["", "?? ??", "4", "488B", "4G", "???"] {
+            let error = invalid.parse::<Pattern>().unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn finds_overlapping_matches_and_matches_at_the_end() {
+        let pattern: Pattern = "AA ?? AA".parse().unwrap();
+        assert_eq!(pattern.find_in(&[0xAA, 0x00, 0xAA, 0x01, 0xAA, 0xAA]), [0, 2]);
+        assert_eq!(pattern.find_in(&[0x00, 0xAA, 0x00, 0xAA]), [1]);
+        assert_eq!(pattern.find_in(&[0xAA, 0x00]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn matches_leading_wildcards_from_the_start() {
+        let pattern: Pattern = "?? ?? 01".parse().unwrap();
+        assert_eq!(pattern.find_in(&[0x01, 0x01, 0x01, 0x01]), [0, 1]);
+    }
+
+    #[test]
+    fn finds_matches_across_sync_chunks_but_not_across_regions() {
+        // one region synced in several chunks, followed by another one right after it
+        let size = 0x100_0000 + 0x1000;
+        let mut first = vec![0; size];
+        first[0xFF_FFFE..0x100_0002].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        first[size - 2..].copy_from_slice(&[0x12, 0x34]);
+        let mut memory = MockMemory::new();
+        memory.insert(0x1000_0000, first, ScanOptions::PAGE_READWRITE);
+        memory.insert(0x1000_0000 + size as u64, vec![0x56, 0x78, 0, 0], ScanOptions::PAGE_READWRITE);
+        let process = Process::from_source(memory).enum_memory_regions();
+        assert_eq!(process.scan_pattern("12 34 ?? 78").unwrap(), [0x1000_0000 + 0xFF_FFFE]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Clients running under Wine or Proton, read through `/proc`. Handles are the process ids, and
+//! reading needs the same permissions as ptrace, so either the same user with
+//! `kernel.yama.ptrace_scope` at 0 or `CAP_SYS_PTRACE`.
+
+use crate::eve_process::module::Module;
+use crate::eve_process::process::{
+    MemoryRegion, OwnedHandle, Process, ProcessHandle, RawHandle, RegionKind, ScanOptions, PAGE_SIZE,
+};
+use crate::eve_process::py_struct::PointerWidth;
+use crate::eve_process::screen::ClientArea;
+use crate::eve_process::source::{LiveMemory, PartialRead};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::hint;
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Set in `/proc/<pid>/pagemap` entries of pages written since `/proc/<pid>/clear_refs` was last
+/// written with 4.
+const PM_SOFT_DIRTY: u64 = 1 << 55;
+/// Set in `/proc/<pid>/pagemap` entries of pages in RAM.
+const PM_PRESENT: u64 = 1 << 63;
+
+fn unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "windows of Wine clients are not supported on Linux")
+}
+
+/// A mapping of `/proc/<pid>/maps`.
+struct Mapping {
+    start: u64,
+    end: u64,
+    /// The `PAGE_*` protection matching the permissions, 0 for inaccessible mappings.
+    protection: u32,
+    path: String,
+}
+
+impl Mapping {
+    /// Files of executables and libraries, including the DLLs Wine maps, count as images.
+    fn kind(&self) -> RegionKind {
+        if !self.path.starts_with('/') {
+            return RegionKind::Private;
+        }
+        let name = Path::new(&self.path).file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+        if [".dll", ".exe", ".so"].iter().any(|ext| name.ends_with(ext)) || name.contains(".so.") {
+            RegionKind::Image
+        } else {
+            RegionKind::Mapped
+        }
+    }
+}
+
+fn read_maps(pid: u32) -> io::Result<Vec<Mapping>> {
+    let maps = fs::read_to_string(format!("/proc/{pid}/maps"))?;
+    Ok(maps.lines().filter_map(parse_mapping).collect())
+}
+
+/// A line of `/proc/<pid>/maps`: range, permissions, offset, device, inode and the path, which
+/// is padded and may hold spaces.
+fn parse_mapping(line: &str) -> Option<Mapping> {
+    let mut fields = line.splitn(6, char::is_whitespace);
+    let (start, end) = fields.next()?.split_once('-')?;
+    let perms = fields.next()?.as_bytes();
+    let path = fields.nth(3).unwrap_or_default().trim_start().to_string();
+    let protection = match (perms.first()?, perms.get(1)?, perms.get(2)?) {
+        (b'r', b'w', b'x') => ScanOptions::PAGE_EXECUTE_READWRITE,
+        (b'r', b'w', _) => ScanOptions::PAGE_READWRITE,
+        (b'r', _, b'x') => ScanOptions::PAGE_EXECUTE_READ,
+        (b'r', _, _) => ScanOptions::PAGE_READONLY,
+        _ => 0,
+    };
+    Some(Mapping {
+        start: u64::from_str_radix(start, 16).ok()?,
+        end: u64::from_str_radix(end, 16).ok()?,
+        protection,
+        path,
+    })
+}
+
+/// 32 bits for processes of 32-bit executables, like the `wine-preloader` of 32-bit prefixes.
+fn pointer_width(pid: u32) -> PointerWidth {
+    let mut ident = [0u8; 5];
+    match File::open(format!("/proc/{pid}/exe")).and_then(|mut exe| exe.read_exact(&mut ident)) {
+        Ok(()) if &ident[..4] == b"\x7FELF" && ident[4] == 1 => PointerWidth::Bits32,
+        _ => PointerWidth::Bits64,
+    }
+}
+
+/// Processes of the current user, with the Windows path for Wine processes. Their windows are
+/// not known, so the titles are left empty.
+pub(crate) fn list_processes() -> io::Result<Vec<Process>> {
+    let mut processes = vec![];
+    for entry in fs::read_dir("/proc")? {
+        let Some(pid) = entry?.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        // Wine keeps the Windows path of the executable as the first argument
+        let Ok(cmdline) = fs::read(format!("/proc/{pid}/cmdline")) else {
+            continue;
+        };
+        let path = match cmdline.split(|&byte| byte == 0).next().filter(|arg| !arg.is_empty()) {
+            Some(arg) => String::from_utf8_lossy(arg).into_owned(),
+            None => continue,
+        };
+        let handle = Arc::new(OwnedHandle::new(RawHandle(pid as usize)));
+        processes.push(Process {
+            pid,
+            path,
+            title: String::new(),
+            regions: vec![],
+            pointer_width: pointer_width(pid),
+            handle: ProcessHandle::Live(handle.clone()),
+            source: Some(Arc::new(LiveMemory(handle))),
+            region_cache: Default::default(),
+            scan_options: Default::default(),
+            window: 0,
+        });
+    }
+    Ok(processes)
+}
+
+/// The maps are read at once, so everything is queried as a single range.
+pub(crate) fn address_chunks() -> Vec<(u64, u64)> {
+    vec![(0, u64::MAX)]
+}
+
+/// Readable mappings with any of the `protection_mask` protections starting between `start`
+/// and `end`, without their data.
+pub(crate) fn query_regions(handle: RawHandle, start: u64, end: u64, protection_mask: u32) -> Vec<MemoryRegion> {
+    let Ok(maps) = read_maps(handle.0 as u32) else {
+        return vec![];
+    };
+    maps.into_iter()
+        // the vsyscall page is listed but can not be read
+        .filter(|map| map.start >= start && map.start < end && map.path != "[vsyscall]" && map.path != "[vvar]")
+        .filter(|map| map.protection & protection_mask != 0)
+        .filter_map(|map| {
+            Some(MemoryRegion {
+                protection: map.protection,
+                kind: map.kind(),
+                ..MemoryRegion::new(map.start, (map.end - map.start) as usize, ProcessHandle::None, None).ok()?
+            })
+        })
+        .collect()
+}
+
+/// Fills `buf` with the memory at `addr`, through `/proc/<pid>/mem` where `process_vm_readv`
+/// is not available.
+pub(crate) fn read_process_memory(handle: RawHandle, addr: u64, buf: &mut [u8]) -> io::Result<()> {
+    let pid = handle.0 as libc::pid_t;
+    let local = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let remote = libc::iovec {
+        iov_base: addr as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let read = unsafe { libc::process_vm_readv(pid, &local, 1, &remote, 1, 0) };
+    if read == buf.len() as isize {
+        return Ok(());
+    }
+    // stopped at an unreadable page
+    if read > 0 {
+        return Err(PartialRead::new(addr, buf.len(), buf[..read as usize].to_vec()).into());
+    }
+    let error = if read < 0 { io::Error::last_os_error() } else { io::ErrorKind::UnexpectedEof.into() };
+    if error.raw_os_error() != Some(libc::ENOSYS) {
+        return Err(error);
+    }
+    File::open(format!("/proc/{pid}/mem"))?.read_exact_at(buf, addr)
+}
+
+pub(crate) fn client_origin(_window: usize) -> io::Result<(i32, i32)> {
+    Err(unsupported())
+}
+
+pub(crate) fn client_area(_window: usize) -> io::Result<ClientArea> {
+    Err(unsupported())
+}
+
+/// Checks `/proc/<pid>/stat` until `timeout`, counting zombies as exited.
+pub(crate) fn wait_for_exit(handle: RawHandle, timeout: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let exited = match fs::read_to_string(format!("/proc/{}/stat", handle.0)) {
+            // the state follows the parenthesized name, which may contain spaces itself
+            Ok(stat) => stat.rsplit_once(')').is_some_and(|(_, rest)| rest.trim_start().starts_with(['Z', 'X'])),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => true,
+            Err(error) => return Err(error),
+        };
+        let now = Instant::now();
+        if exited || now >= deadline {
+            return Ok(exited);
+        }
+        thread::sleep((deadline - now).min(EXIT_POLL_INTERVAL));
+    }
+}
+
+/// Entries of `/proc/<pid>/pagemap` for the `pages` pages at `start`.
+fn read_pagemap(pid: u32, start: u64, pages: usize) -> io::Result<Vec<u64>> {
+    let mut buf = vec![0u8; pages * 8];
+    File::open(format!("/proc/{pid}/pagemap"))?.read_exact_at(&mut buf, start / PAGE_SIZE as u64 * 8)?;
+    Ok(buf.chunks_exact(8).map(|entry| u64::from_le_bytes(entry.try_into().unwrap())).collect())
+}
+
+/// Whether the kernel keeps soft-dirty bits, which a page written just now has. Without
+/// `CONFIG_MEM_SOFT_DIRTY` the bits are always clear and would hide every write.
+fn soft_dirty_supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        let mut page = vec![0u8; PAGE_SIZE];
+        page[0] = 1;
+        let addr = hint::black_box(page.as_ptr()) as u64;
+        read_pagemap(std::process::id(), addr, 1).is_ok_and(|entries| entries[0] & PM_SOFT_DIRTY != 0)
+    })
+}
+
+/// Pages written since the last [`clear_dirty`], going by their soft-dirty bits.
+pub(crate) fn dirty_pages(handle: RawHandle, start: u64, pages: usize) -> io::Result<Vec<bool>> {
+    if !soft_dirty_supported() {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "the kernel does not track soft-dirty pages"));
+    }
+    Ok(read_pagemap(handle.0 as u32, start, pages)?.into_iter().map(|entry| entry & PM_SOFT_DIRTY != 0).collect())
+}
+
+pub(crate) fn clear_dirty(handle: RawHandle) -> io::Result<()> {
+    fs::write(format!("/proc/{}/clear_refs", handle.0), "4")
+}
+
+/// Pages in RAM, swapped out pages and the ones of files not in the page cache are not.
+pub(crate) fn resident_pages(handle: RawHandle, start: u64, pages: usize) -> io::Result<Vec<bool>> {
+    Ok(read_pagemap(handle.0 as u32, start, pages)?.into_iter().map(|entry| entry & PM_PRESENT != 0).collect())
+}
+
+/// Process ids need no closing.
+pub(crate) fn close_handle(_handle: RawHandle) {}
+
+pub(crate) fn duplicate_handle(handle: RawHandle) -> io::Result<RawHandle> {
+    Ok(handle)
+}
+
+/// Mapped files, which under Wine include the DLLs and the executable of the client.
+pub(crate) fn list_modules(handle: RawHandle) -> io::Result<Vec<Module>> {
+    let mut modules: Vec<Module> = vec![];
+    for map in read_maps(handle.0 as u32)?.into_iter().filter(|map| map.path.starts_with('/')) {
+        let name = Path::new(&map.path).file_name().unwrap_or_default().to_string_lossy().into_owned();
+        match modules.iter_mut().find(|module| module.name == name) {
+            Some(module) => {
+                let end = (module.base + module.size as u64).max(map.end);
+                module.base = module.base.min(map.start);
+                module.size = (end - module.base) as usize;
+            }
+            None => modules.push(Module {
+                name,
+                base: map.start,
+                size: (map.end - map.start) as usize,
+            }),
+        }
+    }
+    Ok(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_spaces_in_mapped_paths() {
+        let line = "7f0000001000-7f0000002000 r-xp 00001000 08:01 1234                       \
+                    /home/user/.steam/steamapps/common/Proton 8.0/files/lib64/wine/x86_64-windows/ntdll.dll";
+        let mapping = parse_mapping(line).unwrap();
+        assert_eq!((mapping.start, mapping.end), (0x7f00_0000_1000, 0x7f00_0000_2000));
+        assert_eq!(mapping.protection, ScanOptions::PAGE_EXECUTE_READ);
+        assert_eq!(
+            mapping.path,
+            "/home/user/.steam/steamapps/common/Proton 8.0/files/lib64/wine/x86_64-windows/ntdll.dll"
+        );
+        assert_eq!(mapping.kind(), RegionKind::Image);
+    }
+
+    #[test]
+    fn reads_anonymous_mappings() {
+        let mapping = parse_mapping("7f0000001000-7f0000002000 rw-p 00000000 00:00 0").unwrap();
+        assert_eq!(mapping.protection, ScanOptions::PAGE_READWRITE);
+        assert_eq!(mapping.path, "");
+        assert_eq!(mapping.kind(), RegionKind::Private);
+    }
+}
@@ -0,0 +1,17 @@
+//! OS specific access to other processes. Everything above it, the object model and the parsers,
+//! builds on any platform and works on memory from dumps and fixtures.
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub(crate) use windows::*;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub(crate) use linux::*;
+
+#[cfg(not(any(windows, target_os = "linux")))]
+mod unsupported;
+#[cfg(not(any(windows, target_os = "linux")))]
+pub(crate) use unsupported::*;
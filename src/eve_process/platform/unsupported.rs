@@ -0,0 +1,59 @@
+use crate::eve_process::module::Module;
+use crate::eve_process::process::{MemoryRegion, Process, RawHandle};
+use crate::eve_process::screen::ClientArea;
+use std::io;
+use std::time::Duration;
+
+fn unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "reading live processes is not supported on this platform")
+}
+
+pub(crate) fn list_processes() -> io::Result<Vec<Process>> {
+    Err(unsupported())
+}
+
+pub(crate) fn address_chunks() -> Vec<(u64, u64)> {
+    vec![]
+}
+
+pub(crate) fn query_regions(_handle: RawHandle, _start: u64, _end: u64, _protection_mask: u32) -> Vec<MemoryRegion> {
+    vec![]
+}
+
+pub(crate) fn read_process_memory(_handle: RawHandle, _addr: u64, _buf: &mut [u8]) -> io::Result<()> {
+    Err(unsupported())
+}
+
+pub(crate) fn client_origin(_window: usize) -> io::Result<(i32, i32)> {
+    Err(unsupported())
+}
+
+pub(crate) fn client_area(_window: usize) -> io::Result<ClientArea> {
+    Err(unsupported())
+}
+
+pub(crate) fn wait_for_exit(_handle: RawHandle, _timeout: Duration) -> io::Result<bool> {
+    Err(unsupported())
+}
+
+pub(crate) fn dirty_pages(_handle: RawHandle, _start: u64, _pages: usize) -> io::Result<Vec<bool>> {
+    Err(unsupported())
+}
+
+pub(crate) fn clear_dirty(_handle: RawHandle) -> io::Result<()> {
+    Err(unsupported())
+}
+
+pub(crate) fn resident_pages(_handle: RawHandle, _start: u64, _pages: usize) -> io::Result<Vec<bool>> {
+    Err(unsupported())
+}
+
+pub(crate) fn close_handle(_handle: RawHandle) {}
+
+pub(crate) fn duplicate_handle(_handle: RawHandle) -> io::Result<RawHandle> {
+    Err(unsupported())
+}
+
+pub(crate) fn list_modules(_handle: RawHandle) -> io::Result<Vec<Module>> {
+    Err(unsupported())
+}
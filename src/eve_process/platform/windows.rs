@@ -0,0 +1,343 @@
+use crate::eve_process::module::Module;
+use crate::eve_process::process::{MemoryRegion, OwnedHandle, Process, ProcessHandle, RawHandle, RegionKind, PAGE_SIZE};
+use crate::eve_process::py_struct::PointerWidth;
+use crate::eve_process::screen::ClientArea;
+use crate::eve_process::source::{LiveMemory, PartialRead};
+use crate::eve_process::ui_tree::UiRect;
+use std::ffi::OsString;
+use std::io;
+use std::io::Error;
+use std::os::windows::ffi::OsStringExt;
+use std::sync::Arc;
+use std::time::Duration;
+use winapi::shared::minwindef::{BOOL, DWORD, FALSE, HMODULE, LPARAM, LPVOID, TRUE};
+use winapi::shared::ntdef::{HANDLE, NULL};
+use winapi::shared::winerror::{ERROR_PARTIAL_COPY, WAIT_TIMEOUT};
+use winapi::shared::windef::{DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, HWND, POINT, RECT};
+use winapi::um::handleapi::{CloseHandle, DuplicateHandle};
+use winapi::um::memoryapi::{ReadProcessMemory, VirtualQueryEx};
+use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcess};
+use winapi::um::psapi::{
+    EnumProcessModulesEx, GetModuleBaseNameW, GetModuleInformation, GetProcessImageFileNameW, QueryWorkingSetEx,
+    LIST_MODULES_ALL, MODULEINFO, PSAPI_WORKING_SET_EX_INFORMATION,
+};
+use winapi::um::synchapi::WaitForSingleObject;
+use winapi::um::sysinfoapi::{GetSystemInfo, SYSTEM_INFO};
+use winapi::um::winnt::{DUPLICATE_SAME_ACCESS, MEMORY_BASIC_INFORMATION64, MEM_COMMIT, PAGE_GUARD, PAGE_NOACCESS, PMEMORY_BASIC_INFORMATION, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ, SYNCHRONIZE};
+use winapi::um::winbase::{INFINITE, WAIT_OBJECT_0};
+use winapi::um::wow64apiset::IsWow64Process;
+use winapi::um::winuser::{
+    ClientToScreen, EnumWindows, GetClientRect, GetDpiForWindow, GetMonitorInfoW, GetWindowTextLengthW, GetWindowTextW,
+    GetWindowThreadProcessId, MonitorFromWindow, SetThreadDpiAwarenessContext, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+};
+
+/// How many ASCII characters to read for a process name at most.
+const MAX_PROC_NAME_LEN: usize = 128;
+const MAX_PROC_PATH_LEN: usize = 1024;
+const MAX_PROC_NUM: usize = 1024;
+/// Pages queried per `QueryWorkingSetEx` call.
+const WORKING_SET_BATCH: usize = 0x10000;
+
+#[profiling::function]
+unsafe extern "system" fn list_processes_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let processes = &mut *(lparam as *mut Vec<Process>);
+
+    // get the process id
+    let mut raw_pid: DWORD = 0;
+    GetWindowThreadProcessId(hwnd, &mut raw_pid);
+    if raw_pid == 0 {
+        return TRUE;
+    }
+    // get the window title
+    let title_len: u32 = GetWindowTextLengthW(hwnd) as u32;
+    if title_len == 0 {
+        return TRUE;
+    }
+    let mut raw_title: Vec<u16> = vec![0; (title_len + 1) as usize];
+    GetWindowTextW(hwnd, raw_title.as_mut_ptr(), MAX_PROC_NAME_LEN as i32);
+
+    // get the process path
+    let raw_handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ | SYNCHRONIZE, FALSE, raw_pid);
+    if raw_handle == NULL {
+        return TRUE;
+    }
+    // closes the handle again on the early returns and when the process is dropped
+    let handle = Arc::new(OwnedHandle::new(RawHandle(raw_handle as usize)));
+    let mut raw_path: Vec<u16> = vec![0; MAX_PROC_PATH_LEN];
+    let path_len: u32 =
+        GetProcessImageFileNameW(raw_handle, raw_path.as_mut_ptr(), raw_path.len() as u32);
+    if path_len != 0 {
+        raw_path.set_len(path_len as usize + 1);
+    } else {
+        return TRUE;
+    }
+    processes.push(Process {
+        pid: raw_pid,
+        path: OsString::from_wide(&raw_path[..path_len as usize])
+            .to_string_lossy()
+            .into_owned(),
+        title: OsString::from_wide(&raw_title[..title_len as usize])
+            .to_string_lossy()
+            .into_owned(),
+        regions: vec![],
+        pointer_width: pointer_width(raw_handle),
+        handle: ProcessHandle::Live(handle.clone()),
+        source: Some(Arc::new(LiveMemory(handle))),
+        region_cache: Default::default(),
+        scan_options: Default::default(),
+        window: hwnd as usize,
+    });
+    TRUE
+}
+
+/// 32 bits for WOW64 processes, which are the only 32-bit ones on 64-bit Windows.
+fn pointer_width(handle: HANDLE) -> PointerWidth {
+    let mut wow64: BOOL = FALSE;
+    if unsafe { IsWow64Process(handle, &mut wow64) } != FALSE && wow64 != FALSE {
+        PointerWidth::Bits32
+    } else {
+        PointerWidth::Bits64
+    }
+}
+
+#[profiling::function]
+pub(crate) fn list_processes() -> io::Result<Vec<Process>> {
+    let mut processes = Vec::<Process>::with_capacity(MAX_PROC_NUM);
+    unsafe {
+        EnumWindows(
+            Some(list_processes_callback),
+            &mut processes as *mut Vec<Process> as isize,
+        );
+    }
+    Ok(processes)
+}
+
+/// Lowest and highest address of the user mode address space.
+fn address_range() -> (u64, u64) {
+    let mut sysinfo: SYSTEM_INFO = unsafe { std::mem::zeroed() };
+    unsafe { GetSystemInfo(&mut sysinfo)}
+    (sysinfo.lpMinimumApplicationAddress as u64, sysinfo.lpMaximumApplicationAddress as u64)
+}
+
+/// The user mode address space in chunks of 256 MiB, which are queried in parallel.
+pub(crate) fn address_chunks() -> Vec<(u64, u64)> {
+    let (min_addr, max_addr) = address_range();
+    let step = 256 * (1 << 20);
+    (min_addr..=max_addr).step_by(step).map(|start| (start, (start + step as u64).min(max_addr + 1))).collect()
+}
+
+/// Committed regions with any of the `protection_mask` protections starting between `start` and
+/// `end`, without their data.
+pub(crate) fn query_regions(handle: RawHandle, start: u64, end: u64, protection_mask: u32) -> Vec<MemoryRegion> {
+    let mut mem_info = MEMORY_BASIC_INFORMATION64 {
+        BaseAddress: 0,
+        AllocationBase: 0,
+        AllocationProtect: 0,
+        __alignment1: 0,
+        RegionSize: 0,
+        State: 0,
+        Protect: 0,
+        Type: 0,
+        __alignment2: 0,
+    };
+    let mut regions = Vec::new();
+    let mut current_address: LPVOID = start as LPVOID;
+    unsafe {
+        while current_address < end as LPVOID && VirtualQueryEx(
+            handle.0 as HANDLE,
+            current_address,
+            &mut mem_info as *mut _ as PMEMORY_BASIC_INFORMATION,
+            size_of::<MEMORY_BASIC_INFORMATION64>(),
+        ) == size_of::<MEMORY_BASIC_INFORMATION64>()
+        {
+            if mem_info.State == MEM_COMMIT
+                && mem_info.Protect & PAGE_NOACCESS == 0
+                && mem_info.Protect & PAGE_GUARD == 0
+                && mem_info.Protect & protection_mask != 0
+            {
+                regions.push(MemoryRegion {
+                    protection: mem_info.Protect,
+                    kind: RegionKind::from_mem_type(mem_info.Type),
+                    ..MemoryRegion::new(
+                        mem_info.BaseAddress,
+                        mem_info.RegionSize as usize,
+                        ProcessHandle::None,
+                        None,
+                    ).unwrap()
+                })
+            }
+            current_address = (mem_info.BaseAddress + mem_info.RegionSize) as LPVOID;
+        }
+    }
+    regions
+}
+
+/// Fills `buf` with the memory at `addr`.
+/// Fails with a [`PartialRead`] where `ReadProcessMemory` copied some bytes before an unreadable page.
+pub(crate) fn read_process_memory(handle: RawHandle, addr: u64, buf: &mut [u8]) -> io::Result<()> {
+    let mut read = 0;
+    unsafe {
+        if ReadProcessMemory(
+            handle.0 as HANDLE,
+            addr as LPVOID,
+            buf.as_mut_ptr() as LPVOID,
+            buf.len(),
+            &mut read,
+        ) == TRUE
+        {
+            return Ok(());
+        }
+    }
+    let error = Error::last_os_error();
+    if error.raw_os_error() == Some(ERROR_PARTIAL_COPY as i32) && read > 0 && read < buf.len() {
+        return Err(PartialRead::new(addr, buf.len(), buf[..read].to_vec()).into());
+    }
+    Err(error)
+}
+
+/// Screen position of the top left corner of the client area of `window`.
+pub(crate) fn client_origin(window: usize) -> io::Result<(i32, i32)> {
+    let mut origin = POINT { x: 0, y: 0 };
+    if unsafe { ClientToScreen(window as HWND, &mut origin) } == FALSE {
+        return Err(Error::last_os_error());
+    }
+    Ok((origin.x, origin.y))
+}
+
+fn to_rect(rect: RECT) -> UiRect {
+    UiRect {
+        x: rect.left,
+        y: rect.top,
+        width: rect.right - rect.left,
+        height: rect.bottom - rect.top,
+    }
+}
+
+/// Client area of `window` in physical pixels.
+pub(crate) fn client_area(window: usize) -> io::Result<ClientArea> {
+    let hwnd = window as HWND;
+    // positions and sizes of other windows are scaled to 96 DPI for DPI unaware threads
+    let previous = unsafe { SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) };
+    let result = (|| unsafe {
+        let mut client = std::mem::zeroed::<RECT>();
+        let mut origin = POINT { x: 0, y: 0 };
+        if GetClientRect(hwnd, &mut client) == FALSE || ClientToScreen(hwnd, &mut origin) == FALSE {
+            return Err(Error::last_os_error());
+        }
+        let mut monitor = MONITORINFO {
+            cbSize: size_of::<MONITORINFO>() as DWORD,
+            ..std::mem::zeroed()
+        };
+        GetMonitorInfoW(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST), &mut monitor);
+        Ok(ClientArea {
+            rect: UiRect {
+                x: origin.x,
+                y: origin.y,
+                ..to_rect(client)
+            },
+            monitor: to_rect(monitor.rcMonitor),
+            dpi: match GetDpiForWindow(hwnd) {
+                0 => 96,
+                dpi => dpi,
+            },
+        })
+    })();
+    if !previous.is_null() {
+        unsafe { SetThreadDpiAwarenessContext(previous) };
+    }
+    result
+}
+
+pub(crate) fn wait_for_exit(handle: RawHandle, timeout: Duration) -> io::Result<bool> {
+    let millis = timeout.as_millis().min(INFINITE as u128 - 1) as DWORD;
+    match unsafe { WaitForSingleObject(handle.0 as HANDLE, millis) } {
+        WAIT_OBJECT_0 => Ok(true),
+        WAIT_TIMEOUT => Ok(false),
+        _ => Err(Error::last_os_error()),
+    }
+}
+
+fn writes_not_tracked() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "writes of other processes are not tracked on Windows")
+}
+
+/// `GetWriteWatch` only covers the calling process and `QueryWorkingSetEx` has no dirty bit.
+pub(crate) fn dirty_pages(_handle: RawHandle, _start: u64, _pages: usize) -> io::Result<Vec<bool>> {
+    Err(writes_not_tracked())
+}
+
+pub(crate) fn clear_dirty(_handle: RawHandle) -> io::Result<()> {
+    Err(writes_not_tracked())
+}
+
+/// Pages in the working set of the process, going by `QueryWorkingSetEx`. Needs
+/// `PROCESS_QUERY_INFORMATION`.
+pub(crate) fn resident_pages(handle: RawHandle, start: u64, pages: usize) -> io::Result<Vec<bool>> {
+    let mut resident = Vec::with_capacity(pages);
+    // bounds the buffer for regions of several GB
+    for batch_start in (0..pages).step_by(WORKING_SET_BATCH) {
+        let batch = batch_start..pages.min(batch_start + WORKING_SET_BATCH);
+        let mut entries: Vec<PSAPI_WORKING_SET_EX_INFORMATION> = batch
+            .map(|page| {
+                let mut entry: PSAPI_WORKING_SET_EX_INFORMATION = unsafe { std::mem::zeroed() };
+                entry.VirtualAddress = (start + (page * PAGE_SIZE) as u64) as LPVOID;
+                entry
+            })
+            .collect();
+        let size = (entries.len() * size_of::<PSAPI_WORKING_SET_EX_INFORMATION>()) as DWORD;
+        if unsafe { QueryWorkingSetEx(handle.0 as HANDLE, entries.as_mut_ptr() as LPVOID, size) } == FALSE {
+            return Err(Error::last_os_error());
+        }
+        resident.extend(entries.iter().map(|entry| entry.VirtualAttributes.Valid() != 0));
+    }
+    Ok(resident)
+}
+
+pub(crate) fn close_handle(handle: RawHandle) {
+    unsafe { CloseHandle(handle.0 as HANDLE) };
+}
+
+/// A second handle to the same process with the same access.
+pub(crate) fn duplicate_handle(handle: RawHandle) -> io::Result<RawHandle> {
+    let mut duplicate: HANDLE = NULL;
+    let current = unsafe { GetCurrentProcess() };
+    if unsafe { DuplicateHandle(current, handle.0 as HANDLE, current, &mut duplicate, 0, FALSE, DUPLICATE_SAME_ACCESS) } == FALSE {
+        return Err(Error::last_os_error());
+    }
+    Ok(RawHandle(duplicate as usize))
+}
+
+/// Modules loaded into the process, 32 and 64 bit.
+pub(crate) fn list_modules(handle: RawHandle) -> io::Result<Vec<Module>> {
+    let process = handle.0 as HANDLE;
+    let mut modules: Vec<HMODULE> = vec![NULL as HMODULE; 1024];
+    loop {
+        let mut needed: DWORD = 0;
+        let capacity = (modules.len() * size_of::<HMODULE>()) as DWORD;
+        if unsafe { EnumProcessModulesEx(process, modules.as_mut_ptr(), capacity, &mut needed, LIST_MODULES_ALL) } == FALSE {
+            return Err(Error::last_os_error());
+        }
+        let count = needed as usize / size_of::<HMODULE>();
+        if count <= modules.len() {
+            modules.truncate(count);
+            break;
+        }
+        modules.resize(count, NULL as HMODULE);
+    }
+    Ok(modules
+        .into_iter()
+        .filter_map(|module| unsafe {
+            let mut info: MODULEINFO = std::mem::zeroed();
+            if GetModuleInformation(process, module, &mut info, size_of::<MODULEINFO>() as DWORD) == FALSE {
+                return None;
+            }
+            let mut raw_name: Vec<u16> = vec![0; MAX_PROC_PATH_LEN];
+            let name_len = GetModuleBaseNameW(process, module, raw_name.as_mut_ptr(), raw_name.len() as DWORD);
+            Some(Module {
+                name: OsString::from_wide(&raw_name[..name_len as usize]).to_string_lossy().into_owned(),
+                base: info.lpBaseOfDll as u64,
+                size: info.SizeOfImage as usize,
+            })
+        })
+        .collect())
+}
@@ -1,46 +1,223 @@
+use crate::eve_process::dump;
+use crate::eve_process::minidump;
+use crate::eve_process::module::Module;
+use crate::eve_process::platform;
+use crate::eve_process::py_struct::PointerWidth;
+use crate::eve_process::screen::ClientArea;
+use crate::eve_process::source::{DumpFile, MemorySource, PartialRead};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use rayon::prelude::*;
-use std::ffi::OsString;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Debug;
-use std::{io, result};
-use std::io::Error;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Error, Write};
+use std::mem;
 use std::num::NonZeroUsize;
-use std::os::windows::ffi::OsStringExt;
-use lazy_static::lazy_static;
-use tracing::{debug, info, warn};
+use std::path::Path;
+use tracing::debug;
 use wildmatch::WildMatch;
-use winapi::shared::minwindef::{BOOL, DWORD, FALSE, LPARAM, LPVOID, TRUE};
-use winapi::shared::ntdef::{HANDLE, NULL};
-use winapi::shared::windef::HWND;
-use winapi::um::memoryapi::{ReadProcessMemory, VirtualQueryEx};
-use winapi::um::processthreadsapi::OpenProcess;
-use winapi::um::psapi::GetProcessImageFileNameW;
-use winapi::um::winnt::{MEMORY_BASIC_INFORMATION64, MEM_COMMIT, PAGE_GUARD, PAGE_NOACCESS, PAGE_READONLY, PAGE_READWRITE, PMEMORY_BASIC_INFORMATION, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ};
-use winapi::um::winuser::{
-    EnumWindows, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
-};
 use lru::LruCache;
-use std::sync::Mutex;
-use winapi::um::sysinfoapi::{GetSystemInfo, SYSTEM_INFO};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
-/// How many ASCII characters to read for a process name at most.
-const MAX_PROC_NAME_LEN: usize = 128;
-const MAX_PROC_PATH_LEN: usize = 1024;
-const MAX_PROC_NUM: usize = 1024;
 const MEMORY_MAP_CACHE_SIZE: usize = 1<<6;
+/// Granularity of [`Process::resync_pages`].
+pub const PAGE_SIZE: usize = 0x1000;
+/// Larger regions are synced in chunks of this size in parallel, single reads of heaps of
+/// several GB fail or keep one thread busy.
+const SYNC_CHUNK_SIZE: usize = 0x100_0000;
 
+/// Region index and offset of recently looked up addresses, only valid for the regions of the
+/// process it belongs to.
+#[derive(Debug)]
+pub(crate) struct RegionCache(Mutex<LruCache<u64, (usize, usize)>>);
+
+impl Default for RegionCache {
+    fn default() -> Self {
+        RegionCache(Mutex::new(LruCache::new(NonZeroUsize::new(MEMORY_MAP_CACHE_SIZE).unwrap())))
+    }
+}
+
+impl RegionCache {
+    fn get(&self, addr: u64) -> Option<(usize, usize)> {
+        self.0.lock().unwrap().get(&addr).copied()
+    }
 
+    fn put(&self, addr: u64, location: (usize, usize)) {
+        self.0.lock().unwrap().put(addr, location);
+    }
 
-lazy_static!{
-    static ref _memory_map_cache: Mutex<LruCache::<u64, (usize, usize)>> = 
-    Mutex::new(LruCache::new(NonZeroUsize::new(MEMORY_MAP_CACHE_SIZE).unwrap()));
+    fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
 }
 
 
-/// A handle to an opened process.
+/// What a region holds, `MEMORY_BASIC_INFORMATION::Type` on Windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RegionKind {
+    /// Heaps, stacks and other allocations of the process.
+    Private,
+    /// Mapped executables and DLLs.
+    Image,
+    /// Mapped files and sections.
+    Mapped,
+    /// Where the source does not tell, like dumps without memory info.
+    #[default]
+    Unknown,
+}
 
-#[derive(Debug, Clone, Copy, Default)]
+impl RegionKind {
+    /// The kind of a `MEM_PRIVATE`, `MEM_IMAGE` or `MEM_MAPPED` type.
+    pub(crate) fn from_mem_type(mem_type: u32) -> Self {
+        match mem_type {
+            0x20000 => RegionKind::Private,
+            0x1000000 => RegionKind::Image,
+            0x40000 => RegionKind::Mapped,
+            _ => RegionKind::Unknown,
+        }
+    }
+}
+
+/// Which regions are kept besides their protections. Regions of unknown kind pass the checks
+/// of the kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionFilter {
+    pub min_size: usize,
+    pub max_size: usize,
+    /// Only private regions, which hold the heaps of the interpreter.
+    pub private_only: bool,
+    pub exclude_image: bool,
+    pub exclude_mapped: bool,
+}
+
+impl RegionFilter {
+    pub fn matches(&self, region: &MemoryRegion) -> bool {
+        let kind = match region.kind {
+            RegionKind::Private | RegionKind::Unknown => true,
+            RegionKind::Image => !self.private_only && !self.exclude_image,
+            RegionKind::Mapped => !self.private_only && !self.exclude_mapped,
+        };
+        kind && (self.min_size..=self.max_size).contains(&region.size)
+    }
+}
+
+impl Default for RegionFilter {
+    fn default() -> Self {
+        RegionFilter {
+            min_size: 0,
+            max_size: usize::MAX,
+            private_only: false,
+            exclude_image: false,
+            exclude_mapped: false,
+        }
+    }
+}
+
+/// Which memory of a process is enumerated and read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// `PAGE_*` protections, committed regions with any of them are read. Guard and no-access
+    /// pages are always left out.
+    pub protection_mask: u32,
+    pub filter: RegionFilter,
+    /// Leaves out regions none of whose pages are resident, which reading would make the client
+    /// page in from disk. Where residency is not known, like in dumps, all regions are kept.
+    pub skip_paged_out: bool,
+}
+
+impl ScanOptions {
+    pub const PAGE_READONLY: u32 = 0x02;
+    pub const PAGE_READWRITE: u32 = 0x04;
+    pub const PAGE_WRITECOPY: u32 = 0x08;
+    pub const PAGE_EXECUTE_READ: u32 = 0x20;
+    pub const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+    pub const PAGE_EXECUTE_WRITECOPY: u32 = 0x80;
+
+    /// The options suited to `process`: Wine allocates heaps with executable protections, so
+    /// clients running under Wine or Proton are read with [`ScanOptions::all_readable`].
+    pub fn for_process(process: &Process) -> Self {
+        if process.is_wine() {
+            debug!("process {} runs under Wine", process.pid);
+            Self::all_readable()
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Every readable protection, including copy-on-write and executable pages.
+    pub fn all_readable() -> Self {
+        ScanOptions {
+            protection_mask: Self::PAGE_READONLY
+                | Self::PAGE_READWRITE
+                | Self::PAGE_WRITECOPY
+                | Self::PAGE_EXECUTE_READ
+                | Self::PAGE_EXECUTE_READWRITE
+                | Self::PAGE_EXECUTE_WRITECOPY,
+            filter: Default::default(),
+            skip_paged_out: false,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Every region, for dumps where the protections were chosen when writing them.
+    fn any_protection() -> Self {
+        ScanOptions {
+            protection_mask: u32::MAX,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            protection_mask: Self::PAGE_READONLY | Self::PAGE_READWRITE,
+            filter: Default::default(),
+            skip_paged_out: false,
+        }
+    }
+}
+
+/// A raw OS handle, kept at full pointer width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawHandle(pub usize);
+
+/// An opened process handle, closed when the last clone of it is dropped. Cloning duplicates
+/// the handle.
+#[derive(Debug)]
+pub struct OwnedHandle(RawHandle);
+
+impl OwnedHandle {
+    /// Takes over `raw`, which must be an opened handle no one else closes.
+    pub(crate) fn new(raw: RawHandle) -> Self {
+        OwnedHandle(raw)
+    }
+
+    pub fn raw(&self) -> RawHandle {
+        self.0
+    }
+}
+
+impl Clone for OwnedHandle {
+    fn clone(&self) -> Self {
+        OwnedHandle(platform::duplicate_handle(self.0).expect("failed to duplicate a process handle"))
+    }
+}
+
+impl Drop for OwnedHandle {
+    fn drop(&mut self) {
+        platform::close_handle(self.0);
+    }
+}
+
+/// A handle to an opened process, shared by the process and its regions.
+#[derive(Debug, Clone, Default)]
 pub enum ProcessHandle {
-    Live(u32),
+    Live(Arc<OwnedHandle>),
     File,
     #[default]
     None,
@@ -51,26 +228,43 @@ pub struct Process {
     pub pid: u32,
     pub path: String,
     pub title: String,
+    /// Sorted by start, see [`Process::invalidate_regions`] when changing them.
     pub regions: Vec<MemoryRegion>,
+    /// Detected with `IsWow64Process` for running processes, 64 bits for dumps.
+    pub pointer_width: PointerWidth,
     pub(crate) handle: ProcessHandle,
+    /// Where reads beyond the synced regions go, None for processes that only have their regions.
+    pub(crate) source: Option<Arc<dyn MemorySource>>,
+    pub(crate) region_cache: RegionCache,
+    /// What the regions were enumerated with, reused by [`Process::refresh_regions`].
+    pub(crate) scan_options: ScanOptions,
+    /// Top level window the process was found by.
+    pub(crate) window: usize,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct MemoryRegion {
     pub start: u64,
     pub size: usize,
-    pub data: Vec<u8>,
+    /// Unset until the region is synced, see [`Process::region_data`].
+    pub data: OnceLock<Vec<u8>>,
     pub handle: ProcessHandle,
+    /// `PAGE_*` protection the region was enumerated with, 0 where unknown.
+    pub protection: u32,
+    pub kind: RegionKind,
 }
 
 #[profiling::all_functions]
 impl MemoryRegion {
+    /// A region that is not synced without `data`.
     pub fn new(start: u64, size: usize, handle: ProcessHandle, data: Option<Vec<u8>>) -> io::Result<Self> {
         Ok(MemoryRegion {
             start,
             size,
-            data: data.unwrap_or(vec![0; size]),
+            data: data.map(OnceLock::from).unwrap_or_default(),
             handle,
+            protection: 0,
+            kind: RegionKind::Unknown,
         })
     }
 
@@ -80,36 +274,64 @@ impl MemoryRegion {
     }
 
     pub fn sync(mut self) -> Result<Self, (Self, Error)> {
-        if let ProcessHandle::Live(h) = self.handle {
-            unsafe {
-                if ReadProcessMemory(
-                    h as HANDLE,
-                    self.start as LPVOID,
-                    self.data.as_mut_ptr() as LPVOID,
-                    self.size as usize,
-                    NULL as *mut _,
-                ) == TRUE
-                {
+        if let ProcessHandle::Live(h) = &self.handle {
+            let mut data = vec![0; self.size];
+            let handle = h.raw();
+            match read_chunked(|addr, buf| platform::read_process_memory(handle, addr, buf), self.start, &mut data) {
+                Ok(()) => {
+                    self.data = data.into();
                     Ok(self)
-                } else {
-                    Err((self, Error::last_os_error()))
                 }
+                Err(e) => Err((self, e)),
             }
         } else {
             Err((self, Error::new(io::ErrorKind::InvalidInput, "Invalid handle")))
         }
     }
     
+    /// Re-reads the page holding `offset` of a synced region.
+    pub fn sync_page(&mut self, offset: usize) -> io::Result<()> {
+        let ProcessHandle::Live(h) = &self.handle else {
+            return Err(Error::new(io::ErrorKind::InvalidInput, "Invalid handle"));
+        };
+        let Some(data) = self.data.get_mut() else {
+            return Err(not_synced());
+        };
+        let start = offset / PAGE_SIZE * PAGE_SIZE;
+        let end = (start + PAGE_SIZE).min(self.size);
+        if start >= end {
+            return Err(Error::new(io::ErrorKind::InvalidInput, "Invalid offset or size"));
+        }
+        platform::read_process_memory(h.raw(), self.start + start as u64, &mut data[start..end])
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.data.get().is_some()
+    }
+
+    /// The synced data, empty where the region is not synced.
+    pub fn bytes(&self) -> &[u8] {
+        self.data.get().map_or(&[], Vec::as_slice)
+    }
+
+    fn synced_bytes(&self) -> io::Result<&[u8]> {
+        self.data.get().map(Vec::as_slice).ok_or_else(not_synced)
+    }
+
     pub fn read_bytes(&self, offset: usize, size: usize) -> io::Result<Self> {
         if offset + size > self.size {
             Err(Error::new(io::ErrorKind::InvalidInput, "Invalid offset or size"))
         } else { 
-            MemoryRegion::new(
-                self.start + offset as u64,
-                size,
-                self.handle,
-                Some(self.data[offset..offset + size].to_vec()),
-            )
+            Ok(MemoryRegion {
+                protection: self.protection,
+                kind: self.kind,
+                ..MemoryRegion::new(
+                    self.start + offset as u64,
+                    size,
+                    self.handle.clone(),
+                    Some(self.synced_bytes()?[offset..offset + size].to_vec()),
+                )?
+            })
         }
     }
     
@@ -117,7 +339,7 @@ impl MemoryRegion {
         if offset + size > self.size {
             Err(Error::new(io::ErrorKind::InvalidInput, "Invalid offset or size"))
         } else { 
-            Ok(&self.data[offset..offset + size])
+            Ok(&self.synced_bytes()?[offset..offset + size])
         }
     }
     
@@ -126,7 +348,7 @@ impl MemoryRegion {
         if offset + size > self.size {
             Err(Error::new(io::ErrorKind::InvalidInput, "Invalid offset or size"))
         } else { 
-            Ok(unsafe { (self.data[offset..offset + size].as_ptr() as *const T).as_ref().unwrap() })
+            Ok(unsafe { (self.synced_bytes()?[offset..offset + size].as_ptr() as *const T).as_ref().unwrap() })
         }
     }
     
@@ -136,7 +358,7 @@ impl MemoryRegion {
         } else {
             let v: Vec::<&T>;
             Ok(unsafe {
-                let t: Vec<_> = self.data[offset..offset + size]
+                let t: Vec<_> = self.synced_bytes()?[offset..offset + size]
                     .into_iter()
                     .step_by(size_of::<T>())
                     .map(|x| (std::ptr::from_ref(x) as *const T).as_ref().unwrap())
@@ -149,6 +371,109 @@ impl MemoryRegion {
 
 #[profiling::all_functions]
 impl Process {
+    /// A process without a handle over the given memory, like synthetic images for benchmarks.
+    pub fn from_regions(mut regions: Vec<MemoryRegion>) -> Self {
+        regions.sort_by_key(|region| region.start);
+        Process {
+            pid: 0,
+            path: String::new(),
+            title: String::new(),
+            regions,
+            pointer_width: PointerWidth::Bits64,
+            handle: ProcessHandle::None,
+            source: None,
+            region_cache: Default::default(),
+            scan_options: Default::default(),
+            window: 0,
+        }
+    }
+
+    /// A process reading `source`, like synthetic memory for tests. Its regions are listed and read by
+    /// [`Process::enum_memory_regions`] and [`Process::sync_memory_regions`].
+    pub fn from_source<S: MemorySource + 'static>(source: S) -> Self {
+        Process {
+            pid: 0,
+            path: String::new(),
+            title: String::new(),
+            regions: vec![],
+            pointer_width: PointerWidth::Bits64,
+            handle: source.handle(),
+            source: Some(Arc::new(source)),
+            region_cache: Default::default(),
+            scan_options: Default::default(),
+            window: 0,
+        }
+    }
+
+    /// Writes the synced regions to `path` uncompressed, to be read again with [`Process::open_dump`].
+    pub fn write_dump<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        dump::write(self, &mut out)?;
+        out.flush()
+    }
+
+    /// Opens a dump written by [`Process::write_dump`] or [`Process::save_snapshot`], so that the
+    /// client can be scanned and parsed again without running. Reads outside of the dumped regions fail.
+    pub fn open_dump<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::load_snapshot(path)
+    }
+
+    /// Writes the synced regions with their starts, sizes and protections to `path`, gzip
+    /// compressed, which mostly leaves the unused parts of the heaps out.
+    pub fn save_snapshot<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = GzEncoder::new(BufWriter::new(File::create(path)?), Compression::fast());
+        dump::write(self, &mut out)?;
+        out.finish()?.flush()
+    }
+
+    /// Reads a snapshot written by [`Process::save_snapshot`], or an uncompressed dump.
+    pub fn load_snapshot<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        if let Some(dump) = dump::open(path.as_ref())? {
+            return Ok(Self::from_dump_file(dump, path.as_ref(), &ScanOptions::any_protection()));
+        }
+        let (pid, mut regions) = dump::read(BufReader::new(File::open(&path)?))?;
+        regions.sort_by_key(|region| region.start);
+        Ok(Process {
+            pid,
+            path: path.as_ref().display().to_string(),
+            title: String::new(),
+            regions,
+            pointer_width: PointerWidth::Bits64,
+            handle: ProcessHandle::File,
+            source: None,
+            region_cache: Default::default(),
+            scan_options: Default::default(),
+            window: 0,
+        })
+    }
+
+    /// Opens a Windows minidump, like the ones of Task Manager or procdump, reading all of its memory.
+    pub fn from_minidump<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_minidump(path.as_ref(), None)
+    }
+
+    /// Opens a Windows minidump, reading the memory `options` selects if the dump records the
+    /// protections.
+    pub fn from_minidump_with<P: AsRef<Path>>(path: P, options: &ScanOptions) -> io::Result<Self> {
+        Self::read_minidump(path.as_ref(), Some(options))
+    }
+
+    fn read_minidump(path: &Path, options: Option<&ScanOptions>) -> io::Result<Self> {
+        let all = ScanOptions::any_protection();
+        Ok(Self::from_dump_file(minidump::open(path)?, path, options.unwrap_or(&all)))
+    }
+
+    /// The regions of `dump` that `options` selects, read into memory.
+    fn from_dump_file(dump: DumpFile, path: &Path, options: &ScanOptions) -> Self {
+        Process {
+            pid: dump.pid,
+            path: path.display().to_string(),
+            ..Self::from_source(dump)
+        }
+        .enum_memory_regions_with(options)
+        .sync_memory_regions()
+    }
+
     pub fn list(
         pid: Option<u32>,
         path: Option<&str>,
@@ -163,7 +488,9 @@ impl Process {
                     .filter(|proc| {
                         (pid.is_none() || proc.pid == pid.unwrap())
                             && (path.is_none() || WildMatch::new(path.unwrap()).matches(&proc.path))
+                            // the titles of processes listed without their windows are not known
                             && (title.is_none()
+                                || proc.title.is_empty()
                                 || WildMatch::new(title.unwrap()).matches(&proc.title))
                     })
                     .collect::<Vec<Self>>();
@@ -178,231 +505,355 @@ impl Process {
             }
         }
     }
-    pub fn enum_memory_regions(mut self) -> Self {
-        let mut sysinfo: SYSTEM_INFO = unsafe { std::mem::zeroed() };
-        unsafe { GetSystemInfo(&mut sysinfo)}
-        let min_addr = sysinfo.lpMinimumApplicationAddress as u64;
-        let max_addr = sysinfo.lpMaximumApplicationAddress as u64;
-        let step = 256 * (1 << 20);
-        let batch_size = step * 256;
-        let num_batches = ((max_addr - min_addr + 1) / batch_size);
-        let mut regions_list = Vec::with_capacity(num_batches as usize);
-        for i in 0..num_batches {
-            let batch_min_addr = i * batch_size + min_addr;
-            let batch_max_addr = (i + 1) * batch_size + min_addr;
-            let range: Vec<u64> = (batch_min_addr..batch_max_addr).step_by(step as usize).collect();
-            let sub_regions: Vec<Vec<MemoryRegion>> = range.into_par_iter().filter_map(
-                |start: u64| -> Option<Vec<MemoryRegion>> {
-                    let regions = self.enum_memory_regions_in_range(start, start + step as u64);
-                    if regions.is_empty() {
-                        return None;
-                    } else {
-                        return Some(regions);
-                    }
-                }
-            ).collect();
-            regions_list.push(sub_regions);
+    /// Modules loaded into the process.
+    pub fn modules(&self) -> io::Result<Vec<Module>> {
+        match &self.source {
+            Some(source) => source.modules(),
+            None => Ok(vec![]),
         }
+    }
 
-        self.regions = regions_list.into_par_iter().filter(|x| !x.is_empty()).reduce(
-            || Vec::new(),
-            |mut acc, x| {
-                acc.extend(x);
-                acc
-            }
-        ).into_par_iter().filter(|x| !x.is_empty()).reduce(
-            || Vec::new(),
-            |mut acc, x| {
-                acc.extend(x);
-                acc
-            }
-        );
-        self.regions.sort_by_key(|x| x.start);
+    /// Whether the process runs under Wine or Proton, going by its modules.
+    pub fn is_wine(&self) -> bool {
+        self.modules().is_ok_and(|modules| modules.iter().any(Module::is_wine))
+    }
+
+    /// Waits up to `timeout` for the process to exit, returns whether it did. Fails for
+    /// processes that are not running ones, like dumps.
+    pub fn wait_for_exit(&self, timeout: Duration) -> io::Result<bool> {
+        match &self.handle {
+            ProcessHandle::Live(handle) => platform::wait_for_exit(handle.raw(), timeout),
+            _ => Err(Error::new(io::ErrorKind::Unsupported, "not a running process")),
+        }
+    }
+
+    /// Screen position of the top left corner of the window's client area.
+    pub fn client_origin(&self) -> io::Result<(i32, i32)> {
+        platform::client_origin(self.window)
+    }
+
+    /// Client area, monitor and DPI of the window, see [`ScreenMapping`](crate::eve_process::screen::ScreenMapping).
+    pub fn client_area(&self) -> io::Result<ClientArea> {
+        platform::client_area(self.window)
+    }
+
+    pub fn enum_memory_regions(self) -> Self {
+        self.enum_memory_regions_with(&ScanOptions::default())
+    }
+
+    pub fn enum_memory_regions_with(mut self, options: &ScanOptions) -> Self {
+        self.scan_options = *options;
+        self.regions = self.list_regions();
+        self.invalidate_regions();
         self
     }
-    
-    fn enum_memory_regions_in_range(&self, start: u64, end: u64) -> Vec<MemoryRegion> {
-        let mut mem_info = MEMORY_BASIC_INFORMATION64 {
-            BaseAddress: 0,
-            AllocationBase: 0,
-            AllocationProtect: 0,
-            __alignment1: 0,
-            RegionSize: 0,
-            State: 0,
-            Protect: 0,
-            Type: 0,
-            __alignment2: 0,
+
+    /// The regions of the source selected by the scan options, sorted and without reading them.
+    pub(crate) fn list_regions(&self) -> Vec<MemoryRegion> {
+        let mut regions = match &self.source {
+            Some(source) => source.regions(&self.scan_options),
+            None => vec![],
         };
-        let mut regions = Vec::new();
-        let mut current_address: LPVOID = start as LPVOID;
-        match self.handle {
-            ProcessHandle::Live(handle) => unsafe {
-                while current_address < end as LPVOID && VirtualQueryEx(
-                    handle as HANDLE,
-                    current_address,
-                    &mut mem_info as *mut _ as PMEMORY_BASIC_INFORMATION,
-                    size_of::<MEMORY_BASIC_INFORMATION64>(),
-                ) == size_of::<MEMORY_BASIC_INFORMATION64>()
-                {
-                    if mem_info.State == MEM_COMMIT
-                        && mem_info.Protect & PAGE_NOACCESS == 0
-                        && mem_info.Protect & PAGE_GUARD == 0
-                        && mem_info.Protect & (PAGE_READONLY | PAGE_READWRITE) != 0
-                    {
-                        regions.push(MemoryRegion::new(
-                            mem_info.BaseAddress,
-                            mem_info.RegionSize as usize,
-                            ProcessHandle::Live(handle),
-                            None,
-                        ).unwrap())
-                    }
-                    current_address = (mem_info.BaseAddress + mem_info.RegionSize) as LPVOID;
-                }
-                regions
-            },
-            ProcessHandle::File => {regions}
-            ProcessHandle::None => {regions}
+        regions.retain(|region| self.scan_options.filter.matches(region));
+        if let Some(source) = self.source.as_ref().filter(|_| self.scan_options.skip_paged_out) {
+            regions = drop_paged_out(source, regions);
         }
+        regions.sort_by_key(|x| x.start);
+        regions
     }
 
+    /// Reads the data of the regions from the source, dropping the ones that cannot be read.
+    /// Processes without a source keep their regions as they are.
     pub fn sync_memory_regions(mut self) -> Self {
-        self.regions = self.regions
-            .into_par_iter()
-            .filter_map(|region| {
-                region.sync().ok()
-            }).collect();
+        let Some(source) = self.source.clone() else {
+            return self;
+        };
+        self.regions = read_regions(&source, self.regions);
+        self.invalidate_regions();
         self
     }
 
-    pub fn get_region_from_address(&self, addr: u64) -> io::Result<(usize, usize)> {
-        if let Some(&res) = _memory_map_cache.lock().unwrap().get(&addr) {
-            return Ok(res);
+    /// Lists the regions again and reads only the new ones and the ones whose start, size or
+    /// protection changed, returns how many were read. The others keep their data, see
+    /// [`Process::resync_pages`] to read them again.
+    pub fn refresh_regions(&mut self) -> usize {
+        let Some(source) = self.source.clone() else {
+            return 0;
+        };
+        let mut known: HashMap<_, _> = mem::take(&mut self.regions)
+            .into_iter()
+            .map(|region| ((region.start, region.size, region.protection), region))
+            .collect();
+        let mut changed = vec![];
+        for region in self.list_regions() {
+            match known.remove(&(region.start, region.size, region.protection)) {
+                Some(region) => self.regions.push(region),
+                None => changed.push(region),
+            }
         }
-        let res = match self.regions.binary_search_by_key(&addr, |region| region.start)
-        {
-            Ok(index) => Ok((index, 0)),
-            Err(index) => {
-                if index == 0 || index == self.regions.len() {
-                    Err(Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "Address not found in any memory region",
-                    ))
-                } else {
-                    let index = index - 1;
-                    let offset = addr - self.regions[index].start;
-                    if addr < self.regions[index].start {
-                        Err(Error::new(
-                            io::ErrorKind::InvalidInput,
-                            "Unknown error, MemoryRegions may not be correctly sorted.",
-                        ))
-                    } else if offset > self.regions[index].size as u64 {
-                        Err(Error::new(
-                            io::ErrorKind::InvalidInput,
-                            "Address not found in any memory region",
-                        ))
-                    } else {
-                        Ok((index, offset as usize))
+        let read = read_regions(&source, changed);
+        let count = read.len();
+        self.regions.extend(read);
+        self.regions.sort_by_key(|region| region.start);
+        self.invalidate_regions();
+        count
+    }
+
+    /// Forgets the looked up addresses, needed after changing `regions` directly.
+    pub fn invalidate_regions(&self) {
+        self.region_cache.clear();
+    }
+
+    /// Re-reads only the synced pages holding `addrs` instead of all regions, returns how many
+    /// pages were read. Addresses outside of the regions are ignored.
+    ///
+    /// Where writes of the client cannot be tracked, see [`Process::sync_dirty_pages`], the caller
+    /// names what it needs fresh, like the objects it reads on every poll.
+    pub fn resync_pages<I: IntoIterator<Item = u64>>(&mut self, addrs: I) -> io::Result<usize> {
+        let pages: BTreeSet<(usize, usize)> = addrs
+            .into_iter()
+            .filter_map(|addr| self.get_region_from_address(addr).ok())
+            .map(|(index, offset)| (index, offset / PAGE_SIZE))
+            .collect();
+        let Some(source) = &self.source else {
+            return Err(Error::new(io::ErrorKind::InvalidInput, "Invalid handle"));
+        };
+        let mut count = 0;
+        for &(index, page) in &pages {
+            let region = &mut self.regions[index];
+            // regions not synced yet are read fresh when first accessed
+            let Some(data) = region.data.get_mut() else {
+                continue;
+            };
+            let end = ((page + 1) * PAGE_SIZE).min(region.size);
+            source.read(region.start + (page * PAGE_SIZE) as u64, &mut data[page * PAGE_SIZE..end])?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Re-reads the pages of the regions written since the last call, returns how many pages were
+    /// read. The first call reads about everything, as pages count as written until then.
+    ///
+    /// Writes are tracked with the soft-dirty bits of Linux. Windows has no equivalent for other
+    /// processes, `QueryWorkingSetEx` only tells whether pages are resident and `GetWriteWatch`
+    /// only covers allocations of the calling process, so this fails there and the regions have
+    /// to be read again with [`Process::sync_memory_regions`]. Writes racing with the reset of
+    /// the bits are only seen once the page is written again.
+    pub fn sync_dirty_pages(&mut self) -> io::Result<usize> {
+        let Some(source) = self.source.clone() else {
+            return Err(Error::new(io::ErrorKind::InvalidInput, "Invalid handle"));
+        };
+        let dirty = self
+            .regions
+            .par_iter()
+            .map(|region| source.dirty_pages(region.start, region.size))
+            .collect::<io::Result<Vec<_>>>()?;
+        source.clear_dirty()?;
+        let count = self
+            .regions
+            .par_iter_mut()
+            .zip(dirty)
+            .map(|(region, dirty)| {
+                let Some(data) = region.data.get_mut() else {
+                    return 0;
+                };
+                let mut count = 0;
+                let mut page = 0;
+                // runs of written pages are read at once
+                while page < dirty.len() {
+                    let run = dirty[page..].iter().take_while(|&&written| written).count();
+                    if run == 0 {
+                        page += 1;
+                        continue;
                     }
+                    let (start, end) = (page * PAGE_SIZE, ((page + run) * PAGE_SIZE).min(region.size));
+                    if source.read(region.start + start as u64, &mut data[start..end]).is_ok() {
+                        count += run;
+                    }
+                    page += run;
                 }
-            }
-        };
-        match res {
-            Ok((index, offset)) => {
-                _memory_map_cache.lock().unwrap().put(addr, (index, offset));
-                Ok((index, offset))
-            }
-            Err(e) => Err(e),
+                count
+            })
+            .sum();
+        Ok(count)
+    }
+
+    /// Index of the region holding `addr` and the offset of `addr` in it.
+    pub fn get_region_from_address(&self, addr: u64) -> io::Result<(usize, usize)> {
+        if let Some(res) = self.region_cache.get(addr) {
+            return Ok(res);
+        }
+        let not_found = || Error::new(io::ErrorKind::InvalidInput, "Address not found in any memory region");
+        // the last region starting at or before addr
+        let index = self.regions.partition_point(|region| region.start <= addr).checked_sub(1).ok_or_else(not_found)?;
+        let offset = addr - self.regions[index].start;
+        if offset >= self.regions[index].size as u64 {
+            return Err(not_found());
         }
+        self.region_cache.put(addr, (index, offset as usize));
+        Ok((index, offset as usize))
     }
 
+    /// Reads from the synced regions, syncing the one holding `addr` first if it is not yet.
     pub fn read_cache(&self, addr: u64, size: usize) -> io::Result<MemoryRegion> {
         let (index, offset) = self.get_region_from_address(addr)?;
-        self.regions.get(index).unwrap().read_bytes(offset, size)
+        let region = &self.regions[index];
+        self.region_data(region)?;
+        region.read_bytes(offset, size)
     }
 
+    /// The data of `region`, one of the regions of the process, read from the source on the
+    /// first access. Regions that cannot be read are tried again on the next one.
+    pub fn region_data<'a>(&self, region: &'a MemoryRegion) -> io::Result<&'a [u8]> {
+        if let Some(data) = region.data.get() {
+            return Ok(data);
+        }
+        let source = self.source.as_ref().ok_or_else(not_synced)?;
+        let mut data = vec![0; region.size];
+        read_chunked(|addr, buf| source.read(addr, buf), region.start, &mut data)?;
+        Ok(region.data.get_or_init(|| data))
+    }
+
+    /// Syncs the regions not synced yet, in parallel, leaving out the ones that cannot be read.
+    pub fn sync_remaining_regions(&self) {
+        self.regions.par_iter().for_each(|region| {
+            let _ = self.region_data(region);
+        });
+    }
+
+    /// Reads running processes fresh, the others from the synced regions and then their source.
+    /// Reads from the source running into an unreadable page fail with a [`PartialRead`] holding
+    /// the bytes before it.
     pub fn read_memory(&self, addr: u64, size: usize) -> io::Result<MemoryRegion> {
-        match self.handle {
-            ProcessHandle::Live(handle) => unsafe {
-                let mut data = vec![0; size];
-                if ReadProcessMemory(
-                    handle as HANDLE,
-                    addr as LPVOID,
-                    data.as_mut_ptr() as LPVOID,
-                    size,
-                    NULL as *mut _,
-                ) == TRUE
-                {
-                    Ok(MemoryRegion {
-                        start: addr,
-                        size,
-                        data,
-                        handle: self.handle,
-                    })
-                } else {
-                    Err(Error::last_os_error())
-                }
-            },
-            ProcessHandle::File => {
-                todo!("File reading not implemented yet")
+        let read = |source: &Arc<dyn MemorySource>| {
+            let mut data = vec![0; size];
+            read_retrying(source, addr, &mut data)?;
+            Ok(MemoryRegion {
+                start: addr,
+                size,
+                data: data.into(),
+                handle: self.handle.clone(),
+                protection: 0,
+                kind: RegionKind::Unknown,
+            })
+        };
+        match &self.source {
+            Some(source) if matches!(self.handle, ProcessHandle::Live(_)) => read(source),
+            Some(source) => self.read_cache(addr, size).or_else(|_| read(source)),
+            None if self.regions.is_empty() => Err(Error::other("No process opened.")),
+            // dumps and processes built from regions only have the memory they were given
+            None => self.read_cache(addr, size),
+        }
+    }
+
+    /// Reads a pointer of the width of the process at `addr`.
+    pub fn read_pointer(&self, addr: u64) -> io::Result<u64> {
+        let width = self.pointer_width.size();
+        let mut bytes = [0u8; 8];
+        bytes[..width].copy_from_slice(&self.read_memory(addr, width)?.bytes()[..width]);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Follows the pointer path `[[base] + offsets[0]] + offsets[1] ...`, where every hop reads
+    /// the pointer at the address so far and adds its offset, and returns the final address.
+    /// Fails at the first hop whose pointer cannot be read, is null or is not aligned, so values
+    /// found once can be read again without scanning as long as their path holds.
+    pub fn resolve_chain(&self, base: u64, offsets: &[i64]) -> io::Result<u64> {
+        offsets.iter().enumerate().try_fold(base, |addr, (hop, &offset)| {
+            let invalid = |what: String| Error::new(io::ErrorKind::InvalidData, format!("hop {hop}: {what}"));
+            let pointer = self
+                .read_pointer(addr)
+                .map_err(|error| invalid(format!("cannot read 0x{addr:X}: {error}")))?;
+            if pointer == 0 {
+                return Err(invalid(format!("null pointer at 0x{addr:X}")));
+            }
+            if pointer % self.pointer_width.size() as u64 != 0 {
+                return Err(invalid(format!("misaligned pointer 0x{pointer:X} at 0x{addr:X}")));
+            }
+            Ok(pointer.wrapping_add_signed(offset))
+        })
+    }
+}
+
+/// Fills the data of `regions` from `source`, dropping the ones that cannot be read.
+fn read_regions(source: &Arc<dyn MemorySource>, regions: Vec<MemoryRegion>) -> Vec<MemoryRegion> {
+    regions
+        .into_par_iter()
+        .filter_map(|mut region| {
+            let mut data = vec![0; region.size];
+            read_chunked(|addr, buf| source.read(addr, buf), region.start, &mut data).ok()?;
+            region.data = data.into();
+            Some(region)
+        }).collect()
+}
+
+/// Leaves out the regions none of whose pages are resident, keeping the ones where it is not known.
+fn drop_paged_out(source: &Arc<dyn MemorySource>, regions: Vec<MemoryRegion>) -> Vec<MemoryRegion> {
+    let count = regions.len();
+    let regions: Vec<_> = regions
+        .into_par_iter()
+        .filter(|region| source.resident_pages(region.start, region.size).map_or(true, |pages| pages.contains(&true)))
+        .collect();
+    debug!("skipped {} paged out regions", count - regions.len());
+    regions
+}
+
+/// Fills `buf` from `source`, and where that fails, reads as much of its start as it can with
+/// smaller and smaller reads. Fails with a [`PartialRead`] then, or with the first error where
+/// not even the first page can be read.
+fn read_retrying(source: &Arc<dyn MemorySource>, addr: u64, buf: &mut [u8]) -> io::Result<()> {
+    let mut read = 0;
+    let mut size = buf.len();
+    let mut failure = None;
+    while read < buf.len() {
+        let start = addr + read as u64;
+        let end = read + size.min(buf.len() - read);
+        let error = match source.read(start, &mut buf[read..end]) {
+            Ok(()) => {
+                read = end;
+                continue;
             }
-            ProcessHandle::None => Err(Error::new(io::ErrorKind::Other, "No process opened.")),
+            Err(error) => error,
+        };
+        // stopped right before the unreadable page
+        if let Some(partial) = PartialRead::of(&error) {
+            read += partial.data.len();
+            failure.get_or_insert(error);
+            break;
         }
+        failure.get_or_insert(error);
+        // the page at `start` cannot be read
+        let to_page_end = PAGE_SIZE - (start % PAGE_SIZE as u64) as usize;
+        if size <= to_page_end {
+            break;
+        }
+        size = (size / 2).max(to_page_end);
+    }
+    match failure {
+        _ if read == buf.len() => Ok(()),
+        Some(error) if read == 0 => Err(error),
+        _ => Err(PartialRead::new(addr, buf.len(), buf[..read].to_vec()).into()),
+    }
+}
+
+/// Fills `buf` with the memory at `addr` with `read`, in chunks read in parallel for large buffers.
+fn read_chunked<R>(read: R, addr: u64, buf: &mut [u8]) -> io::Result<()>
+where
+    R: Fn(u64, &mut [u8]) -> io::Result<()> + Sync,
+{
+    if buf.len() <= SYNC_CHUNK_SIZE {
+        return read(addr, buf);
     }
+    buf.par_chunks_mut(SYNC_CHUNK_SIZE)
+        .enumerate()
+        .try_for_each(|(index, chunk)| read(addr + (index * SYNC_CHUNK_SIZE) as u64, chunk))
 }
 
-#[profiling::function]
-unsafe extern "system" fn list_processes_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
-    let processes = &mut *(lparam as *mut Vec<Process>);
-
-    // get the process id
-    let mut raw_pid: DWORD = 0;
-    GetWindowThreadProcessId(hwnd, &mut raw_pid);
-    if raw_pid == 0 {
-        return TRUE;
-    }
-    // get the window title
-    let title_len: u32 = GetWindowTextLengthW(hwnd) as u32;
-    if title_len == 0 {
-        return TRUE;
-    }
-    let mut raw_title: Vec<u16> = vec![0; (title_len + 1) as usize];
-    GetWindowTextW(hwnd, raw_title.as_mut_ptr(), MAX_PROC_NAME_LEN as i32);
-
-    // get the process path
-    let raw_handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, FALSE, raw_pid);
-    if raw_handle == NULL {
-        return TRUE;
-    }
-    let mut raw_path: Vec<u16> = vec![0; MAX_PROC_PATH_LEN];
-    let path_len: u32 =
-        GetProcessImageFileNameW(raw_handle, raw_path.as_mut_ptr(), raw_path.len() as u32);
-    if path_len != 0 {
-        raw_path.set_len(path_len as usize + 1);
-    } else {
-        return TRUE;
-    }
-    processes.push(Process {
-        pid: raw_pid,
-        path: OsString::from_wide(&raw_path[..path_len as usize])
-            .to_string_lossy()
-            .into_owned(),
-        title: OsString::from_wide(&raw_title[..title_len as usize])
-            .to_string_lossy()
-            .into_owned(),
-        regions: vec![],
-        handle: ProcessHandle::Live(raw_handle as u32),
-    });
-    TRUE
+fn not_synced() -> Error {
+    Error::new(io::ErrorKind::InvalidInput, "Region is not synced")
 }
 
-#[profiling::function]
 pub fn list_processes() -> io::Result<Vec<Process>> {
-    let mut processes = Vec::<Process>::with_capacity(MAX_PROC_NUM);
-    unsafe {
-        EnumWindows(
-            Some(list_processes_callback),
-            &mut processes as *mut Vec<Process> as isize,
-        );
-    }
-    Ok(processes)
+    platform::list_processes()
 }
@@ -1,4 +1,4 @@
-use std::mem::ManuallyDrop;
+use std::mem::{offset_of, size_of, MaybeUninit};
 use libc::*;
 
 macro_rules! rpointer {
@@ -29,7 +29,9 @@ pub struct CPyVarObject {
 #[repr(C)]
 pub struct CPyTypeObject {
     pub ob_base: CPyVarObject,
-    pub tp_name: rpointer![c_char]
+    pub tp_name: rpointer![c_char],
+    pub tp_basicsize: ssize_t,
+    pub tp_itemsize: ssize_t
 }
 
 #[repr(C)]
@@ -43,7 +45,7 @@ pub struct CPyByteArrayExtra {
 #[repr(C)]
 pub struct CPyStringObject {
     pub ob_base: CPyVarObject,
-    pub ob_shash: c_long,
+    pub ob_shash: i32,
     pub ob_sstate: c_int,
     pub ob_sval: rarray![c_char]
 }
@@ -77,7 +79,7 @@ pub struct CPyFloatObject {
 #[repr(C)]
 pub struct CPyIntObject {
     pub ob_base: CPyObject,
-    pub ob_ival: c_long
+    pub ob_ival: i32
 }
 
 type CPyBoolObject = CPyIntObject;
@@ -97,7 +99,7 @@ pub struct CPyLongObject {
 
 #[repr(C)]
 pub struct CPySetEntry {
-    pub hash: c_long,
+    pub hash: i32,
     pub key: rpyobject
 }
 
@@ -120,8 +122,8 @@ pub struct CPyTupleObject {
 pub struct CPyUnicodeObject {
     pub ob_base: CPyObject,
     pub length: ssize_t,
-    pub str: rpointer![wchar_t],
-    pub hash: c_long,
+    pub str: rpointer![u16],
+    pub hash: i32,
     pub defenc: rpyobject
 }
 
@@ -130,3 +132,339 @@ pub struct CPyCustomObject {
     pub ob_base: CPyObject,
     pub attributes: rpointer![CPyDictObject]
 }
+
+// The offsets of the 64-bit Windows client, whatever the host's `long` and `wchar_t` are.
+const _: () = assert!(offset_of!(CPyStringObject, ob_sval) == 32);
+const _: () = assert!(offset_of!(CPyIntObject, ob_ival) == 16);
+const _: () = assert!(offset_of!(CPyUnicodeObject, defenc) == 40);
+
+// Python 3.6 to 3.10, where they differ from 2.7. Python 3 `int` and `bool` use the layout of
+// 2.7 `long`, `bytes` the one of 2.7 `str`.
+
+#[repr(C)]
+pub struct CPy3DictObject {
+    pub ob_base: CPyObject,
+    pub ma_used: ssize_t,
+    pub ma_version_tag: u64,
+    pub ma_keys: rpointer![CPy3DictKeysObject],
+    /// Values of split-table dicts, NULL for combined tables.
+    pub ma_values: rpointer![rpyobject]
+}
+
+/// Followed by `dk_size` indices of 1, 2, 4 or 8 bytes and the `CPyDictEntry` entries.
+#[repr(C)]
+pub struct CPy3DictKeysObject {
+    pub dk_refcnt: ssize_t,
+    pub dk_size: ssize_t,
+    pub dk_lookup: rpointer![],
+    pub dk_usable: ssize_t,
+    pub dk_nentries: ssize_t
+}
+
+/// PEP 393 string, the characters of compact ASCII strings follow right after it.
+#[repr(C)]
+pub struct CPyASCIIObject {
+    pub ob_base: CPyObject,
+    pub length: ssize_t,
+    pub hash: ssize_t,
+    /// Bit fields: interned (2), kind (3), compact (1), ascii (1), ready (1).
+    pub state: u32,
+    pub wstr: rpointer![u16]
+}
+
+/// The characters of other compact strings follow right after it.
+#[repr(C)]
+pub struct CPyCompactUnicodeObject {
+    pub _base: CPyASCIIObject,
+    pub utf8_length: ssize_t,
+    pub utf8: rpointer![c_char],
+    pub wstr_length: ssize_t
+}
+
+#[repr(C)]
+pub struct CPy3UnicodeObject {
+    pub _base: CPyCompactUnicodeObject,
+    pub data: rpointer![]
+}
+
+/// How dicts keep their entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DictLayout {
+    /// One open addressing table of `CPyDictEntry` (2.7).
+    Table,
+    /// Indices followed by the entries in insertion order, `CPy3DictKeysObject` (3.6+).
+    Compact,
+}
+
+/// How `str` objects keep their text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrLayout {
+    /// Bytes after the header, `CPyStringObject` (2.7, and 3.x `bytes`).
+    Bytes,
+    /// PEP 393 strings of 1, 2 or 4 byte characters (3.3+).
+    Pep393,
+}
+
+/// How `int` and `bool` objects keep their value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntLayout {
+    /// A C long, `CPyIntObject` (2.7).
+    Fixed,
+    /// 30-bit digits, `CPyLongObject` (2.7 `long`, 3.x `int`).
+    Digits,
+}
+
+/// Width of pointers and `Py_ssize_t` in the target. The structs above have the 64-bit layouts,
+/// the ones of 32-bit targets are widened into them when read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PointerWidth {
+    Bits32,
+    #[default]
+    Bits64,
+}
+
+/// Kinds of the fields of the structs above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Pointers, zero extended when widened.
+    Ptr,
+    /// `Py_ssize_t`, sign extended when widened.
+    Ssize,
+    /// C `long`, 32 bits in Windows targets either way.
+    Long,
+    /// C `int` and other 32-bit integers.
+    Int,
+    /// Doubles and 64-bit integers.
+    Quad,
+}
+
+impl Field {
+    fn size(self, width: PointerWidth) -> usize {
+        match (self, width) {
+            (Field::Int | Field::Long, _) | (Field::Ptr | Field::Ssize, PointerWidth::Bits32) => 4,
+            (Field::Quad, _) | (Field::Ptr | Field::Ssize, PointerWidth::Bits64) => 8,
+        }
+    }
+}
+
+/// Structs with their fields in order, without the variable length items at their end.
+pub trait PyStruct: Sized {
+    const FIELDS: &'static [Field];
+}
+
+macro_rules! py_struct_fields {
+    ($($T:ty => [$($field:ident),*];)*) => {
+        $(impl PyStruct for $T {
+            const FIELDS: &'static [Field] = &[$(Field::$field),*];
+        })*
+    };
+}
+
+py_struct_fields! {
+    u64 => [Ptr];
+    CPyObject => [Ssize, Ptr];
+    CPyVarObject => [Ssize, Ptr, Ssize];
+    CPyTypeObject => [Ssize, Ptr, Ssize, Ptr, Ssize, Ssize];
+    CPyStringObject => [Ssize, Ptr, Ssize, Long, Int];
+    CPyDictEntry => [Ssize, Ptr, Ptr];
+    CPyDictObject => [Ssize, Ptr, Ssize, Ssize, Ssize, Ptr, Ptr];
+    CPyFloatObject => [Ssize, Ptr, Quad];
+    CPyIntObject => [Ssize, Ptr, Long];
+    CPyListObject => [Ssize, Ptr, Ssize, Ptr, Ssize];
+    CPyLongObject => [Ssize, Ptr, Ssize];
+    CPySetObject => [Ssize, Ptr, Ssize, Ssize, Ssize, Ptr];
+    CPyTupleObject => [Ssize, Ptr, Ssize];
+    CPyUnicodeObject => [Ssize, Ptr, Ssize, Ptr, Long, Ptr];
+    CPyCustomObject => [Ssize, Ptr, Ptr];
+    CPy3DictObject => [Ssize, Ptr, Ssize, Quad, Ptr, Ptr];
+    CPy3DictKeysObject => [Ssize, Ssize, Ptr, Ssize, Ssize];
+    CPyASCIIObject => [Ssize, Ptr, Ssize, Ssize, Int, Ptr];
+    CPyCompactUnicodeObject => [Ssize, Ptr, Ssize, Ssize, Int, Ptr, Ssize, Ptr, Ssize];
+    CPy3UnicodeObject => [Ssize, Ptr, Ssize, Ssize, Int, Ptr, Ssize, Ptr, Ssize, Ptr];
+}
+
+impl PointerWidth {
+    pub fn size(self) -> usize {
+        match self {
+            PointerWidth::Bits32 => 4,
+            PointerWidth::Bits64 => 8,
+        }
+    }
+
+    /// End of the last of `fields` and the size of the struct they make up, which is padded to
+    /// the largest field.
+    fn extent(self, fields: &[Field]) -> (usize, usize) {
+        let (mut end, mut align) = (0usize, 1);
+        for field in fields {
+            let size = field.size(self);
+            end = end.next_multiple_of(size) + size;
+            align = align.max(size);
+        }
+        (end, end.next_multiple_of(align))
+    }
+
+    /// Size of `T` in the target.
+    pub fn size_of<T: PyStruct>(self) -> usize {
+        match self {
+            PointerWidth::Bits32 => self.extent(T::FIELDS).1,
+            PointerWidth::Bits64 => size_of::<T>(),
+        }
+    }
+
+    /// Copies `fields` from their offsets in the target in `raw` to their 64-bit ones in `wide`,
+    /// None if `raw` is too short.
+    fn widen_fields(self, fields: &[Field], raw: &[u8], wide: &mut [u8]) -> Option<()> {
+        let (mut from, mut to) = (0usize, 0usize);
+        for &field in fields {
+            let (narrow, full) = (field.size(self), field.size(PointerWidth::Bits64));
+            from = from.next_multiple_of(narrow);
+            to = to.next_multiple_of(full);
+            let bytes = raw.get(from..from + narrow)?;
+            let value = match (narrow, field) {
+                (4, Field::Ssize | Field::Long) => i32::from_le_bytes(bytes.try_into().ok()?) as i64 as u64,
+                (4, _) => u32::from_le_bytes(bytes.try_into().ok()?) as u64,
+                _ => u64::from_le_bytes(bytes.try_into().ok()?),
+            };
+            wide[to..to + full].copy_from_slice(&value.to_le_bytes()[..full]);
+            from += narrow;
+            to += full;
+        }
+        Some(())
+    }
+
+    /// Reads a `T` laid out as in the target from the start of `raw`.
+    pub fn read<T: PyStruct>(self, raw: &[u8]) -> Option<T> {
+        if raw.len() < self.size_of::<T>() {
+            return None;
+        }
+        if self == PointerWidth::Bits64 {
+            return Some(unsafe { std::ptr::read_unaligned(raw.as_ptr() as *const T) });
+        }
+        let mut value = MaybeUninit::<T>::zeroed();
+        // the structs only hold integers and floats, for which zeroes are valid
+        let wide = unsafe { std::slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8, size_of::<T>()) };
+        self.widen_fields(T::FIELDS, raw, wide)?;
+        Some(unsafe { value.assume_init() })
+    }
+
+    /// Widens an array of `T` read from the target, `raw` as it is for 64-bit targets.
+    pub fn widen<T: PyStruct>(self, raw: &[u8]) -> Vec<u8> {
+        if self == PointerWidth::Bits64 {
+            return raw.to_vec();
+        }
+        let count = raw.len() / self.size_of::<T>();
+        let mut wide = vec![0; count * size_of::<T>()];
+        for (item, chunk) in raw.chunks_exact(self.size_of::<T>()).zip(wide.chunks_exact_mut(size_of::<T>())) {
+            self.widen_fields(T::FIELDS, item, chunk);
+        }
+        wide
+    }
+}
+
+/// Variable length items at the end of objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Items {
+    None,
+    Bytes,
+    Pointers,
+    /// 30-bit `u32` digits in 64-bit targets, 15-bit `u16` ones in 32-bit targets.
+    Digits,
+}
+
+impl Items {
+    fn size(self, width: PointerWidth) -> usize {
+        match (self, width) {
+            (Items::None, _) => 0,
+            (Items::Bytes, _) => 1,
+            (Items::Pointers, _) => width.size(),
+            (Items::Digits, PointerWidth::Bits32) => size_of::<u16>(),
+            (Items::Digits, PointerWidth::Bits64) => size_of::<u32>(),
+        }
+    }
+}
+
+/// The layouts of the objects that differ between interpreter versions, picked by
+/// [`EVEProcess::init`](crate::eve_process::eve_process::EVEProcess::init).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PyLayout {
+    pub dict: DictLayout,
+    pub str: StrLayout,
+    pub int: IntLayout,
+    pub pointer: PointerWidth,
+}
+
+impl PyLayout {
+    pub const PY2: PyLayout = PyLayout {
+        dict: DictLayout::Table,
+        str: StrLayout::Bytes,
+        int: IntLayout::Fixed,
+        pointer: PointerWidth::Bits64,
+    };
+    pub const PY3: PyLayout = PyLayout {
+        dict: DictLayout::Compact,
+        str: StrLayout::Pep393,
+        int: IntLayout::Digits,
+        pointer: PointerWidth::Bits64,
+    };
+
+    /// Fields, 64-bit size up to the items and items of objects of type `tp_name`, the ones of a
+    /// custom object for types without their own layout.
+    fn object_fields(&self, tp_name: &str) -> (&'static [Field], usize, Items) {
+        fn fixed<T: PyStruct>() -> (&'static [Field], usize, Items) {
+            (T::FIELDS, size_of::<T>(), Items::None)
+        }
+        match tp_name {
+            "str" if self.str == StrLayout::Pep393 => fixed::<CPy3UnicodeObject>(),
+            "str" | "bytes" => (CPyStringObject::FIELDS, offset_of!(CPyStringObject, ob_sval), Items::Bytes),
+            "unicode" => fixed::<CPyUnicodeObject>(),
+            "int" | "bool" if self.int == IntLayout::Fixed => fixed::<CPyIntObject>(),
+            "int" | "bool" | "long" => (CPyLongObject::FIELDS, offset_of!(CPyLongObject, ob_digit), Items::Digits),
+            "float" => fixed::<CPyFloatObject>(),
+            "dict" => match self.dict {
+                DictLayout::Table => fixed::<CPyDictObject>(),
+                DictLayout::Compact => fixed::<CPy3DictObject>(),
+            },
+            "list" => fixed::<CPyListObject>(),
+            "tuple" => (CPyTupleObject::FIELDS, offset_of!(CPyTupleObject, ob_item), Items::Pointers),
+            "NoneType" => fixed::<CPyObject>(),
+            _ => fixed::<CPyCustomObject>(),
+        }
+    }
+
+    /// Bytes to read for an object of type `tp_name` holding `items` items or characters.
+    pub fn object_size(&self, tp_name: &str, items: usize) -> usize {
+        let (fields, wide_size, kind) = self.object_fields(tp_name);
+        let (end, size) = match self.pointer {
+            PointerWidth::Bits32 => self.pointer.extent(fields),
+            PointerWidth::Bits64 => (wide_size, wide_size),
+        };
+        match kind {
+            Items::None => size,
+            _ => end + items * kind.size(self.pointer),
+        }
+    }
+
+    /// Widens an object of type `tp_name` read from the target, None if `raw` is too short. Digits
+    /// are left as they are.
+    pub fn widen_object(&self, tp_name: &str, raw: &[u8]) -> Option<Vec<u8>> {
+        if self.pointer == PointerWidth::Bits64 {
+            return Some(raw.to_vec());
+        }
+        let (fields, wide_size, kind) = self.object_fields(tp_name);
+        let mut wide = vec![0; wide_size];
+        self.pointer.widen_fields(fields, raw, &mut wide)?;
+        let items = raw.get(self.pointer.extent(fields).0..).unwrap_or_default();
+        match kind {
+            Items::None => {}
+            Items::Bytes | Items::Digits => wide.extend_from_slice(items),
+            Items::Pointers => wide.extend_from_slice(&self.pointer.widen::<u64>(items)),
+        }
+        Some(wide)
+    }
+}
+
+impl Default for PyLayout {
+    fn default() -> Self {
+        PyLayout::PY2
+    }
+}
@@ -1,15 +1,196 @@
 use std::collections::HashMap;
-use std::ffi::OsString;
-use std::fmt::Formatter;
 use std::io;
-use std::os::windows::prelude::OsStringExt;
-use libc::{abs, c_char};
+use std::mem::offset_of;
 use crate::eve_process::eve_process::{PyObjectNode, EVEProcess};
-use crate::eve_process::py_struct::{CPyDictEntry, CPyDictObject, CPyFloatObject, CPyIntObject, CPyListObject, CPyLongObject, CPyObject, CPyStringObject, CPyTypeObject, CPyUnicodeObject};
+use crate::eve_process::process::MemoryRegion;
+use crate::eve_process::source::PartialRead;
+use crate::eve_process::py_struct::{CPy3DictKeysObject, CPy3DictObject, CPy3UnicodeObject, CPyASCIIObject, CPyCompactUnicodeObject, CPyCustomObject, CPyDictEntry, CPyDictObject, CPyFloatObject, CPyIntObject, CPyListObject, CPyLongObject, CPyStringObject, CPyTupleObject, CPyTypeObject, CPyUnicodeObject, CPyVarObject, DictLayout, IntLayout, PointerWidth, PyStruct, StrLayout};
 
+/// Upper bound for the length of a `tp_name` string.
+const MAX_TP_NAME_LEN: usize = 128;
+/// Upper bound for the number of items read from a single container,
+/// anything larger is most likely garbage from a stale pointer.
+const MAX_CONTAINER_LEN: usize = 1 << 16;
+/// Upper bound for the length of a single string object in bytes.
+const MAX_STR_LEN: usize = 1 << 20;
 
 impl EVEProcess {
-     pub fn parse_dict(&self, node: &PyObjectNode) -> io::Result<HashMap<String, PyObjectNode>> {
+    /// Reads a NUL-terminated C string from the target, at most `max_len` bytes.
+    pub fn read_c_string(&self, addr: u64, max_len: usize) -> io::Result<String> {
+        let mut bytes = Vec::new();
+        let mut chunk = 32;
+        while bytes.len() < max_len {
+            // the string may end right before an unreadable page
+            let data = match self.process.read_memory(addr + bytes.len() as u64, chunk) {
+                Ok(region) => region.data.into_inner().unwrap_or_default(),
+                Err(e) => match PartialRead::of(&e) {
+                    Some(partial) => partial.data.clone(),
+                    None if chunk > 1 => {
+                        chunk /= 2;
+                        continue;
+                    }
+                    None => return Err(e),
+                },
+            };
+            if let Some(end) = data.iter().position(|&b| b == 0) {
+                bytes.extend_from_slice(&data[..end]);
+                return Ok(String::from_utf8_lossy(&bytes).into_owned());
+            }
+            bytes.extend_from_slice(&data);
+        }
+        Err(io::Error::new(io::ErrorKind::InvalidData, "C string too long"))
+    }
+
+    /// Reads `count` structs at `addr`, widened to the layouts of `py_struct` for 32-bit targets.
+    pub fn read_structs<T: PyStruct>(&self, addr: u64, count: usize) -> io::Result<MemoryRegion> {
+        let width = self.layout.pointer;
+        let region = self.process.read_memory(addr, count * width.size_of::<T>())?;
+        if width == PointerWidth::Bits64 {
+            return Ok(region);
+        }
+        let data = width.widen::<T>(region.bytes());
+        Ok(MemoryRegion { size: data.len(), data: data.into(), ..region })
+    }
+
+    /// Resolves the `tp_name` of the type object at `type_addr`, cached per process.
+    pub fn read_type_name(&self, type_addr: u64) -> io::Result<String> {
+        if let Some(tp_name) = self.tp_names.lock().unwrap().get(&type_addr) {
+            return Ok(tp_name.clone());
+        }
+        let tp_region = self.read_structs::<CPyTypeObject>(type_addr, 1)?;
+        let tp_view = tp_region.view_bytes_as::<CPyTypeObject>(0, None)?;
+        let tp_name = self.read_c_string(tp_view.tp_name, MAX_TP_NAME_LEN)?;
+        self.tp_names.lock().unwrap().insert(type_addr, tp_name.clone());
+        Ok(tp_name)
+    }
+
+    /// Reads the python object at `addr` with a region large enough for the parser of its type.
+    pub fn read_object(&self, addr: u64) -> io::Result<PyObjectNode> {
+        if addr == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "NULL object pointer"));
+        }
+        let header = self.read_structs::<CPyVarObject>(addr, 1)?;
+        let header_view = header.view_bytes_as::<CPyVarObject>(0, None)?;
+        let ob_type = header_view.ob_type;
+        let ob_size = header_view.ob_size.unsigned_abs();
+        let tp_name = self.read_type_name(ob_type)?;
+        let items = match tp_name.as_str() {
+            "str" | "bytes" => ob_size.min(MAX_STR_LEN),
+            _ => ob_size.min(MAX_CONTAINER_LEN),
+        };
+        let size = self.layout.object_size(&tp_name, items);
+        let region = match self.layout.pointer {
+            PointerWidth::Bits64 if size <= header.size => header.read_bytes(0, size)?,
+            PointerWidth::Bits64 => self.process.read_memory(addr, size)?,
+            PointerWidth::Bits32 => {
+                let raw = self.process.read_memory(addr, size)?;
+                let data = self.layout.widen_object(&tp_name, raw.bytes()).ok_or_else(|| io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("`{}` at 0x{:X} is truncated", tp_name, addr)
+                ))?;
+                MemoryRegion { size: data.len(), data: data.into(), ..raw }
+            }
+        };
+        Ok(PyObjectNode {
+            base_addr: addr,
+            region,
+            ob_type: self
+                .objects
+                .get(&ob_type)
+                .map(std::sync::Arc::downgrade)
+                .unwrap_or_default(),
+            tp_name,
+            child: Default::default(),
+        })
+    }
+
+    /// Reads the instance `__dict__` of a custom (non builtin) object.
+    pub fn read_instance_dict(&self, node: &PyObjectNode) -> io::Result<PyObjectNode> {
+        let obj_view = node.region.view_bytes_as::<CPyCustomObject>(0, None)?;
+        let dict = self.read_object(obj_view.attributes)?;
+        if dict.tp_name != "dict" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("`{}` at 0x{:X} has no instance dict", node.tp_name, node.base_addr)
+            ))
+        }
+        Ok(dict)
+    }
+
+    /// Parses a `str`, `unicode` or `bytes` key into a rust string.
+    pub fn parse_string_like(&self, node: &PyObjectNode) -> io::Result<String> {
+        match node.tp_name.as_str() {
+            "str" => self.parse_str(node),
+            "unicode" => self.parse_unicode(node),
+            "bytes" => self.parse_byte_string(node),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expect a PyObjectNode of type `str`, `unicode` or `bytes`, get `{}`", node.tp_name)
+            ))
+        }
+    }
+
+    pub fn parse_dict(&self, node: &PyObjectNode) -> io::Result<HashMap<String, PyObjectNode>> {
+        match self.layout.dict {
+            DictLayout::Table => self.parse_dict2(node),
+            DictLayout::Compact => self.parse_dict3(node),
+        }
+    }
+
+    /// Reads the key and value of a dict entry, `None` for empty and deleted entries.
+    fn parse_dict_entry(&self, key_addr: u64, value_addr: u64) -> Option<(String, PyObjectNode)> {
+        if key_addr == 0 || value_addr == 0 {
+            return None
+        }
+        let key = self.read_object(key_addr).and_then(|key_node| self.parse_string_like(&key_node)).ok()?;
+        Some((key, self.read_object(value_addr).ok()?))
+    }
+
+    fn parse_dict3(&self, node: &PyObjectNode) -> io::Result<HashMap<String, PyObjectNode>> {
+        if node.tp_name != "dict" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("parse_dict expect a PyObjectNode of type `dict`, get `{}`", node.tp_name)
+            ))
+        }
+        let dict_view = node.region.view_bytes_as::<CPy3DictObject>(0, None)?;
+        let keys_region = self.read_structs::<CPy3DictKeysObject>(dict_view.ma_keys, 1)?;
+        let keys_view = keys_region.view_bytes_as::<CPy3DictKeysObject>(0, None)?;
+        let dk_size = keys_view.dk_size;
+        let nentries = keys_view.dk_nentries;
+        if dk_size <= 0 || dk_size as usize > MAX_CONTAINER_LEN || nentries < 0 || nentries > dk_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("parse_dict got invalid dict keys ({}/{}) at 0x{:X}", nentries, dk_size, node.base_addr)
+            ))
+        }
+        let (dk_size, nentries) = (dk_size as usize, nentries as usize);
+        let index_width = match dk_size {
+            0..=0xff => 1,
+            0x100..=0xffff => 2,
+            _ => 4,
+        };
+        let keys_size = self.layout.pointer.size_of::<CPy3DictKeysObject>();
+        let entries_addr = dict_view.ma_keys + (keys_size + dk_size * index_width) as u64;
+        let entries = self.read_structs::<CPyDictEntry>(entries_addr, nentries)?;
+        // split-table dicts share the keys and keep the values in their own array
+        let values = match dict_view.ma_values {
+            0 => None,
+            values_addr => Some(self.read_structs::<u64>(values_addr, nentries)?),
+        };
+        let mut result = HashMap::with_capacity(dict_view.ma_used.max(0) as usize);
+        for i in 0..nentries {
+            let entry_view = entries.view_bytes_as::<CPyDictEntry>(i * size_of::<CPyDictEntry>(), None)?;
+            let value_addr = match &values {
+                Some(values) => *values.view_bytes_as::<u64>(i * size_of::<u64>(), None)?,
+                None => entry_view.me_value,
+            };
+            result.extend(self.parse_dict_entry(entry_view.me_key, value_addr));
+        }
+        Ok(result)
+    }
+
+     fn parse_dict2(&self, node: &PyObjectNode) -> io::Result<HashMap<String, PyObjectNode>> {
          if node.tp_name != "dict" {
              return Err(io::Error::new(
                  io::ErrorKind::InvalidInput,
@@ -19,57 +200,22 @@ impl EVEProcess {
          let attr_dict_view = node.region.view_bytes_as::<CPyDictObject>(0, None)?;
          let mask = attr_dict_view.ma_mask;
          let ma_table = attr_dict_view.ma_table;
+         if mask < 0 || mask as usize >= MAX_CONTAINER_LEN {
+             return Err(io::Error::new(
+                 io::ErrorKind::InvalidData,
+                 format!("parse_dict got an invalid ma_mask {} at 0x{:X}", mask, node.base_addr)
+             ))
+         }
 
-         // let mut result = HashMap::new();
-
-         for i in 0..mask+1 {
-             if let Ok(entry_region) = self.process.read_memory(
-                     ma_table + (i as usize * size_of::<CPyDictEntry>()) as u64,
-                     size_of::<CPyDictEntry>())
-             {
-                 if let Ok(entry_view) = entry_region.view_bytes_as::<CPyDictEntry>(0, None) {
-                     let me_key_addr = entry_view.me_key;
-                     let me_value_addr = entry_view.me_value;
-                     let key: String;
-                     if me_key_addr != 0 && me_value_addr != 0 {
-                         if let Ok(key_region) = self.process.read_memory(me_key_addr, 8) {
-                             if let Ok(key_view) = key_region.view_bytes_as::<CPyObject>(0, None) {
-                                 if let Ok(tp_region) = self.process.read_memory(key_view.ob_type, size_of::<CPyTypeObject>()) {
-                                     if let Ok(tp_view) = tp_region.view_bytes_as::<CPyTypeObject>(0, None) {
-                                         if let Ok(tp_name_region) = self.process.read_memory(tp_view.tp_name, 8) {
-                                             if tp_name_region.data[..3] == *b"str" {
-                                                 key = self.parse_str(&PyObjectNode {
-                                                     base_addr: me_key_addr,
-                                                     region: key_region,
-                                                     ob_type: Default::default(),
-                                                     tp_name: "str".to_string(),
-                                                     child: Default::default()
-                                                 })?;
-                                             }
-                                             else if tp_name_region.data[..7] == *b"unicode" {
-                                                 key = self.parse_unicode(&PyObjectNode {
-                                                     base_addr: me_key_addr,
-                                                     region: key_region,
-                                                     ob_type: Default::default(),
-                                                     tp_name: "unicode".to_string(),
-                                                     child: Default::default()
-                                                 })?;
-                                             } else {
-                                                 continue
-                                             }
-                                         }
-                                     }
-                                 }
-                             }
-                         }
-                     }
-
-                 }
-             }
+         let table_region = self.read_structs::<CPyDictEntry>(ma_table, mask as usize + 1)?;
+         let mut result = HashMap::with_capacity(attr_dict_view.ma_used.max(0) as usize);
+         for i in 0..mask as usize + 1 {
+             let entry_view = table_region.view_bytes_as::<CPyDictEntry>(i * size_of::<CPyDictEntry>(), None)?;
+             result.extend(self.parse_dict_entry(entry_view.me_key, entry_view.me_value));
          }
-         todo!()
+         Ok(result)
      }
-    
+
     pub fn parse_list(&self, node: &PyObjectNode) -> io::Result<Vec<PyObjectNode>> {
         if node.tp_name != "list" {
             return Err(io::Error::new(
@@ -79,17 +225,88 @@ impl EVEProcess {
         }
         let list_view = node.region.view_bytes_as::<CPyListObject>(0, None)?;
         let ob_size = list_view.ob_base.ob_size;
-        let item_addr_array = list_view.ob_item;
-        // let mut result = Vec::with_capacity(ob_size as usize);
-        // node.region.view_bytes_as_vec_of::<u64>(
-        //     (list_view.ob_item.as_ptr() as u64 - node.base_addr) as usize,
-        //     (ob_size as u64 * size_of::<u64>() as u64) as usize
-        // )?.into_iter().enumerate().map(
-        //     |(i, d)| (*d as i64) * 2_i64.pow(30_u32 * i as u32)
-        // ).reduce(|acc, x| acc + x).ok_or(
-        //     io::Error::new(io::ErrorKind::InvalidInput, "parse_long failed")
-        // )
-        todo!()
+        if ob_size < 0 || ob_size as usize > MAX_CONTAINER_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("parse_list got an invalid ob_size {} at 0x{:X}", ob_size, node.base_addr)
+            ))
+        }
+        if ob_size == 0 {
+            return Ok(vec![])
+        }
+        // unlike tuples, list items live in a separately allocated array
+        let item_addr_array = list_view.ob_item[0];
+        let items_region = self.read_structs::<u64>(item_addr_array, ob_size as usize)?;
+        self.parse_item_array(&items_region, 0, ob_size as usize)
+    }
+
+    pub fn parse_tuple(&self, node: &PyObjectNode) -> io::Result<Vec<PyObjectNode>> {
+        if node.tp_name != "tuple" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("parse_tuple expect a PyObjectNode of type `tuple`, get `{}`", node.tp_name)
+            ))
+        }
+        let tuple_view = node.region.view_bytes_as::<CPyTupleObject>(0, None)?;
+        let ob_size = (tuple_view.ob_base.ob_size.max(0) as usize).min(MAX_CONTAINER_LEN);
+        self.parse_item_array(&node.region, offset_of!(CPyTupleObject, ob_item), ob_size)
+    }
+
+    /// Follows an attribute path like `children[2]._name` from the object at `addr`.
+    ///
+    /// Names are looked up in dicts and instance dicts, indexes in lists and tuples. Indexing a
+    /// `PyChildrenList` goes to its `_childrenObjects`.
+    pub fn follow(&self, addr: u64, path: &str) -> io::Result<PyObjectNode> {
+        let mut node = self.read_object(addr)?;
+        for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+            let (name, indexes) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+            if !name.is_empty() {
+                node = self.follow_attr(&node, name)?;
+            }
+            for index in indexes.split(']').filter(|index| !index.is_empty()) {
+                let index = index.trim_start_matches('[').trim().parse().map_err(|_| io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid index in `{}`", segment)
+                ))?;
+                node = self.follow_index(&node, index)?;
+            }
+        }
+        Ok(node)
+    }
+
+    fn follow_attr(&self, node: &PyObjectNode, name: &str) -> io::Result<PyObjectNode> {
+        let mut entries = if node.tp_name == "dict" {
+            self.parse_dict(node)?
+        } else {
+            self.parse_dict(&self.read_instance_dict(node)?)?
+        };
+        entries.remove(name).ok_or_else(|| io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("`{}` at 0x{:X} has no attribute `{}`", node.tp_name, node.base_addr, name)
+        ))
+    }
+
+    fn follow_index(&self, node: &PyObjectNode, index: usize) -> io::Result<PyObjectNode> {
+        let mut items = match node.tp_name.as_str() {
+            "list" => self.parse_list(node)?,
+            "tuple" => self.parse_tuple(node)?,
+            _ => self.parse_list(&self.follow_attr(node, "_childrenObjects")?)?,
+        };
+        if index >= items.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("index {} out of range for `{}` of length {}", index, node.tp_name, items.len())
+            ))
+        }
+        Ok(items.swap_remove(index))
+    }
+
+    fn parse_item_array(&self, region: &MemoryRegion, offset: usize, len: usize) -> io::Result<Vec<PyObjectNode>> {
+        Ok(region
+            .view_bytes_as_vec_of::<u64>(offset, len * size_of::<u64>())?
+            .into_iter()
+            .filter_map(|&item_addr| self.read_object(item_addr).ok())
+            .collect())
     }
 
     pub fn parse_str(&self, node: &PyObjectNode) -> io::Result<String> {
@@ -99,13 +316,19 @@ impl EVEProcess {
                 format!("parse_str expect a PyObjectNode of type `str`, get `{}`", node.tp_name)
             ))
         }
-        let str_view = node.region.view_bytes_as::<CPyStringObject>(0, None)?;
-        let str_len = str_view.ob_base.ob_size;
-        let raw_char_array = node.region.view_bytes(
-            (str_view.ob_sval.as_ptr() as u64 - node.base_addr) as usize,
-            (str_len as u64 * size_of::<c_char>() as u64) as usize
-        )?;
-        Ok(String::from_utf8_lossy(raw_char_array).to_string())
+        match self.layout.str {
+            StrLayout::Bytes => self.parse_byte_string(node),
+            StrLayout::Pep393 => self.parse_unicode3(node),
+        }
+    }
+
+    /// Decodes a 2.7 `str` or 3.x `bytes` with [`EVEProcess::str_encoding`].
+    fn parse_byte_string(&self, node: &PyObjectNode) -> io::Result<String> {
+        // the region ends after the last byte, which is shorter than `CPyStringObject` when padded
+        let ob_size = node.region.view_bytes_as::<CPyVarObject>(0, None)?.ob_size;
+        let str_len = (ob_size.max(0) as usize).min(MAX_STR_LEN);
+        let raw_char_array = node.region.view_bytes(offset_of!(CPyStringObject, ob_sval), str_len)?;
+        Ok(self.str_encoding.decode(raw_char_array))
     }
 
     pub fn parse_unicode(&self, node: &PyObjectNode) -> io::Result<String> {
@@ -116,11 +339,53 @@ impl EVEProcess {
             ))
         };
         let unicode_view = node.region.view_bytes_as::<CPyUnicodeObject>(0, None)?;
-        let str_len = unicode_view.length;
-        let raw_wchar_region = self.process.read_cache(unicode_view.str, (str_len as u64 * size_of::<u16>() as u64) as usize)?;
-        let raw_wchar_vec_view = raw_wchar_region.view_bytes_as_vec_of::<u16>(0, (str_len as u64 * size_of::<u16>() as u64) as usize)?;
+        let str_len = (unicode_view.length.max(0) as usize).min(MAX_STR_LEN);
+        if str_len == 0 {
+            return Ok(String::new())
+        }
+        let raw_wchar_region = self.process.read_memory(unicode_view.str, str_len * size_of::<u16>())?;
+        let raw_wchar_vec_view = raw_wchar_region.view_bytes_as_vec_of::<u16>(0, str_len * size_of::<u16>())?;
         let raw_wchar_vec_copy: Vec<_> = raw_wchar_vec_view.into_iter().map(|x| *x).collect();
-        Ok(OsString::from_wide(raw_wchar_vec_copy.as_slice()).to_string_lossy().into_owned())
+        // the client is a UCS-2 build, characters outside the BMP are stored as surrogate pairs
+        Ok(String::from_utf16_lossy(&raw_wchar_vec_copy))
+    }
+
+    /// Reads a PEP 393 string, stored with 1, 2 or 4 bytes per character.
+    fn parse_unicode3(&self, node: &PyObjectNode) -> io::Result<String> {
+        let ascii_view = node.region.view_bytes_as::<CPyASCIIObject>(0, None)?;
+        let length = (ascii_view.length.max(0) as usize).min(MAX_STR_LEN);
+        let state = ascii_view.state;
+        let kind = ((state >> 2) & 0b111) as usize;
+        let (compact, ascii) = (state & (1 << 5) != 0, state & (1 << 6) != 0);
+        if length == 0 {
+            return Ok(String::new())
+        }
+        if !matches!(kind, 1 | 2 | 4) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("parse_unicode3 got an invalid kind {} at 0x{:X}", kind, node.base_addr)
+            ))
+        }
+        let data_addr = match (compact, ascii) {
+            (true, true) => node.base_addr + self.layout.pointer.size_of::<CPyASCIIObject>() as u64,
+            (true, false) => node.base_addr + self.layout.pointer.size_of::<CPyCompactUnicodeObject>() as u64,
+            (false, _) => node.region.view_bytes_as::<CPy3UnicodeObject>(0, None)?.data,
+        };
+        let region = self.process.read_memory(data_addr, length * kind)?;
+        let data = region.bytes();
+        Ok(match kind {
+            // latin-1, the code points are the bytes
+            1 => data.iter().map(|&b| b as char).collect(),
+            2 => {
+                let units: Vec<u16> = data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+                String::from_utf16_lossy(&units)
+            }
+            _ => data
+                .chunks_exact(4)
+                .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .map(|c| char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect(),
+        })
     }
 
     pub fn parse_NoneType(&self, node: &PyObjectNode) -> io::Result<()> {
@@ -140,6 +405,9 @@ impl EVEProcess {
                 format!("parse_int expect a PyObjectNode of type `int`, get `{}`", node.tp_name)
             ))
         }
+        if self.layout.int == IntLayout::Digits {
+            return self.parse_digits(node)
+        }
         let int_view = node.region.view_bytes_as::<CPyIntObject>(0, None)?;
         Ok(int_view.ob_ival as i64)
     }
@@ -162,6 +430,9 @@ impl EVEProcess {
                 format!("parse_bool expect a PyObjectNode of type `bool`, get `{}`", node.tp_name)
             ))
         }
+        if self.layout.int == IntLayout::Digits {
+            return Ok(self.parse_digits(node)? != 0)
+        }
         let bool_view = node.region.view_bytes_as::<CPyIntObject>(0, None)?;
         Ok(bool_view.ob_ival != 0)
     }
@@ -173,15 +444,27 @@ impl EVEProcess {
                 format!("parse_long expect a PyObjectNode of type `long`, get `{}`", node.tp_name)
             ))
         }
-        let long_view = node.region.view_bytes_as::<CPyLongObject>(0, None)?;
-        let ob_size = long_view.ob_base.ob_size;
-        Ok(node.region.view_bytes_as_vec_of::<u64>(
-            (long_view.ob_digit.as_ptr() as u64 - node.base_addr) as usize,
-            (ob_size.abs() as u64 * size_of::<u64>() as u64) as usize
-        )?.into_iter().enumerate().map(
-            |(i, d)| (*d as i64) * 2_i64.pow(30_u32 * i as u32)
-        ).reduce(|acc, x| acc + x).ok_or(
-            io::Error::new(io::ErrorKind::InvalidInput, "parse_long failed")
-        )? * (if ob_size < 0 {-1} else if ob_size > 0 {1} else { 0 }))
+        self.parse_digits(node)
+    }
+
+    /// Reads the value of a 2.7 `long` or 3.x `int`.
+    fn parse_digits(&self, node: &PyObjectNode) -> io::Result<i64> {
+        // the region ends after the last digit, which is shorter than `CPyLongObject` when padded
+        let ob_size = node.region.view_bytes_as::<CPyVarObject>(0, None)?.ob_size;
+        // stored as 30-bit digits, or 15-bit ones in 32-bit targets, least significant first
+        let (digit_size, digit_bits) = match self.layout.pointer {
+            PointerWidth::Bits32 => (size_of::<u16>(), 15),
+            PointerWidth::Bits64 => (size_of::<u32>(), 30),
+        };
+        let value = node.region.view_bytes(
+            offset_of!(CPyLongObject, ob_digit),
+            ob_size.unsigned_abs() * digit_size
+        )?.chunks_exact(digit_size).rev().try_fold(0_i64, |acc, digit| {
+            let d = digit.iter().rev().fold(0_i64, |d, &byte| d << 8 | byte as i64);
+            acc.checked_mul(1 << digit_bits).and_then(|acc| acc.checked_add(d))
+        }).ok_or(
+            io::Error::new(io::ErrorKind::InvalidData, "parse_long overflowed i64")
+        )?;
+        Ok(value * ob_size.signum() as i64)
     }
- }
\ No newline at end of file
+ }
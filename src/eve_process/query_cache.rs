@@ -0,0 +1,128 @@
+use crate::eve_process::ui_diff::UiChange;
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::export::json::UiNodeDocument;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+struct CachedQuery {
+    segments: Vec<String>,
+    /// Child indices leading from the root to each match.
+    matches: Vec<Vec<usize>>,
+    /// Address of each match, checked on lookup in case siblings were reordered.
+    addrs: Vec<u64>,
+    /// Addresses of the matches and all of their ancestors.
+    chain: HashSet<u64>,
+}
+
+impl CachedQuery {
+    fn resolve(tree: &UiTree, path: &str) -> Self {
+        let matches = tree.root.query_paths(path);
+        let mut chain = HashSet::new();
+        let mut addrs = vec![];
+        for indices in &matches {
+            let mut node = &tree.root;
+            chain.insert(node.addr);
+            for &i in indices {
+                node = &node.children[i];
+                chain.insert(node.addr);
+            }
+            addrs.push(node.addr);
+        }
+        CachedQuery {
+            segments: path.split('/').filter(|segment| !segment.is_empty()).map(str::to_string).collect(),
+            matches,
+            addrs,
+            chain,
+        }
+    }
+
+    fn lookup<'a>(&self, tree: &'a UiTree) -> Option<Vec<&'a UiNode>> {
+        self.matches
+            .iter()
+            .zip(&self.addrs)
+            .map(|(indices, addr)| tree.root.descendant(indices).filter(|node| node.addr == *addr))
+            .collect()
+    }
+
+    /// Whether an added subtree may hold a new match, only its last segment is checked.
+    fn may_match(&self, document: &UiNodeDocument) -> bool {
+        let Some(last) = self.segments.iter().rev().find(|segment| *segment != "**") else {
+            return true;
+        };
+        let mut stack = vec![document];
+        while let Some(document) = stack.pop() {
+            if last == "*" || document.py_type == *last || document.name.as_ref() == Some(last) {
+                return true;
+            }
+            stack.extend(&document.children);
+        }
+        false
+    }
+
+    fn is_affected_by(&self, change: &UiChange) -> bool {
+        match change {
+            UiChange::Added { parent: None, .. } => true,
+            UiChange::Added { node, .. } => self.may_match(node),
+            UiChange::Removed { addr, .. } => self.chain.contains(addr),
+            UiChange::Updated { addr, attrs, .. } => match attrs.get("_name") {
+                Some(name) => {
+                    self.chain.contains(addr)
+                        || name
+                            .as_ref()
+                            .and_then(|name| name.as_str())
+                            .is_some_and(|name| self.segments.iter().any(|segment| segment == name))
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+/// Remembers where [`UiNode::query`] paths matched, so that polling the same paths every frame
+/// follows child indices instead of walking the whole tree.
+///
+/// Entries are dropped by [`invalidate`](Self::invalidate) when the changes between two trees
+/// may move, remove or add matches, and re-resolved when a match is no longer where it was.
+#[derive(Debug, Clone, Default)]
+pub struct QueryCache {
+    queries: HashMap<String, CachedQuery>,
+}
+
+impl QueryCache {
+    pub fn new() -> Self {
+        QueryCache::default()
+    }
+
+    /// Nodes of `tree` matching `path`, like [`UiNode::query`] on its root.
+    pub fn query<'a>(&mut self, tree: &'a UiTree, path: &str) -> Vec<&'a UiNode> {
+        if let Some(nodes) = self.queries.get(path).and_then(|cached| cached.lookup(tree)) {
+            return nodes;
+        }
+        let cached = CachedQuery::resolve(tree, path);
+        let nodes = cached.lookup(tree).unwrap_or_default();
+        self.queries.insert(path.to_string(), cached);
+        nodes
+    }
+
+    pub fn query_first<'a>(&mut self, tree: &'a UiTree, path: &str) -> Option<&'a UiNode> {
+        self.query(tree, path).into_iter().next()
+    }
+
+    /// Drops the paths that `changes`, as reported by [`UiTree::diff`], may have affected.
+    pub fn invalidate(&mut self, changes: &[UiChange]) {
+        self.queries
+            .retain(|_, cached| !changes.iter().any(|change| cached.is_affected_by(change)));
+    }
+
+    pub fn clear(&mut self) {
+        self.queries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+}
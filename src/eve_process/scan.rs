@@ -0,0 +1,369 @@
+use crate::eve_process::eve_process::EVEProcess;
+use crate::eve_process::process::{MemoryRegion, Process, PAGE_SIZE};
+use crate::eve_process::py_struct::{PointerWidth, PyStruct};
+use memchr::memmem;
+use rayon::prelude::*;
+use std::ops::ControlFlow;
+use std::fmt;
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How many bytes are scanned between checks of the [`CancellationToken`].
+const CANCEL_CHECK_INTERVAL: usize = 0x10000;
+
+/// Smallest buffer of a scanning thread under [`EVEProcess::memory_budget`].
+const MIN_CHUNK_SIZE: usize = 0x10000;
+
+/// Stops the scans of an [`EVEProcess`] from another thread or once a deadline passed, see
+/// [`EVEProcess::cancel`]. Clones share the cancellation.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// A token that is cancelled at `deadline` at the latest.
+    pub fn with_deadline(deadline: Instant) -> Self {
+        CancellationToken {
+            deadline: Some(deadline),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::with_deadline(Instant::now() + timeout)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// How far a scan got, passed to [`EVEProcess::progress`] after every region. Every scan starts
+/// from zero again, and `init` runs one for each type it looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanProgress {
+    pub regions_scanned: usize,
+    pub regions_total: usize,
+    pub bytes_scanned: usize,
+    pub bytes_total: usize,
+}
+
+/// Called from the scanning threads, see [`EVEProcess::progress`].
+#[derive(Clone)]
+pub struct ProgressCallback(pub Arc<dyn Fn(ScanProgress) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+impl ProgressCallback {
+    pub fn new<F: Fn(ScanProgress) + Send + Sync + 'static>(callback: F) -> Self {
+        ProgressCallback(Arc::new(callback))
+    }
+}
+
+/// Counts the scanned regions of one scan.
+struct Progress<'a> {
+    callback: Option<&'a ProgressCallback>,
+    regions: AtomicUsize,
+    bytes: AtomicUsize,
+    regions_total: usize,
+    bytes_total: usize,
+}
+
+impl<'a> Progress<'a> {
+    fn new(process: &'a EVEProcess, regions: &[MemoryRegion]) -> Self {
+        Progress {
+            callback: process.progress.as_ref(),
+            regions: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            regions_total: regions.len(),
+            bytes_total: regions.iter().map(|region| region.size).sum(),
+        }
+    }
+
+    fn region_done(&self, region: &MemoryRegion) {
+        let Some(callback) = self.callback else {
+            return;
+        };
+        callback.0(ScanProgress {
+            regions_scanned: self.regions.fetch_add(1, Ordering::Relaxed) + 1,
+            regions_total: self.regions_total,
+            bytes_scanned: self.bytes.fetch_add(region.size, Ordering::Relaxed) + region.size,
+            bytes_total: self.bytes_total,
+        });
+    }
+}
+
+/// Calls `f` with the address and the view of every pointer-aligned `T` that fits into `region`,
+/// until it breaks or `cancel` is cancelled.
+fn for_each_object<T: PyStruct, F>(width: PointerWidth, region: &MemoryRegion, cancel: &CancellationToken, mut f: F)
+where
+    F: FnMut(u64, &T) -> ControlFlow<()>,
+{
+    let Some(last) = region.size.checked_sub(width.size_of::<T>()).filter(|_| region.is_synced()) else {
+        return;
+    };
+    for offset in (0..=last).step_by(width.size()) {
+        if offset % CANCEL_CHECK_INTERVAL == 0 && cancel.is_cancelled() {
+            return;
+        }
+        let addr = region.start + offset as u64;
+        let flow = match width {
+            PointerWidth::Bits64 => f(addr, region.view_bytes_as::<T>(offset, None).unwrap()),
+            PointerWidth::Bits32 => match width.read::<T>(&region.bytes()[offset..region.size]) {
+                Some(object) => f(addr, &object),
+                None => continue,
+            },
+        };
+        if flow.is_break() {
+            return;
+        }
+    }
+}
+
+/// Like [`for_each_object`], but only for the objects whose `ob_type` is `ob_type`, found by
+/// searching the region for the pointer first instead of checking every offset.
+fn for_each_object_of_type<T: PyStruct, F>(
+    width: PointerWidth,
+    region: &MemoryRegion,
+    cancel: &CancellationToken,
+    ob_type: u64,
+    mut f: F,
+) where
+    F: FnMut(u64, &T) -> ControlFlow<()>,
+{
+    let Some(last) = region.size.checked_sub(width.size_of::<T>()).filter(|_| region.is_synced()) else {
+        return;
+    };
+    let needle = ob_type.to_le_bytes();
+    // ob_type follows ob_refcnt
+    let type_offset = width.size();
+    let mut next_check = 0;
+    for hit in memmem::find_iter(&region.bytes()[..region.size], &needle[..width.size()]) {
+        if hit >= next_check {
+            if cancel.is_cancelled() {
+                return;
+            }
+            next_check = hit + CANCEL_CHECK_INTERVAL;
+        }
+        let Some(offset) = hit.checked_sub(type_offset) else {
+            continue;
+        };
+        if offset % width.size() != 0 || offset > last {
+            continue;
+        }
+        let addr = region.start + offset as u64;
+        let flow = match width {
+            PointerWidth::Bits64 => f(addr, region.view_bytes_as::<T>(offset, None).unwrap()),
+            PointerWidth::Bits32 => match width.read::<T>(&region.bytes()[offset..region.size]) {
+                Some(object) => f(addr, &object),
+                None => continue,
+            },
+        };
+        if flow.is_break() {
+            return;
+        }
+    }
+}
+
+/// Scans with [`for_each_object_of_type`] given an `ob_type`, [`for_each_object`] otherwise.
+fn for_each_candidate<T: PyStruct, F>(
+    width: PointerWidth,
+    region: &MemoryRegion,
+    cancel: &CancellationToken,
+    ob_type: Option<u64>,
+    f: F,
+) where
+    F: FnMut(u64, &T) -> ControlFlow<()>,
+{
+    match ob_type {
+        Some(ob_type) => for_each_object_of_type(width, region, cancel, ob_type, f),
+        None => for_each_object(width, region, cancel, f),
+    }
+}
+
+impl EVEProcess {
+    /// Runs `op` in [`EVEProcess::thread_pool`], or the global pool without one.
+    pub(crate) fn install<R: Send, OP: FnOnce() -> R + Send>(&self, op: OP) -> R {
+        match &self.thread_pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Calls `filter` with every pointer-aligned `T` in the synced regions and passes what it
+    /// returns to `sink` as soon as it is found, so callers can stop early. Regions are scanned
+    /// in parallel, `sink` is called from several threads and stops the scan by returning
+    /// `false`. Returns whether all regions were scanned, which they are not after the sink
+    /// stopped the scan or [`EVEProcess::cancel`] was cancelled.
+    pub fn scan_objects<T, F, S>(&self, filter: F, sink: S) -> bool
+    where
+        T: PyStruct,
+        F: Fn(&Process, u64, &T) -> Option<u64> + Sync,
+        S: Fn(u64) -> bool + Sync,
+    {
+        self.scan_candidates(None, filter, sink)
+    }
+
+    /// [`EVEProcess::scan_objects`] for the objects of the type at `ob_type` only, which is
+    /// much faster as `filter` is not called for the other offsets.
+    pub fn scan_objects_of_type<T, F, S>(&self, ob_type: u64, filter: F, sink: S) -> bool
+    where
+        T: PyStruct,
+        F: Fn(&Process, u64, &T) -> Option<u64> + Sync,
+        S: Fn(u64) -> bool + Sync,
+    {
+        self.scan_candidates(Some(ob_type), filter, sink)
+    }
+
+    /// Everything `filter` returns for the pointer-aligned `T` in the synced regions, see
+    /// [`EVEProcess::scan_objects`]. Only holds what was found until then if the scan was
+    /// cancelled.
+    pub fn collect_objects<T, R, F>(&self, filter: F) -> Vec<R>
+    where
+        T: PyStruct,
+        R: Send,
+        F: Fn(&Process, u64, &T) -> Option<R> + Sync,
+    {
+        self.collect_candidates(None, filter)
+    }
+
+    /// [`EVEProcess::collect_objects`] for the objects of the type at `ob_type` only.
+    pub fn collect_objects_of_type<T, R, F>(&self, ob_type: u64, filter: F) -> Vec<R>
+    where
+        T: PyStruct,
+        R: Send,
+        F: Fn(&Process, u64, &T) -> Option<R> + Sync,
+    {
+        self.collect_candidates(Some(ob_type), filter)
+    }
+
+    fn scan_candidates<T, F, S>(&self, ob_type: Option<u64>, filter: F, sink: S) -> bool
+    where
+        T: PyStruct,
+        F: Fn(&Process, u64, &T) -> Option<u64> + Sync,
+        S: Fn(u64) -> bool + Sync,
+    {
+        let stopped = AtomicBool::new(false);
+        let width = self.process.pointer_width;
+        self.scan_regions::<T, (), _>(&stopped, |region| {
+            for_each_candidate(width, region, &self.cancel, ob_type, |addr, object: &T| {
+                if stopped.load(Ordering::Relaxed) {
+                    return ControlFlow::Break(());
+                }
+                if let Some(found) = filter(&self.process, addr, object) {
+                    if !sink(found) {
+                        stopped.store(true, Ordering::Relaxed);
+                        return ControlFlow::Break(());
+                    }
+                }
+                ControlFlow::Continue(())
+            });
+            vec![]
+        });
+        !stopped.into_inner() && !self.cancel.is_cancelled()
+    }
+
+    fn collect_candidates<T, R, F>(&self, ob_type: Option<u64>, filter: F) -> Vec<R>
+    where
+        T: PyStruct,
+        R: Send,
+        F: Fn(&Process, u64, &T) -> Option<R> + Sync,
+    {
+        let width = self.process.pointer_width;
+        self.scan_regions::<T, _, _>(&AtomicBool::new(false), |region| {
+            let mut found = vec![];
+            for_each_candidate(width, region, &self.cancel, ob_type, |addr, object: &T| {
+                found.extend(filter(&self.process, addr, object));
+                ControlFlow::Continue(())
+            });
+            found
+        })
+    }
+
+    /// Calls `scan` with every region in parallel, syncing the ones that are not synced yet, and
+    /// collects what it returns in the order of the regions. Under a [`EVEProcess::memory_budget`] the regions are read from
+    /// the source instead, in chunks overlapping by a `T` so every object is whole in one of
+    /// them, and no more are read once `stopped` is set.
+    fn scan_regions<T, R, F>(&self, stopped: &AtomicBool, scan: F) -> Vec<R>
+    where
+        T: PyStruct,
+        R: Send,
+        F: Fn(&MemoryRegion) -> Vec<R> + Sync,
+    {
+        let (Some(budget), Some(source)) = (self.memory_budget, &self.process.source) else {
+            let progress = Progress::new(self, &self.process.regions);
+            return self.install(|| {
+                self.process
+                    .regions
+                    .par_iter()
+                    .flat_map_iter(|region| {
+                        // unreadable regions are skipped
+                        let found = match self.process.region_data(region) {
+                            Ok(_) => scan(region),
+                            Err(_) => vec![],
+                        };
+                        progress.region_done(region);
+                        found
+                    })
+                    .collect()
+            });
+        };
+        let regions = self.process.list_regions();
+        let progress = Progress::new(self, &regions);
+        let width = self.process.pointer_width;
+        let object_size = width.size_of::<T>();
+        self.install(|| {
+            let chunk_size = (budget / rayon::current_num_threads() / PAGE_SIZE * PAGE_SIZE).max(MIN_CHUNK_SIZE);
+            // the objects starting after the stride are found in the next chunk
+            let stride = (chunk_size - object_size + width.size()) / width.size() * width.size();
+            regions
+                .par_iter()
+                .map_init(Vec::new, |buffer: &mut Vec<u8>, region| {
+                    let mut found = vec![];
+                    let mut offset = 0;
+                    while offset + object_size <= region.size {
+                        if stopped.load(Ordering::Relaxed) || self.cancel.is_cancelled() {
+                            break;
+                        }
+                        let size = chunk_size.min(region.size - offset);
+                        let start = region.start + offset as u64;
+                        buffer.resize(size, 0);
+                        // unreadable chunks are skipped like unreadable regions when syncing
+                        if source.read(start, buffer).is_ok() {
+                            let chunk = MemoryRegion {
+                                start,
+                                size,
+                                data: mem::take(buffer).into(),
+                                handle: region.handle.clone(),
+                                protection: region.protection,
+                                kind: region.kind,
+                            };
+                            found.extend(scan(&chunk));
+                            *buffer = chunk.data.into_inner().unwrap_or_default();
+                        }
+                        offset += stride;
+                    }
+                    progress.region_done(region);
+                    found
+                })
+                .flatten_iter()
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use crate::eve_process::process::Process;
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use serde::{Deserialize, Serialize};
+
+/// The client area of a game window on the virtual desktop, in physical pixels.
+///
+/// The virtual desktop spans all monitors, monitors left of or above the primary one have
+/// negative coordinates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientArea {
+    pub rect: UiRect,
+    /// Bounds of the monitor showing most of the window.
+    pub monitor: UiRect,
+    /// DPI of that monitor, 96 at 100% scaling.
+    pub dpi: u32,
+}
+
+/// Maps the client coordinates of [`UiNode`] rects to the virtual desktop.
+///
+/// The client lays out its UI in its own units, which differ from physical pixels with the in-game
+/// UI scaling and when Windows scales a DPI unaware client. The factor between both is taken from
+/// the size of the UI root against the size of the client area.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScreenMapping {
+    /// Top left corner of the client area on the virtual desktop.
+    pub origin: (i32, i32),
+    /// Physical pixels per UI unit.
+    pub scale: f64,
+    pub monitor: UiRect,
+    pub dpi: u32,
+}
+
+impl Default for ScreenMapping {
+    fn default() -> Self {
+        ScreenMapping {
+            origin: (0, 0),
+            scale: 1.0,
+            monitor: UiRect::default(),
+            dpi: 96,
+        }
+    }
+}
+
+impl ScreenMapping {
+    pub fn new(area: &ClientArea, tree: &UiTree) -> Self {
+        let scale = if tree.root.rect.width > 0 && area.rect.width > 0 {
+            area.rect.width as f64 / tree.root.rect.width as f64
+        } else {
+            1.0
+        };
+        ScreenMapping {
+            origin: (area.rect.x, area.rect.y),
+            scale,
+            monitor: area.monitor,
+            dpi: area.dpi,
+        }
+    }
+
+    /// Reads the client area of `process` for `tree`, falling back to the identity mapping
+    /// where the window can not be queried.
+    pub fn for_tree(process: &Process, tree: &UiTree) -> Self {
+        process
+            .client_area()
+            .map(|area| ScreenMapping::new(&area, tree))
+            .unwrap_or_default()
+    }
+
+    pub fn point_to_screen(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (
+            self.origin.0 + (x as f64 * self.scale).round() as i32,
+            self.origin.1 + (y as f64 * self.scale).round() as i32,
+        )
+    }
+
+    pub fn point_to_client(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (
+            ((x - self.origin.0) as f64 / self.scale).round() as i32,
+            ((y - self.origin.1) as f64 / self.scale).round() as i32,
+        )
+    }
+
+    pub fn rect_to_screen(&self, rect: UiRect) -> UiRect {
+        let (x, y) = self.point_to_screen((rect.x, rect.y));
+        let (right, bottom) = self.point_to_screen((rect.x + rect.width, rect.y + rect.height));
+        UiRect {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+}
+
+impl UiNode {
+    /// [`rect`](UiNode::rect) on the virtual desktop.
+    pub fn screen_rect(&self, mapping: &ScreenMapping) -> UiRect {
+        mapping.rect_to_screen(self.rect)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::eve_process::eve_process::EVEProcess;
+use crate::eve_process::process::{Process, ScanOptions};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// The EVE clients of this machine, attached by pid.
+#[derive(Debug)]
+pub struct EVESessionManager {
+    pub sessions: HashMap<u32, EVEProcess>,
+    /// How many clients are synced and scanned at the same time. Each scan already runs on all
+    /// cores and holds a copy of the client's memory, so more mostly costs memory.
+    pub max_concurrent: usize,
+    /// Least time between starting to attach two clients.
+    pub start_interval: Duration,
+    /// Memory read of newly attached clients, `None` picks it per client with
+    /// [`ScanOptions::for_process`].
+    pub scan_options: Option<ScanOptions>,
+}
+
+impl Default for EVESessionManager {
+    fn default() -> Self {
+        EVESessionManager {
+            sessions: HashMap::new(),
+            max_concurrent: 2,
+            start_interval: Duration::from_millis(500),
+            scan_options: None,
+        }
+    }
+}
+
+/// Clients started at most one per `interval`, shared by the attaching threads.
+struct RateLimiter {
+    interval: Duration,
+    next_start: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn wait(&self) {
+        let delay = {
+            let mut next_start = self.next_start.lock().unwrap();
+            let now = Instant::now();
+            let start = (*next_start).max(now);
+            *next_start = start + self.interval;
+            start - now
+        };
+        thread::sleep(delay);
+    }
+}
+
+/// Opens and initializes the client `pid`, failing with `NotFound` if it is still loading.
+fn open_session(pid: u32, options: Option<&ScanOptions>) -> io::Result<EVEProcess> {
+    let opened = match options {
+        Some(options) => EVEProcess::open_with(pid, options),
+        None => EVEProcess::open(pid),
+    };
+    let mut process = opened.inspect_err(|e| warn!("failed to open client {}: {}", pid, e))?;
+    // a client still loading has no python runtime to find yet
+    if process.init().is_none() {
+        info!("client {} is not ready yet", pid);
+        return Err(io::Error::new(io::ErrorKind::NotFound, "python type object not found"));
+    }
+    info!("attached to client {}", pid);
+    Ok(process)
+}
+
+impl EVESessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The running clients, none without an error if there are no clients.
+    pub fn running_clients() -> io::Result<Vec<Process>> {
+        match EVEProcess::list_clients() {
+            Ok(found) => Ok(found),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn running() -> io::Result<HashSet<u32>> {
+        Ok(Self::running_clients()?.iter().map(|process| process.pid).collect())
+    }
+
+    /// Opens and initializes the client `pid`, replacing its session if it had one.
+    pub fn attach(&mut self, pid: u32) -> io::Result<&mut EVEProcess> {
+        let process = open_session(pid, self.scan_options.as_ref())?;
+        Ok(self.sessions.entry(pid).insert_entry(process).into_mut())
+    }
+
+    pub fn detach(&mut self, pid: u32) -> Option<EVEProcess> {
+        self.sessions.remove(&pid)
+    }
+
+    pub fn is_attached(&self, pid: u32) -> bool {
+        self.sessions.contains_key(&pid)
+    }
+
+    /// Attaches all running clients that are not attached yet, up to `max_concurrent` at a time,
+    /// and returns their pids.
+    ///
+    /// Clients that fail to open or are still loading are skipped, the next call retries them.
+    pub fn init_all(&mut self) -> io::Result<Vec<u32>> {
+        let pending: Vec<u32> = Self::running()?
+            .into_iter()
+            .filter(|pid| !self.sessions.contains_key(pid))
+            .collect();
+        let queue = Mutex::new(pending.into_iter());
+        let limiter = RateLimiter {
+            interval: self.start_interval,
+            next_start: Mutex::new(Instant::now()),
+        };
+        let attached: Vec<(u32, EVEProcess)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.max_concurrent.max(1))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut attached = vec![];
+                        loop {
+                            let Some(pid) = queue.lock().unwrap().next() else { break };
+                            limiter.wait();
+                            attached.extend(open_session(pid, self.scan_options.as_ref()).ok().map(|process| (pid, process)));
+                        }
+                        attached
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        });
+        Ok(attached
+            .into_iter()
+            .map(|(pid, process)| {
+                self.sessions.insert(pid, process);
+                pid
+            })
+            .collect())
+    }
+
+    /// Forgets the clients that exited and returns their pids.
+    pub fn remove_exited(&mut self) -> io::Result<Vec<u32>> {
+        let running = Self::running()?;
+        let exited: Vec<u32> = self.sessions.keys().filter(|pid| !running.contains(pid)).copied().collect();
+        for pid in &exited {
+            info!("client {} exited", pid);
+            self.sessions.remove(pid);
+        }
+        Ok(exited)
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&EVEProcess> {
+        self.sessions.get(&pid)
+    }
+
+    pub fn get_mut(&mut self, pid: u32) -> Option<&mut EVEProcess> {
+        self.sessions.get_mut(&pid)
+    }
+}
@@ -0,0 +1,230 @@
+use crate::eve_process::process::{MemoryRegion, Process};
+use rayon::prelude::*;
+use serde::Serialize;
+
+/// Equal blocks of this size are skipped before comparing byte by byte.
+const BLOCK_SIZE: usize = 0x1000;
+
+/// Bytes `start..start + size` of the address space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ChangedRange {
+    pub start: u64,
+    pub size: usize,
+}
+
+impl ChangedRange {
+    pub fn end(&self) -> u64 {
+        self.start + self.size as u64
+    }
+
+    pub fn contains(&self, addr: u64) -> bool {
+        (self.start..self.end()).contains(&addr)
+    }
+}
+
+/// Where the synced regions of two snapshots of a process differ, like two reads of the
+/// same client before and after a value in the UI changed. All ranges are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiff {
+    /// Bytes that both snapshots have but with other values.
+    pub changed: Vec<ChangedRange>,
+    /// Memory only the newer snapshot has.
+    pub added: Vec<ChangedRange>,
+    /// Memory only the older snapshot has.
+    pub removed: Vec<ChangedRange>,
+}
+
+/// Start and end of the overlaps of two sorted region lists, with the indices of the regions.
+fn overlaps(a: &[MemoryRegion], b: &[MemoryRegion]) -> Vec<(usize, usize, u64, u64)> {
+    let (mut i, mut j) = (0, 0);
+    let mut overlaps = vec![];
+    while i < a.len() && j < b.len() {
+        let start = a[i].start.max(b[j].start);
+        let (a_end, b_end) = (a[i].start + a[i].size as u64, b[j].start + b[j].size as u64);
+        if start < a_end.min(b_end) {
+            overlaps.push((i, j, start, a_end.min(b_end)));
+        }
+        if a_end <= b_end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    overlaps
+}
+
+/// Parts of the regions of `a` that none of `overlaps` covers.
+fn uncovered(a: &[MemoryRegion], overlaps: &[(u64, u64)]) -> Vec<ChangedRange> {
+    let mut ranges = vec![];
+    let mut covered = overlaps.iter().peekable();
+    for region in a {
+        let mut position = region.start;
+        let end = region.start + region.size as u64;
+        while let Some(&&(start, overlap_end)) = covered.peek() {
+            if start >= end {
+                break;
+            }
+            if start > position {
+                ranges.push(ChangedRange { start: position, size: (start - position) as usize });
+            }
+            position = position.max(overlap_end);
+            covered.next();
+        }
+        if position < end {
+            ranges.push(ChangedRange { start: position, size: (end - position) as usize });
+        }
+    }
+    ranges
+}
+
+/// Runs of differing bytes of `old` and `new` of the same length, starting at `start`.
+fn changed_runs(start: u64, old: &[u8], new: &[u8]) -> Vec<ChangedRange> {
+    let mut runs: Vec<ChangedRange> = vec![];
+    for (block, (old, new)) in old.chunks(BLOCK_SIZE).zip(new.chunks(BLOCK_SIZE)).enumerate() {
+        if old == new {
+            continue;
+        }
+        let block_start = start + (block * BLOCK_SIZE) as u64;
+        for (offset, _) in old.iter().zip(new).enumerate().filter(|(_, (old, new))| old != new) {
+            let addr = block_start + offset as u64;
+            match runs.last_mut() {
+                Some(run) if run.end() == addr => run.size += 1,
+                _ => runs.push(ChangedRange { start: addr, size: 1 }),
+            }
+        }
+    }
+    runs
+}
+
+impl SnapshotDiff {
+    /// Compares the regions of `old` and `new`, syncing the ones that are not synced yet.
+    pub fn between(old: &Process, new: &Process) -> Self {
+        old.sync_remaining_regions();
+        new.sync_remaining_regions();
+        Self::between_regions(&old.regions, &new.regions)
+    }
+
+    /// Compares two sets of regions, each sorted by start. Regions that are not synced count
+    /// as unchanged where they overlap.
+    pub fn between_regions(old: &[MemoryRegion], new: &[MemoryRegion]) -> Self {
+        let overlaps = overlaps(old, new);
+        let changed = overlaps
+            .par_iter()
+            .filter(|&&(i, j, _, _)| old[i].is_synced() && new[j].is_synced())
+            .flat_map_iter(|&(i, j, start, end)| {
+                let size = (end - start) as usize;
+                let old_offset = (start - old[i].start) as usize;
+                let new_offset = (start - new[j].start) as usize;
+                changed_runs(
+                    start,
+                    &old[i].bytes()[old_offset..old_offset + size],
+                    &new[j].bytes()[new_offset..new_offset + size],
+                )
+            })
+            .collect();
+        let covered: Vec<_> = overlaps.iter().map(|&(_, _, start, end)| (start, end)).collect();
+        SnapshotDiff {
+            changed,
+            added: uncovered(new, &covered),
+            removed: uncovered(old, &covered),
+        }
+    }
+
+    pub fn changed_bytes(&self) -> usize {
+        self.changed.iter().map(|range| range.size).sum()
+    }
+
+    /// Keeps the changed bytes that changed in `other` as well, to narrow down where a value
+    /// lives over several pairs of snapshots.
+    pub fn narrow(&mut self, other: &SnapshotDiff) {
+        let mut narrowed = vec![];
+        let mut others = other.changed.iter().peekable();
+        for range in &self.changed {
+            while others.next_if(|other| other.end() <= range.start).is_some() {}
+            for other in others.clone().take_while(|other| other.start < range.end()) {
+                let start = range.start.max(other.start);
+                let end = range.end().min(other.end());
+                narrowed.push(ChangedRange { start, size: (end - start) as usize });
+            }
+        }
+        self.changed = narrowed;
+    }
+
+    /// Whether `addr` changed.
+    pub fn is_changed(&self, addr: u64) -> bool {
+        let index = self.changed.partition_point(|range| range.start <= addr);
+        index.checked_sub(1).is_some_and(|index| self.changed[index].contains(addr))
+    }
+}
+
+impl Process {
+    /// What changed from `self` to `newer`, see [`SnapshotDiff`].
+    pub fn diff(&self, newer: &Process) -> SnapshotDiff {
+        SnapshotDiff::between(self, newer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eve_process::process::ScanOptions;
+    use crate::eve_process::source::MockMemory;
+
+    fn snapshot(regions: &[(u64, Vec<u8>)]) -> Process {
+        let mut memory = MockMemory::new();
+        for (start, data) in regions {
+            memory.insert(*start, data.clone(), ScanOptions::PAGE_READWRITE);
+        }
+        Process::from_source(memory).enum_memory_regions().sync_memory_regions()
+    }
+
+    fn range(start: u64, size: usize) -> ChangedRange {
+        ChangedRange { start, size }
+    }
+
+    #[test]
+    fn finds_changed_added_and_removed_ranges() {
+        let mut changed = vec![0; 0x3000];
+        changed[0x10] = 1;
+        changed[0xFFE..0x1002].fill(2);
+        changed[0x2FFF] = 3;
+        // the region at 0x2000_0000 grew, the one at 0x3000_0000 was freed and 0x4000_0000 allocated
+        let old = snapshot(&[
+            (0x1000_0000, vec![0; 0x3000]),
+            (0x2000_0000, vec![0; 0x1000]),
+            (0x3000_0000, vec![0; 0x1000]),
+        ]);
+        let new = snapshot(&[
+            (0x1000_0000, changed),
+            (0x2000_0000, vec![0; 0x2000]),
+            (0x4000_0000, vec![0; 0x1000]),
+        ]);
+        let diff = old.diff(&new);
+        assert_eq!(diff.changed, [range(0x1000_0010, 1), range(0x1000_0FFE, 4), range(0x1000_2FFF, 1)]);
+        assert_eq!(diff.added, [range(0x2000_1000, 0x1000), range(0x4000_0000, 0x1000)]);
+        assert_eq!(diff.removed, [range(0x3000_0000, 0x1000)]);
+        assert_eq!(diff.changed_bytes(), 6);
+        assert!(diff.is_changed(0x1000_1001));
+        assert!(!diff.is_changed(0x1000_1002));
+        assert!(!diff.is_changed(0x2000_1000));
+    }
+
+    #[test]
+    fn same_snapshots_do_not_differ() {
+        let regions = [(0x1000_0000, (0..=255).collect::<Vec<u8>>())];
+        assert_eq!(snapshot(&regions).diff(&snapshot(&regions)), SnapshotDiff::default());
+    }
+
+    #[test]
+    fn narrows_to_bytes_changed_in_both_diffs() {
+        let base = snapshot(&[(0x1000_0000, vec![0; 0x100])]);
+        let mut first = vec![0; 0x100];
+        first[0x10..0x20].fill(1);
+        first[0x80] = 1;
+        let mut second = vec![0; 0x100];
+        second[0x18..0x28].fill(1);
+        let mut diff = base.diff(&snapshot(&[(0x1000_0000, first)]));
+        diff.narrow(&base.diff(&snapshot(&[(0x1000_0000, second)])));
+        assert_eq!(diff.changed, [range(0x1000_0018, 8)]);
+    }
+}
@@ -0,0 +1,274 @@
+//! Where the memory of a [`Process`](crate::eve_process::process::Process) is read from: a running
+//! process, a dump file or synthetic memory.
+
+use crate::eve_process::module::Module;
+use crate::eve_process::platform;
+use crate::eve_process::process::{MemoryRegion, OwnedHandle, ProcessHandle, RegionKind, ScanOptions, PAGE_SIZE};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
+
+const PAGE_GUARD: u32 = 0x100;
+
+fn not_found() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "Address not found in any memory region")
+}
+
+/// Error of reads that only got the bytes before an unreadable page, carried by an
+/// [`io::Error`] of kind `UnexpectedEof`, see [`PartialRead::of`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialRead {
+    pub addr: u64,
+    pub requested: usize,
+    /// The first bytes at `addr`, fewer than `requested`.
+    pub data: Vec<u8>,
+}
+
+impl PartialRead {
+    pub fn new(addr: u64, requested: usize, data: Vec<u8>) -> Self {
+        PartialRead { addr, requested, data }
+    }
+
+    /// The partial read `error` is about, None for other errors.
+    pub fn of(error: &io::Error) -> Option<&PartialRead> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for PartialRead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "read {} of {} bytes at 0x{:X}", self.data.len(), self.requested, self.addr)
+    }
+}
+
+impl Error for PartialRead {}
+
+impl From<PartialRead> for io::Error {
+    fn from(partial: PartialRead) -> Self {
+        io::Error::new(io::ErrorKind::UnexpectedEof, partial)
+    }
+}
+
+/// Reads memory for a [`Process`](crate::eve_process::process::Process), see
+/// [`Process::from_source`](crate::eve_process::process::Process::from_source).
+pub trait MemorySource: Debug + Send + Sync {
+    /// Fills `buf` with the memory at `addr`. Fails with a [`PartialRead`] where only the start
+    /// of `buf` could be read, which is filled then as well.
+    fn read(&self, addr: u64, buf: &mut [u8]) -> io::Result<()>;
+
+    /// The regions with any of the `options` protections, without their data.
+    fn regions(&self, options: &ScanOptions) -> Vec<MemoryRegion>;
+
+    /// Modules loaded into the process, none where they are not known.
+    fn modules(&self) -> io::Result<Vec<Module>> {
+        Ok(vec![])
+    }
+
+    /// The handle the regions are bound to.
+    fn handle(&self) -> ProcessHandle {
+        ProcessHandle::None
+    }
+
+    /// For each page of `size` bytes at `start`, whether it was written since the last
+    /// [`MemorySource::clear_dirty`]. Fails where writes are not tracked.
+    fn dirty_pages(&self, _start: u64, _size: usize) -> io::Result<Vec<bool>> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "writes are not tracked"))
+    }
+
+    /// Marks all pages as not written.
+    fn clear_dirty(&self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "writes are not tracked"))
+    }
+
+    /// For each page of `size` bytes at `start`, whether it is in memory instead of paged out.
+    /// Fails where that is not known.
+    fn resident_pages(&self, _start: u64, _size: usize) -> io::Result<Vec<bool>> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "residency is not known"))
+    }
+}
+
+fn page_count(size: usize) -> usize {
+    size.div_ceil(PAGE_SIZE)
+}
+
+/// A running process, read through the OS.
+#[derive(Debug, Clone)]
+pub struct LiveMemory(pub Arc<OwnedHandle>);
+
+impl MemorySource for LiveMemory {
+    fn read(&self, addr: u64, buf: &mut [u8]) -> io::Result<()> {
+        platform::read_process_memory(self.0.raw(), addr, buf)
+    }
+
+    fn regions(&self, options: &ScanOptions) -> Vec<MemoryRegion> {
+        platform::address_chunks()
+            .into_par_iter()
+            .flat_map_iter(|(start, end)| platform::query_regions(self.0.raw(), start, end, options.protection_mask))
+            .filter_map(|region| region.bound(self.handle()).ok())
+            .collect()
+    }
+
+    fn modules(&self) -> io::Result<Vec<Module>> {
+        platform::list_modules(self.0.raw())
+    }
+
+    fn handle(&self) -> ProcessHandle {
+        ProcessHandle::Live(self.0.clone())
+    }
+
+    fn dirty_pages(&self, start: u64, size: usize) -> io::Result<Vec<bool>> {
+        platform::dirty_pages(self.0.raw(), start, page_count(size))
+    }
+
+    fn clear_dirty(&self) -> io::Result<()> {
+        platform::clear_dirty(self.0.raw())
+    }
+
+    fn resident_pages(&self, start: u64, size: usize) -> io::Result<Vec<bool>> {
+        platform::resident_pages(self.0.raw(), start, page_count(size))
+    }
+}
+
+/// A range of memory stored in a dump file.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DumpRange {
+    pub start: u64,
+    pub size: u64,
+    /// Where the data starts in the file.
+    pub offset: u64,
+    /// `PAGE_*` protection, 0 where unknown.
+    pub protection: u32,
+    pub kind: RegionKind,
+}
+
+/// An uncompressed dump of [`Process::write_dump`](crate::eve_process::process::Process::write_dump)
+/// or a Windows minidump, read from the file when needed instead of kept in memory.
+#[derive(Debug)]
+pub struct DumpFile {
+    /// Process id of the dumped process, 0 where the dump does not record it.
+    pub pid: u32,
+    file: Mutex<File>,
+    /// Sorted by start.
+    ranges: Vec<DumpRange>,
+}
+
+impl DumpFile {
+    pub(crate) fn new(pid: u32, file: File, mut ranges: Vec<DumpRange>) -> Self {
+        ranges.sort_by_key(|range| range.start);
+        DumpFile {
+            pid,
+            file: Mutex::new(file),
+            ranges,
+        }
+    }
+}
+
+impl MemorySource for DumpFile {
+    fn read(&self, addr: u64, buf: &mut [u8]) -> io::Result<()> {
+        let index = self.ranges.partition_point(|range| range.start <= addr).checked_sub(1).ok_or_else(not_found)?;
+        let range = self.ranges[index];
+        if addr >= range.start + range.size {
+            return Err(not_found());
+        }
+        let available = ((range.start + range.size - addr) as usize).min(buf.len());
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start(range.offset + addr - range.start))?;
+        file.read_exact(&mut buf[..available])?;
+        if available < buf.len() {
+            return Err(PartialRead::new(addr, buf.len(), buf[..available].to_vec()).into());
+        }
+        Ok(())
+    }
+
+    /// All ranges where the dump does not record protections.
+    fn regions(&self, options: &ScanOptions) -> Vec<MemoryRegion> {
+        self.ranges
+            .iter()
+            .filter(|range| {
+                range.protection == 0
+                    || (range.protection & options.protection_mask != 0 && range.protection & PAGE_GUARD == 0)
+            })
+            .filter_map(|range| {
+                Some(MemoryRegion {
+                    protection: range.protection,
+                    kind: range.kind,
+                    ..MemoryRegion::new(range.start, range.size as usize, ProcessHandle::File, None).ok()?
+                })
+            })
+            .collect()
+    }
+
+    fn handle(&self) -> ProcessHandle {
+        ProcessHandle::File
+    }
+
+    /// Dumps do not change.
+    fn dirty_pages(&self, _start: u64, size: usize) -> io::Result<Vec<bool>> {
+        Ok(vec![false; page_count(size)])
+    }
+
+    fn clear_dirty(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Synthetic memory, like the images of tests and benchmarks.
+#[derive(Debug, Default)]
+pub struct MockMemory {
+    /// Data and protection by start.
+    regions: BTreeMap<u64, (Vec<u8>, u32)>,
+}
+
+impl MockMemory {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds `data` at `start` as a region with the `PAGE_*` `protection`. It must not overlap
+    /// the others.
+    pub fn insert(&mut self, start: u64, data: Vec<u8>, protection: u32) {
+        self.regions.insert(start, (data, protection));
+    }
+}
+
+impl MemorySource for MockMemory {
+    fn read(&self, addr: u64, buf: &mut [u8]) -> io::Result<()> {
+        let (start, (data, _)) = self.regions.range(..=addr).next_back().ok_or_else(not_found)?;
+        let offset = (addr - start) as usize;
+        let bytes = data.get(offset..).filter(|bytes| !bytes.is_empty()).ok_or_else(not_found)?;
+        if bytes.len() < buf.len() {
+            buf[..bytes.len()].copy_from_slice(bytes);
+            return Err(PartialRead::new(addr, buf.len(), bytes.to_vec()).into());
+        }
+        buf.copy_from_slice(&bytes[..buf.len()]);
+        Ok(())
+    }
+
+    fn regions(&self, options: &ScanOptions) -> Vec<MemoryRegion> {
+        self.regions
+            .iter()
+            .filter(|(_, (_, protection))| protection & options.protection_mask != 0)
+            .filter_map(|(&start, (data, protection))| {
+                Some(MemoryRegion {
+                    protection: *protection,
+                    ..MemoryRegion::new(start, data.len(), ProcessHandle::None, None).ok()?
+                })
+            })
+            .collect()
+    }
+
+    /// The regions can not change once the memory is used.
+    fn dirty_pages(&self, _start: u64, size: usize) -> io::Result<Vec<bool>> {
+        Ok(vec![false; page_count(size)])
+    }
+
+    fn clear_dirty(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::eve_process::eve_process::EVEProcess;
+use crate::eve_process::py_struct::CPyObject;
+use crate::eve_process::ui_tree::{UiTree, VALUE_TYPES};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// How far the parsers get with the objects of a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeSupport {
+    /// Converted to a plain value by [`EVEProcess::parse_py_value`].
+    Value,
+    /// Read as a node of the UI tree.
+    UiNode,
+    /// Only kept by reference.
+    Unhandled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeCount {
+    pub tp_name: String,
+    /// Type objects with this name, modules can define types of the same name.
+    pub type_addrs: Vec<u64>,
+    /// Approximate, any two words that look like an object header of the type count.
+    pub instances: usize,
+    pub support: TypeSupport,
+}
+
+/// Live objects have a small positive reference count.
+fn is_plausible_header(object: &CPyObject) -> bool {
+    object.ob_refcnt > 0 && object.ob_refcnt < 1 << 32
+}
+
+impl EVEProcess {
+    /// Addresses of all type objects whose type is `type`.
+    pub fn search_all_types(&self) -> Vec<u64> {
+        let Some(py_type) = self.py_type.upgrade() else {
+            return vec![];
+        };
+        let py_type = py_type.base_addr;
+        let mut types = self.collect_objects_of_type(py_type, |_, addr, object: &CPyObject| {
+            (is_plausible_header(object) && object.ob_type == py_type).then_some(addr)
+        });
+        types.sort_unstable();
+        types
+    }
+
+    /// Counts the objects of each of `types` in the synced memory.
+    pub fn count_instances(&self, types: &HashSet<u64>) -> HashMap<u64, usize> {
+        let mut result = HashMap::new();
+        for (_, ob_type) in self.scan_headers(|ob_type| types.contains(&ob_type)) {
+            *result.entry(ob_type).or_insert(0) += 1;
+        }
+        result
+    }
+
+    /// Addresses and `ob_type` of the object headers in all regions whose type matches `filter`.
+    fn scan_headers<F: Fn(u64) -> bool + Sync>(&self, filter: F) -> Vec<(u64, u64)> {
+        self.collect_objects(|_, addr, object: &CPyObject| {
+            (is_plausible_header(object) && filter(object.ob_type)).then_some((addr, object.ob_type))
+        })
+    }
+
+    /// Instance counts of all types by name, most frequent first. Types of the nodes in `ui`
+    /// count as handled by the UI parser.
+    ///
+    /// Scans the whole synced memory twice, expect it to take as long as [`EVEProcess::init`].
+    pub fn type_histogram(&self, ui: Option<&UiTree>) -> Vec<TypeCount> {
+        let types: HashSet<u64> = self.search_all_types().into_iter().collect();
+        let instances = self.count_instances(&types);
+        let ui_types: HashSet<&str> = ui
+            .map(|tree| tree.root.iter().map(|node| node.py_type.as_str()).collect())
+            .unwrap_or_default();
+        let mut by_name: BTreeMap<String, TypeCount> = BTreeMap::new();
+        for addr in types {
+            let Ok(tp_name) = self.read_type_name(addr) else { continue };
+            let support = if VALUE_TYPES.contains(&tp_name.as_str()) {
+                TypeSupport::Value
+            } else if ui_types.contains(tp_name.as_str()) {
+                TypeSupport::UiNode
+            } else {
+                TypeSupport::Unhandled
+            };
+            let count = by_name.entry(tp_name.clone()).or_insert(TypeCount {
+                tp_name,
+                type_addrs: vec![],
+                instances: 0,
+                support,
+            });
+            count.type_addrs.push(addr);
+            count.instances += instances.get(&addr).copied().unwrap_or(0);
+        }
+        let mut histogram: Vec<_> = by_name.into_values().collect();
+        histogram.sort_by(|a, b| b.instances.cmp(&a.instances).then_with(|| a.tp_name.cmp(&b.tp_name)));
+        histogram
+    }
+}
@@ -0,0 +1,96 @@
+use crate::eve_process::ui_tree::{PyValue, UiNode, UiRect, UiTree};
+use crate::export::json::UiNodeDocument;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A difference between two readouts of the same UI tree, nodes are matched by address.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum UiChange {
+    /// A new subtree below `parent`.
+    Added { parent: Option<u64>, node: UiNodeDocument },
+    /// A subtree that disappeared, only its top-most node is reported.
+    Removed { addr: u64, py_type: String },
+    Updated {
+        addr: u64,
+        py_type: String,
+        name: Option<String>,
+        /// The new rect, if it moved or resized.
+        rect: Option<UiRect>,
+        /// Changed attributes with their new value, `null` for removed ones.
+        attrs: BTreeMap<String, Option<PyValue>>,
+    },
+}
+
+impl UiChange {
+    pub fn addr(&self) -> u64 {
+        match self {
+            UiChange::Added { node, .. } => node.addr,
+            UiChange::Removed { addr, .. } | UiChange::Updated { addr, .. } => *addr,
+        }
+    }
+}
+
+fn diff_node(node: &UiNode, previous: &HashMap<u64, &UiNode>, changes: &mut Vec<UiChange>) {
+    let Some(old) = previous.get(&node.addr) else {
+        return;
+    };
+    let mut attrs = BTreeMap::new();
+    for (key, value) in &node.attrs {
+        if old.attrs.get(key) != Some(value) {
+            attrs.insert(key.clone(), Some(value.clone()));
+        }
+    }
+    for key in old.attrs.keys().filter(|key| !node.attrs.contains_key(*key)) {
+        attrs.insert(key.clone(), None);
+    }
+    let rect = (old.rect != node.rect).then_some(node.rect);
+    if rect.is_some() || !attrs.is_empty() {
+        changes.push(UiChange::Updated {
+            addr: node.addr,
+            py_type: node.py_type.clone(),
+            name: node.name().map(str::to_string),
+            rect,
+            attrs,
+        });
+    }
+    for child in &node.children {
+        if previous.contains_key(&child.addr) {
+            diff_node(child, previous, changes);
+        } else {
+            changes.push(UiChange::Added {
+                parent: Some(node.addr),
+                node: UiNodeDocument::from(child),
+            });
+        }
+    }
+}
+
+impl UiTree {
+    /// Changes that turn `previous` into this tree.
+    pub fn diff(&self, previous: &UiTree) -> Vec<UiChange> {
+        let old_nodes: HashMap<u64, &UiNode> = previous.root.iter().map(|node| (node.addr, node)).collect();
+        let mut changes = vec![];
+        if old_nodes.contains_key(&self.root.addr) {
+            diff_node(&self.root, &old_nodes, &mut changes);
+        } else {
+            changes.push(UiChange::Added {
+                parent: None,
+                node: UiNodeDocument::from(&self.root),
+            });
+        }
+        let new_addrs: HashSet<u64> = self.root.iter().map(|node| node.addr).collect();
+        let mut stack = vec![&previous.root];
+        while let Some(node) = stack.pop() {
+            if new_addrs.contains(&node.addr) {
+                stack.extend(node.children.iter());
+            } else {
+                changes.push(UiChange::Removed {
+                    addr: node.addr,
+                    py_type: node.py_type.clone(),
+                });
+            }
+        }
+        changes
+    }
+}
@@ -0,0 +1,419 @@
+use crate::eve_process::annotations::annotation;
+use crate::eve_process::eve_process::{EVEProcess, PyObjectNode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io;
+use tracing::debug;
+
+/// Maximum nesting of UI objects followed below the root.
+const MAX_UI_DEPTH: usize = 128;
+/// Maximum nesting of plain python containers kept as attribute values.
+const MAX_VALUE_DEPTH: usize = 2;
+/// Types [`EVEProcess::parse_py_value`] converts into plain values.
+pub const VALUE_TYPES: &[&str] = &[
+    "NoneType", "bool", "int", "long", "float", "str", "unicode", "bytes", "list", "tuple", "dict",
+];
+
+/// A python value read from the attribute dict of a UI object.
+///
+/// Serialized as the plain JSON value, `Object` must stay ahead of `Dict` for deserialization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PyValue {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    List(Vec<PyValue>),
+    /// Any other object, kept by reference only.
+    Object { addr: u64, tp_name: String },
+    Dict(BTreeMap<String, PyValue>),
+}
+
+impl PyValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PyValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            PyValue::Float(f) => Some(f),
+            PyValue::Int(i) => Some(i as f64),
+            PyValue::Bool(b) => Some(b as i64 as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            PyValue::Int(i) => Some(i),
+            PyValue::Bool(b) => Some(b as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            PyValue::Bool(b) => Some(b),
+            PyValue::Int(i) => Some(i != 0),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[PyValue]> {
+        match self {
+            PyValue::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    pub fn as_dict(&self) -> Option<&BTreeMap<String, PyValue>> {
+        match self {
+            PyValue::Dict(d) => Some(d),
+            _ => None,
+        }
+    }
+}
+
+/// A rectangle in client coordinates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl UiRect {
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+}
+
+/// A UI object of the client together with its attributes and children.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct UiNode {
+    pub addr: u64,
+    pub py_type: String,
+    pub attrs: BTreeMap<String, PyValue>,
+    /// Position accumulated from `_displayX`/`_displayY` of all ancestors.
+    pub rect: UiRect,
+    pub children: Vec<UiNode>,
+}
+
+impl fmt::Debug for UiNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("UiNode");
+        debug.field("addr", &format_args!("0x{:X}", self.addr));
+        if let Some(label) = annotation(self.addr) {
+            debug.field("annotation", &label);
+        }
+        debug
+            .field("py_type", &self.py_type)
+            .field("attrs", &self.attrs)
+            .field("rect", &self.rect)
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+#[profiling::all_functions]
+impl UiNode {
+    pub fn attr(&self, key: &str) -> Option<&PyValue> {
+        self.attrs.get(key)
+    }
+
+    pub fn attr_str(&self, key: &str) -> Option<&str> {
+        self.attr(key).and_then(PyValue::as_str)
+    }
+
+    pub fn attr_f64(&self, key: &str) -> Option<f64> {
+        self.attr(key).and_then(PyValue::as_f64)
+    }
+
+    pub fn attr_i64(&self, key: &str) -> Option<i64> {
+        self.attr(key).and_then(PyValue::as_i64)
+    }
+
+    pub fn attr_bool(&self, key: &str) -> Option<bool> {
+        self.attr(key).and_then(PyValue::as_bool)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.attr_str("_name")
+    }
+
+    /// The label text, if this node displays any.
+    pub fn text(&self) -> Option<&str> {
+        self.attr_str("_setText")
+            .or_else(|| self.attr_str("_text"))
+            .filter(|text| !text.is_empty())
+    }
+
+    pub fn hint(&self) -> Option<&str> {
+        self.attr_str("_hint").filter(|hint| !hint.is_empty())
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.attr_bool("_display").unwrap_or(true)
+    }
+
+    /// Iterates over this node and all of its descendants in depth-first pre-order.
+    pub fn iter(&self) -> UiNodeIter<'_> {
+        UiNodeIter { stack: vec![self] }
+    }
+
+    pub fn find_first<P: FnMut(&UiNode) -> bool>(&self, mut predicate: P) -> Option<&UiNode> {
+        self.iter().find(|node| predicate(node))
+    }
+
+    pub fn find_all<P: FnMut(&UiNode) -> bool>(&self, mut predicate: P) -> Vec<&UiNode> {
+        self.iter().filter(|node| predicate(node)).collect()
+    }
+
+    pub fn find_by_type(&self, py_type: &str) -> Option<&UiNode> {
+        self.find_first(|node| node.py_type == py_type)
+    }
+
+    pub fn find_all_by_type(&self, py_type: &str) -> Vec<&UiNode> {
+        self.find_all(|node| node.py_type == py_type)
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&UiNode> {
+        self.find_first(|node| node.name() == Some(name))
+    }
+
+    /// Descendants matching a `/` separated path, each segment being a python type or a name.
+    ///
+    /// `*` matches any child and `**` any number of levels, e.g. `**/OverView/**/OverviewScrollEntry`.
+    pub fn query(&self, path: &str) -> Vec<&UiNode> {
+        self.query_paths(path)
+            .iter()
+            .filter_map(|indices| self.descendant(indices))
+            .collect()
+    }
+
+    /// Like [`query`](Self::query), but returns the child indices leading to each match.
+    pub fn query_paths(&self, path: &str) -> Vec<Vec<usize>> {
+        fn matches(node: &UiNode, segment: &str) -> bool {
+            segment == "*" || node.py_type == segment || node.name() == Some(segment)
+        }
+        fn walk(node: &UiNode, segments: &[&str], indices: &mut Vec<usize>, result: &mut Vec<Vec<usize>>) {
+            let Some((&segment, rest)) = segments.split_first() else {
+                if !result.contains(indices) {
+                    result.push(indices.clone());
+                }
+                return;
+            };
+            if segment == "**" {
+                walk(node, rest, indices, result);
+                for (i, child) in node.children.iter().enumerate() {
+                    indices.push(i);
+                    walk(child, segments, indices, result);
+                    indices.pop();
+                }
+            } else {
+                for (i, child) in node.children.iter().enumerate().filter(|(_, child)| matches(child, segment)) {
+                    indices.push(i);
+                    walk(child, rest, indices, result);
+                    indices.pop();
+                }
+            }
+        }
+        let segments: Vec<_> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+        let mut result = vec![];
+        walk(self, &segments, &mut vec![], &mut result);
+        result
+    }
+
+    /// The descendant reached by following the child `indices`, this node for none.
+    pub fn descendant(&self, indices: &[usize]) -> Option<&UiNode> {
+        indices.iter().try_fold(self, |node, &i| node.children.get(i))
+    }
+
+    /// All label texts below this node, in tree order.
+    pub fn texts(&self) -> Vec<&str> {
+        self.iter().filter_map(UiNode::text).collect()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.iter().count()
+    }
+}
+
+pub struct UiNodeIter<'a> {
+    stack: Vec<&'a UiNode>,
+}
+
+impl<'a> Iterator for UiNodeIter<'a> {
+    type Item = &'a UiNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children.iter().rev());
+        Some(node)
+    }
+}
+
+/// The UI object tree below a `UIRoot` instance.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiTree {
+    pub root: UiNode,
+}
+
+impl UiTree {
+    pub fn node_count(&self) -> usize {
+        self.root.node_count()
+    }
+}
+
+#[profiling::all_functions]
+impl EVEProcess {
+    /// Converts a python object into a [`PyValue`], following containers up to `depth` levels.
+    pub fn parse_py_value(&self, node: &PyObjectNode, depth: usize) -> PyValue {
+        let value = match node.tp_name.as_str() {
+            "NoneType" => Ok(PyValue::None),
+            "bool" => self.parse_bool(node).map(PyValue::Bool),
+            "int" => self.parse_int(node).map(PyValue::Int),
+            "long" => self.parse_long(node).map(PyValue::Int),
+            "float" => self.parse_float(node).map(PyValue::Float),
+            "str" | "unicode" | "bytes" => self.parse_string_like(node).map(PyValue::Str),
+            "list" if depth > 0 => self.parse_list(node).map(|items| {
+                PyValue::List(items.iter().map(|item| self.parse_py_value(item, depth - 1)).collect())
+            }),
+            "tuple" if depth > 0 => self.parse_tuple(node).map(|items| {
+                PyValue::List(items.iter().map(|item| self.parse_py_value(item, depth - 1)).collect())
+            }),
+            "dict" if depth > 0 => self.parse_dict(node).map(|entries| {
+                PyValue::Dict(
+                    entries
+                        .iter()
+                        .map(|(key, value)| (key.clone(), self.parse_py_value(value, depth - 1)))
+                        .collect(),
+                )
+            }),
+            _ => Err(io::Error::new(io::ErrorKind::Unsupported, "not a plain value")),
+        };
+        value.unwrap_or_else(|_| PyValue::Object {
+            addr: node.base_addr,
+            tp_name: node.tp_name.clone(),
+        })
+    }
+
+    /// Reads the object at `addr` as a [`PyValue`], expanding containers and the instance dicts
+    /// of other objects up to `depth` levels. Expanded objects become dicts with additional
+    /// `__addr__` and `__type__` entries.
+    pub fn materialize(&self, addr: u64, depth: usize) -> io::Result<PyValue> {
+        Ok(self.materialize_node(&self.read_object(addr)?, depth))
+    }
+
+    fn materialize_node(&self, node: &PyObjectNode, depth: usize) -> PyValue {
+        let value = self.parse_py_value(node, 0);
+        if depth == 0 || !matches!(value, PyValue::Object { .. }) {
+            return value;
+        }
+        let list = |items: Vec<PyObjectNode>| {
+            PyValue::List(items.iter().map(|item| self.materialize_node(item, depth - 1)).collect())
+        };
+        let dict = |entries: HashMap<String, PyObjectNode>| -> BTreeMap<String, PyValue> {
+            entries
+                .iter()
+                .map(|(key, value)| (key.clone(), self.materialize_node(value, depth - 1)))
+                .collect()
+        };
+        let expanded = match node.tp_name.as_str() {
+            "list" => self.parse_list(node).map(list),
+            "tuple" => self.parse_tuple(node).map(list),
+            "dict" => self.parse_dict(node).map(|entries| PyValue::Dict(dict(entries))),
+            _ => self
+                .read_instance_dict(node)
+                .and_then(|instance_dict| self.parse_dict(&instance_dict))
+                .map(|entries| {
+                    let mut attrs = dict(entries);
+                    attrs.insert("__addr__".to_string(), PyValue::Int(node.base_addr as i64));
+                    attrs.insert("__type__".to_string(), PyValue::Str(node.tp_name.clone()));
+                    PyValue::Dict(attrs)
+                }),
+        };
+        expanded.unwrap_or(value)
+    }
+
+    /// Reads the addresses of the UI objects held by a `children` attribute.
+    fn parse_ui_children(&self, children: &PyObjectNode) -> io::Result<Vec<u64>> {
+        let list = if children.tp_name == "list" {
+            self.parse_list(children)?
+        } else {
+            // PyChildrenList keeps the actual list in `_childrenObjects`
+            let dict = self.read_instance_dict(children)?;
+            match self.parse_dict(&dict)?.get("_childrenObjects") {
+                Some(objects) => self.parse_list(objects)?,
+                None => vec![],
+            }
+        };
+        Ok(list.iter().map(|item| item.base_addr).collect())
+    }
+
+    /// Parses the UI object at `addr` and everything below it.
+    pub fn parse_ui_node(
+        &self,
+        addr: u64,
+        origin: (i32, i32),
+        depth: usize,
+        visited: &mut HashSet<u64>,
+    ) -> io::Result<UiNode> {
+        if depth > MAX_UI_DEPTH || !visited.insert(addr) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("UI object 0x{:X} is nested too deep or cyclic", addr),
+            ));
+        }
+        let obj = self.read_object(addr)?;
+        let dict = self.read_instance_dict(&obj)?;
+        let mut node = UiNode {
+            addr,
+            py_type: obj.tp_name.clone(),
+            ..Default::default()
+        };
+        let mut children_addrs = vec![];
+        for (key, value) in self.parse_dict(&dict)? {
+            if key == "children" {
+                children_addrs = self.parse_ui_children(&value).unwrap_or_default();
+            } else {
+                node.attrs.insert(key, self.parse_py_value(&value, MAX_VALUE_DEPTH));
+            }
+        }
+        let display = |key| node.attr_f64(key).unwrap_or(0.0).round() as i32;
+        node.rect = UiRect {
+            x: origin.0 + display("_displayX"),
+            y: origin.1 + display("_displayY"),
+            width: display("_displayWidth"),
+            height: display("_displayHeight"),
+        };
+        let child_origin = (node.rect.x, node.rect.y);
+        for child_addr in children_addrs {
+            match self.parse_ui_node(child_addr, child_origin, depth + 1, visited) {
+                Ok(child) => node.children.push(child),
+                Err(e) => debug!("skipped UI child 0x{:X}: {}", child_addr, e),
+            }
+        }
+        Ok(node)
+    }
+
+    /// Parses the UI trees of all `UIRoot` instances and keeps the largest one,
+    /// stale instances left over from previous sessions are nearly empty.
+    pub fn ui_tree(&mut self) -> Option<UiTree> {
+        self.search_ui_root(None)
+            .into_iter()
+            .filter_map(|addr| self.parse_ui_tree(addr))
+            .max_by_key(UiTree::node_count)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::eve_process::eve_process::EVEProcess;
+use crate::eve_process::query_cache::QueryCache;
+use crate::eve_process::ui_diff::UiChange;
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Result of one poll of a [`UiWatcher`].
+#[derive(Debug, Clone)]
+pub struct UiUpdate {
+    pub tree: UiTree,
+    /// Empty for the first readout.
+    pub changes: Vec<UiChange>,
+}
+
+/// Re-reads the UI tree of a client at a fixed interval and reports what changed.
+#[derive(Debug)]
+pub struct UiWatcher {
+    pub process: EVEProcess,
+    pub interval: Duration,
+    /// Invalidated with the changes of every poll.
+    pub queries: QueryCache,
+    previous: Option<UiTree>,
+}
+
+impl UiWatcher {
+    pub fn new(process: EVEProcess, interval: Duration) -> Self {
+        UiWatcher {
+            process,
+            interval,
+            queries: QueryCache::new(),
+            previous: None,
+        }
+    }
+
+    pub fn previous(&self) -> Option<&UiTree> {
+        self.previous.as_ref()
+    }
+
+    /// Reads the tree once, returns `None` if no UI root could be parsed.
+    pub fn poll(&mut self) -> Option<UiUpdate> {
+        let tree = self.process.ui_tree()?;
+        let changes = self
+            .previous
+            .as_ref()
+            .map(|previous| tree.diff(previous))
+            .unwrap_or_default();
+        self.queries.invalidate(&changes);
+        self.previous = Some(tree.clone());
+        Some(UiUpdate { tree, changes })
+    }
+
+    /// Nodes of the last readout matching `path`, see [`UiNode::query`].
+    pub fn query(&mut self, path: &str) -> Vec<&UiNode> {
+        match &self.previous {
+            Some(tree) => self.queries.query(tree, path),
+            None => vec![],
+        }
+    }
+
+    /// Polls until `callback` returns `false`, sleeping for what is left of the interval in between.
+    pub fn run<F: FnMut(&UiUpdate) -> bool>(&mut self, mut callback: F) {
+        loop {
+            let started = Instant::now();
+            if let Some(update) = self.poll() {
+                if !callback(&update) {
+                    return;
+                }
+            }
+            thread::sleep(self.interval.saturating_sub(started.elapsed()));
+        }
+    }
+}
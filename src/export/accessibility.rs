@@ -0,0 +1,77 @@
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use crate::readers::{labels, strip_tags, window_caption};
+
+/// Vertical distance in pixels within which elements count as the same line.
+const LINE_TOLERANCE: i32 = 6;
+
+fn is_window(node: &UiNode) -> bool {
+    node.is_visible() && node.py_type.ends_with("Window") && node.rect.width > 0 && node.rect.height > 0
+}
+
+/// The window the player is interacting with: the one flagged active, otherwise the topmost one.
+///
+/// Windows of a layer are kept in z-order with the topmost first, so tree order finds it.
+pub fn focused_window(tree: &UiTree) -> Option<&UiNode> {
+    let windows = tree.root.find_all(is_window);
+    windows
+        .iter()
+        .find(|window| window.attr_bool("_active").or_else(|| window.attr_bool("isActive")) == Some(true))
+        .or_else(|| windows.first())
+        .copied()
+}
+
+/// Texts and buttons of `node` with their position, buttons are not descended into.
+fn elements(node: &UiNode, result: &mut Vec<(UiRect, String)>) {
+    if !node.is_visible() {
+        return;
+    }
+    if node.py_type.contains("Button") {
+        if let Some(label) = labels(node).into_iter().next() {
+            result.push((node.rect, format!("[{}]", label)));
+            return;
+        }
+    }
+    if let Some(text) = node.text() {
+        let text = strip_tags(text);
+        let text = text.trim();
+        if !text.is_empty() {
+            result.push((node.rect, text.to_string()));
+        }
+    }
+    for child in &node.children {
+        elements(child, result);
+    }
+}
+
+/// Renders a window as plain text in reading order: the caption, then one line per row of
+/// elements from left to right. Buttons are written as `[label]`.
+pub fn window_text(window: &UiNode) -> String {
+    let mut found = vec![];
+    for child in &window.children {
+        elements(child, &mut found);
+    }
+    found.sort_by_key(|(rect, _)| (rect.y, rect.x));
+
+    let mut lines: Vec<(i32, Vec<(UiRect, String)>)> = vec![];
+    for (rect, text) in found {
+        match lines.last_mut() {
+            Some((y, line)) if rect.y - *y <= LINE_TOLERANCE => line.push((rect, text)),
+            _ => lines.push((rect.y, vec![(rect, text)])),
+        }
+    }
+    let mut result = window_caption(window).unwrap_or_else(|| window.py_type.clone());
+    for (_, mut line) in lines {
+        line.sort_by_key(|(rect, _)| rect.x);
+        let texts: Vec<_> = line.into_iter().map(|(_, text)| text).collect();
+        result.push('\n');
+        result.push_str(&texts.join("  "));
+    }
+    result
+}
+
+impl UiTree {
+    /// Plain text of the focused window, see [`window_text`].
+    pub fn focused_window_text(&self) -> Option<String> {
+        focused_window(self).map(window_text)
+    }
+}
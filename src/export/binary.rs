@@ -0,0 +1,56 @@
+use crate::eve_process::ui_tree::UiTree;
+use serde::Serialize;
+use std::io;
+use std::io::Write;
+
+/// Binary encodings for snapshots and event streams, each behind its cargo feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryFormat {
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+/// Encodes `record` into `out`. Both formats are self-delimiting, so records written one after
+/// another can be decoded as a stream without extra framing.
+pub fn encode<T: Serialize, W: Write>(format: BinaryFormat, record: &T, out: &mut W) -> io::Result<()> {
+    match format {
+        #[cfg(feature = "msgpack")]
+        BinaryFormat::MessagePack => {
+            // named fields keep the records compatible with the JSON schema
+            rmp_serde::encode::write_named(out, record).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+        #[cfg(feature = "cbor")]
+        BinaryFormat::Cbor => ciborium::into_writer(record, out).map_err(|e| match e {
+            ciborium::ser::Error::Io(e) => e,
+            e => io::Error::new(io::ErrorKind::InvalidData, e.to_string()),
+        }),
+    }
+}
+
+impl UiTree {
+    /// Serializes the tree with the versioned schema of [`crate::export::json::UiTreeDocument`].
+    pub fn to_binary(&self, format: BinaryFormat) -> io::Result<Vec<u8>> {
+        let mut out = vec![];
+        encode(format, &self.to_document(), &mut out)?;
+        Ok(out)
+    }
+}
+
+/// Binary counterpart of [`crate::export::jsonl::JsonLinesWriter`].
+pub struct BinaryWriter<W: Write> {
+    out: W,
+    format: BinaryFormat,
+}
+
+impl<W: Write> BinaryWriter<W> {
+    pub fn new(out: W, format: BinaryFormat) -> Self {
+        BinaryWriter { out, format }
+    }
+
+    pub fn write<T: Serialize>(&mut self, record: &T) -> io::Result<()> {
+        encode(self.format, record, &mut self.out)?;
+        self.out.flush()
+    }
+}
@@ -0,0 +1,156 @@
+use crate::readers::industry::{IndustryJob, IndustryJobs};
+use crate::readers::inventory::{InventoryItem, ShipHangar};
+use crate::readers::local::{LocalChat, LocalMember};
+use crate::readers::lp_store::{LpOffer, LpStore};
+use crate::readers::mining::{LedgerEntry, MiningReadout, SurveyResult};
+use crate::readers::targets::{Target, Targets};
+use std::io;
+use std::io::Write;
+
+/// A row of a tabular readout.
+///
+/// Column names are snake_case and carry their unit where there is one (`distance_m`,
+/// `remaining_s`), empty cells stand for values the client did not show.
+pub trait CsvRow {
+    const COLUMNS: &'static [&'static str];
+
+    fn cells(&self) -> Vec<String>;
+}
+
+/// Writes a header line followed by one line per row.
+pub fn write_csv<R: CsvRow, W: Write>(rows: &[R], writer: W) -> io::Result<()> {
+    let mut out = csv::Writer::from_writer(writer);
+    out.write_record(R::COLUMNS)?;
+    for row in rows {
+        out.write_record(row.cells())?;
+    }
+    out.flush()
+}
+
+fn cell<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+impl CsvRow for LocalMember {
+    const COLUMNS: &'static [&'static str] = &["name", "standing"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.name.clone(), format!("{:?}", self.standing).to_lowercase()]
+    }
+}
+
+impl CsvRow for IndustryJob {
+    const COLUMNS: &'static [&'static str] =
+        &["blueprint", "activity", "runs", "facility", "end_time", "remaining_s", "status"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.blueprint.clone(),
+            cell(&self.activity),
+            cell(&self.runs),
+            cell(&self.facility),
+            cell(&self.end_time),
+            cell(&self.remaining.map(|remaining| remaining.as_secs())),
+            cell(&self.status),
+        ]
+    }
+}
+
+impl CsvRow for SurveyResult {
+    const COLUMNS: &'static [&'static str] = &["ore", "quantity", "distance_m"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.ore.clone(), cell(&self.quantity), cell(&self.distance)]
+    }
+}
+
+impl CsvRow for LedgerEntry {
+    const COLUMNS: &'static [&'static str] = &["ore", "quantity", "volume_m3"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.ore.clone(), cell(&self.quantity), cell(&self.volume)]
+    }
+}
+
+impl CsvRow for LpOffer {
+    const COLUMNS: &'static [&'static str] = &["item", "quantity", "lp_cost", "isk_cost", "required_items"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.item.clone(),
+            cell(&self.quantity),
+            cell(&self.lp_cost),
+            cell(&self.isk_cost),
+            self.required_items.join("; "),
+        ]
+    }
+}
+
+impl CsvRow for InventoryItem {
+    const COLUMNS: &'static [&'static str] = &["name", "type_id", "type", "quantity", "is_active_ship"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            cell(&self.type_id),
+            cell(&self.type_name),
+            cell(&self.quantity),
+            self.is_active_ship.to_string(),
+        ]
+    }
+}
+
+impl CsvRow for Target {
+    const COLUMNS: &'static [&'static str] = &["name", "type", "distance_m", "is_active"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            cell(&self.name),
+            cell(&self.type_name),
+            cell(&self.distance),
+            self.is_active.to_string(),
+        ]
+    }
+}
+
+impl LocalChat {
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(&self.members, writer)
+    }
+}
+
+impl IndustryJobs {
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(&self.jobs, writer)
+    }
+}
+
+impl MiningReadout {
+    /// Writes the survey scanner results, see [`MiningReadout::ledger_to_csv`] for the ledger.
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(&self.survey, writer)
+    }
+
+    pub fn ledger_to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(&self.ledger, writer)
+    }
+}
+
+impl LpStore {
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(&self.offers, writer)
+    }
+}
+
+impl ShipHangar {
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(&self.ships, writer)
+    }
+}
+
+impl Targets {
+    /// Writes the locked targets.
+    pub fn to_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_csv(&self.locked, writer)
+    }
+}
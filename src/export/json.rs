@@ -0,0 +1,74 @@
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use serde::{Deserialize, Serialize};
+
+/// Version of the JSON schema produced by [`UiTree::to_json`].
+///
+/// Bumped on any change that can break consumers: removed or renamed fields, changed types or
+/// semantics. Adding optional fields does not bump the version.
+pub const UI_TREE_SCHEMA_VERSION: u32 = 1;
+
+/// Top level JSON document.
+///
+/// ```json
+/// { "schema_version": 1, "root": { ... } }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiTreeDocument {
+    pub schema_version: u32,
+    pub root: UiNodeDocument,
+}
+
+/// A UI object.
+///
+/// - `type`: python type name of the object, e.g. `ShipUI`
+/// - `name`: the `_name` attribute, `null` if unset
+/// - `addr`: address of the object in the client, only stable during one session
+/// - `rect`: `{x, y, width, height}` in client coordinates
+/// - `visible`: `false` if the object itself is hidden, children of hidden objects keep their own flag
+/// - `text`, `hint`: label text and tooltip with the client markup kept, `null` if unset
+/// - `children`: child objects in tree order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiNodeDocument {
+    #[serde(rename = "type")]
+    pub py_type: String,
+    pub name: Option<String>,
+    pub addr: u64,
+    pub rect: UiRect,
+    pub visible: bool,
+    pub text: Option<String>,
+    pub hint: Option<String>,
+    pub children: Vec<UiNodeDocument>,
+}
+
+impl From<&UiNode> for UiNodeDocument {
+    fn from(node: &UiNode) -> Self {
+        UiNodeDocument {
+            py_type: node.py_type.clone(),
+            name: node.name().map(str::to_string),
+            addr: node.addr,
+            rect: node.rect,
+            visible: node.is_visible(),
+            text: node.text().map(str::to_string),
+            hint: node.hint().map(str::to_string),
+            children: node.children.iter().map(UiNodeDocument::from).collect(),
+        }
+    }
+}
+
+impl UiTree {
+    pub fn to_document(&self) -> UiTreeDocument {
+        UiTreeDocument {
+            schema_version: UI_TREE_SCHEMA_VERSION,
+            root: UiNodeDocument::from(&self.root),
+        }
+    }
+
+    /// Serializes the tree with the versioned schema of [`UiTreeDocument`].
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.to_document())
+    }
+
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.to_document())
+    }
+}
@@ -0,0 +1,75 @@
+use crate::eve_process::ui_diff::UiChange;
+use crate::eve_process::ui_watcher::UiUpdate;
+use crate::export::json::UiTreeDocument;
+use serde::Serialize;
+use std::io;
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A record of the JSON-lines watch output, `time` is in seconds since the unix epoch.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WatchEvent {
+    Snapshot { time: f64, tree: UiTreeDocument },
+    Change { time: f64, change: UiChange },
+    /// Output of a reader, `reader` names the readout type.
+    Reading {
+        time: f64,
+        reader: String,
+        value: serde_json::Value,
+    },
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+impl WatchEvent {
+    /// Converts a watcher update into records, either one snapshot or one record per change.
+    pub fn from_update(update: &UiUpdate, snapshots: bool) -> Vec<WatchEvent> {
+        let time = now();
+        if snapshots {
+            vec![WatchEvent::Snapshot {
+                time,
+                tree: update.tree.to_document(),
+            }]
+        } else {
+            update
+                .changes
+                .iter()
+                .map(|change| WatchEvent::Change {
+                    time,
+                    change: change.clone(),
+                })
+                .collect()
+        }
+    }
+
+    pub fn reading<T: Serialize>(reader: &str, value: &T) -> io::Result<WatchEvent> {
+        Ok(WatchEvent::Reading {
+            time: now(),
+            reader: reader.to_string(),
+            value: serde_json::to_value(value)?,
+        })
+    }
+}
+
+/// Writes one JSON object per line and flushes after each, so the output can be piped.
+pub struct JsonLinesWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(out: W) -> Self {
+        JsonLinesWriter { out }
+    }
+
+    pub fn write<T: Serialize>(&mut self, record: &T) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, record)?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+}
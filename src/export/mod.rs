@@ -0,0 +1,10 @@
+pub mod accessibility;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+pub mod binary;
+pub mod csv;
+pub mod json;
+pub mod jsonl;
+pub mod overlay;
+pub mod pretty;
+#[cfg(feature = "shared-memory")]
+pub mod shared_memory;
\ No newline at end of file
@@ -0,0 +1,128 @@
+use crate::eve_process::screen::ScreenMapping;
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use crate::readers::strip_tags;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayElementKind {
+    Label,
+    /// A gauge or progress bar, `value` is its fill from 0 to 1.
+    Gauge,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayElement {
+    pub addr: u64,
+    pub kind: OverlayElementKind,
+    /// Virtual desktop coordinates.
+    pub rect: UiRect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    /// Movement since the previous frame in pixels per second, renderers can extrapolate the
+    /// position with it until the next frame arrives.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<(f64, f64)>,
+}
+
+/// One frame of the overlay feed, serialized as a flat JSON object for browser sources.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayFrame {
+    pub sequence: u64,
+    /// Seconds since the unix epoch.
+    pub time: f64,
+    /// Position of the client area on the virtual desktop, already applied to all element rects.
+    pub origin: (i32, i32),
+    /// Physical pixels per UI unit, already applied to all element rects.
+    pub scale: f64,
+    /// Expected time until the next frame, the span renderers should interpolate over.
+    pub next_frame_ms: u64,
+    pub elements: Vec<OverlayElement>,
+}
+
+fn element(node: &UiNode) -> Option<(OverlayElementKind, Option<String>, Option<f64>)> {
+    if node.py_type.contains("Gauge") || node.py_type.contains("ProgressBar") {
+        let value = node.attr_f64("_value").map(|value| value.clamp(0.0, 1.0));
+        return Some((OverlayElementKind::Gauge, node.text().map(strip_tags), value));
+    }
+    let text = strip_tags(node.text()?);
+    let text = text.trim();
+    (!text.is_empty()).then(|| (OverlayElementKind::Label, Some(text.to_string()), None))
+}
+
+/// Turns UI trees into [`OverlayFrame`]s of the visible labels and gauges, at most one frame per
+/// `min_interval`.
+#[derive(Debug)]
+pub struct OverlayFeed {
+    pub min_interval: Duration,
+    sequence: u64,
+    last_frame: Option<Instant>,
+    /// Rects of the previous frame, for the velocity hints.
+    previous: HashMap<u64, UiRect>,
+}
+
+impl OverlayFeed {
+    pub fn new(min_interval: Duration) -> Self {
+        OverlayFeed {
+            min_interval,
+            sequence: 0,
+            last_frame: None,
+            previous: HashMap::new(),
+        }
+    }
+
+    /// Builds the next frame, or returns `None` if the last one is younger than `min_interval`.
+    pub fn frame(&mut self, tree: &UiTree, screen: &ScreenMapping) -> Option<OverlayFrame> {
+        let now = Instant::now();
+        let elapsed = self.last_frame.map(|last| now - last);
+        if elapsed.is_some_and(|elapsed| elapsed < self.min_interval) {
+            return None;
+        }
+        let mut elements = vec![];
+        let mut visible = vec![&tree.root];
+        while let Some(node) = visible.pop() {
+            if !node.is_visible() {
+                continue;
+            }
+            visible.extend(node.children.iter().rev());
+            let Some((kind, text, value)) = element(node) else { continue };
+            let rect = node.screen_rect(screen);
+            let velocity = match (self.previous.get(&node.addr), elapsed) {
+                (Some(previous), Some(elapsed)) if previous != &rect => {
+                    let seconds = elapsed.as_secs_f64();
+                    Some((
+                        (rect.x - previous.x) as f64 / seconds,
+                        (rect.y - previous.y) as f64 / seconds,
+                    ))
+                }
+                _ => None,
+            };
+            elements.push(OverlayElement {
+                addr: node.addr,
+                kind,
+                rect,
+                text,
+                value,
+                velocity,
+            });
+        }
+        self.previous = elements.iter().map(|element| (element.addr, element.rect)).collect();
+        self.last_frame = Some(now);
+        self.sequence += 1;
+        Some(OverlayFrame {
+            sequence: self.sequence,
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64(),
+            origin: screen.origin,
+            scale: screen.scale,
+            next_frame_ms: self.min_interval.as_millis() as u64,
+            elements,
+        })
+    }
+}
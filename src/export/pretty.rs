@@ -0,0 +1,103 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use std::io;
+use std::io::Write;
+#[cfg(windows)]
+use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+#[cfg(windows)]
+use winapi::um::processenv::GetStdHandle;
+#[cfg(windows)]
+use winapi::um::winbase::STD_OUTPUT_HANDLE;
+#[cfg(windows)]
+use winapi::um::wincon::ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+
+const TYPE: &str = "\x1b[36m";
+const NAME: &str = "\x1b[33m";
+const TEXT: &str = "\x1b[32m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Turns on escape code processing of the console stdout is attached to, returns `false` if it is
+/// not a console or the console is too old for colors.
+#[cfg(windows)]
+pub fn enable_ansi_colors() -> bool {
+    unsafe {
+        let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut mode = 0;
+        GetConsoleMode(handle, &mut mode) != 0 && SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+    }
+}
+
+/// Terminals outside of Windows handle escape codes on their own.
+#[cfg(not(windows))]
+pub fn enable_ansi_colors() -> bool {
+    true
+}
+
+/// Renders UI trees as indented text, one node per line: type, name, text, rect and whether it is
+/// hidden.
+pub struct TreePrinter<W: Write> {
+    out: W,
+    /// Colors the output with ANSI escape codes.
+    pub color: bool,
+}
+
+impl<W: Write> TreePrinter<W> {
+    pub fn new(out: W, color: bool) -> Self {
+        TreePrinter { out, color }
+    }
+
+    fn paint(&self, style: &'static str) -> &'static str {
+        if self.color {
+            style
+        } else {
+            ""
+        }
+    }
+
+    fn write_node(&mut self, node: &UiNode, prefix: &str, branch: &str) -> io::Result<()> {
+        let (type_color, name_color, text_color, dim, reset) =
+            (self.paint(TYPE), self.paint(NAME), self.paint(TEXT), self.paint(DIM), self.paint(RESET));
+        // hidden subtrees are dimmed as a whole
+        let line_style = if node.is_visible() { "" } else { dim };
+        write!(self.out, "{}{}{}{}{}{}", dim, prefix, branch, reset, line_style, type_color)?;
+        write!(self.out, "{}{}{}", node.py_type, reset, line_style)?;
+        if let Some(name) = node.name() {
+            write!(self.out, " {}{}{}{}", name_color, name, reset, line_style)?;
+        }
+        if let Some(text) = node.text() {
+            write!(self.out, " {}{:?}{}{}", text_color, text, reset, line_style)?;
+        }
+        let rect = node.rect;
+        write!(self.out, " {}{},{} {}x{}{}", dim, rect.x, rect.y, rect.width, rect.height, reset)?;
+        if !node.is_visible() {
+            write!(self.out, " {}(hidden){}", dim, reset)?;
+        }
+        writeln!(self.out)?;
+
+        let child_prefix = match branch {
+            "├─ " => format!("{}│  ", prefix),
+            "└─ " => format!("{}   ", prefix),
+            _ => prefix.to_string(),
+        };
+        for (i, child) in node.children.iter().enumerate() {
+            let branch = if i + 1 == node.children.len() { "└─ " } else { "├─ " };
+            self.write_node(child, &child_prefix, branch)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_tree(&mut self, tree: &UiTree) -> io::Result<()> {
+        self.write_node(&tree.root, "", "")?;
+        self.out.flush()
+    }
+}
+
+impl UiTree {
+    /// The tree as indented text without colors, see [`TreePrinter`].
+    pub fn to_pretty_string(&self) -> String {
+        let mut printer = TreePrinter::new(vec![], false);
+        // writing to a vec does not fail
+        let _ = printer.write_tree(self);
+        String::from_utf8_lossy(&printer.out).into_owned()
+    }
+}
@@ -0,0 +1,104 @@
+use memmap2::{Mmap, MmapMut};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+
+/// `"PYEVE\0\0\x01"`, the last byte is the layout version.
+const MAGIC: u64 = u64::from_le_bytes(*b"PYEVE\0\0\x01");
+/// magic, sequence number, payload length, each a little endian u64.
+const HEADER_LEN: usize = 24;
+
+fn header(map: &[u8], index: usize) -> &AtomicU64 {
+    // the mapping is page aligned and the header fields are 8 byte aligned within it
+    unsafe { &*(map.as_ptr().add(index * 8) as *const AtomicU64) }
+}
+
+/// Publishes the latest snapshot as JSON into a memory-mapped file.
+///
+/// Readers follow a sequence lock: the sequence number is odd while a snapshot is being written
+/// and bumped to the next even number once it is complete. A reader copies the payload and keeps
+/// it only if the sequence number was even and unchanged before and after the copy.
+pub struct SharedMemoryPublisher {
+    map: MmapMut,
+}
+
+impl SharedMemoryPublisher {
+    /// Creates or truncates `path` with room for `capacity` payload bytes.
+    pub fn create<P: AsRef<Path>>(path: P, capacity: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len((HEADER_LEN + capacity) as u64)?;
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        header(&map, 0).store(MAGIC, Ordering::Relaxed);
+        header(&map, 1).store(0, Ordering::Relaxed);
+        header(&map, 2).store(0, Ordering::Relaxed);
+        Ok(SharedMemoryPublisher { map })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.map.len() - HEADER_LEN
+    }
+
+    /// Sequence number of the last complete snapshot, 0 before the first one.
+    pub fn sequence(&self) -> u64 {
+        header(&self.map, 1).load(Ordering::Relaxed)
+    }
+
+    pub fn publish_bytes(&mut self, payload: &[u8]) -> io::Result<u64> {
+        if payload.len() > self.capacity() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("snapshot of {} bytes exceeds the capacity of {}", payload.len(), self.capacity()),
+            ));
+        }
+        let sequence = self.sequence();
+        header(&self.map, 1).store(sequence + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.map[HEADER_LEN..HEADER_LEN + payload.len()].copy_from_slice(payload);
+        header(&self.map, 2).store(payload.len() as u64, Ordering::Relaxed);
+        header(&self.map, 1).store(sequence + 2, Ordering::Release);
+        Ok(sequence + 2)
+    }
+
+    /// Publishes `snapshot` as JSON and returns its sequence number.
+    pub fn publish<T: Serialize>(&mut self, snapshot: &T) -> io::Result<u64> {
+        self.publish_bytes(&serde_json::to_vec(snapshot)?)
+    }
+}
+
+/// Reading side of [`SharedMemoryPublisher`], mostly useful for consumers written in Rust and as
+/// a reference for the protocol.
+pub struct SharedMemoryReader {
+    map: Mmap,
+}
+
+impl SharedMemoryReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let map = unsafe { Mmap::map(&File::open(path)?)? };
+        if map.len() < HEADER_LEN || header(&map, 0).load(Ordering::Relaxed) != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a pyevereader snapshot file"));
+        }
+        Ok(SharedMemoryReader { map })
+    }
+
+    /// Copies the latest complete snapshot if it is newer than `seen`.
+    pub fn read_newer(&self, seen: u64) -> Option<(u64, Vec<u8>)> {
+        loop {
+            let before = header(&self.map, 1).load(Ordering::Acquire);
+            if before == seen || before == 0 {
+                return None;
+            }
+            if before % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let len = (header(&self.map, 2).load(Ordering::Relaxed) as usize).min(self.map.len() - HEADER_LEN);
+            let payload = self.map[HEADER_LEN..HEADER_LEN + len].to_vec();
+            fence(Ordering::Acquire);
+            if header(&self.map, 1).load(Ordering::Relaxed) == before {
+                return Some((before, payload));
+            }
+        }
+    }
+}
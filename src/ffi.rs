@@ -0,0 +1,126 @@
+//! C ABI for embedding the reader, declared in `include/pyevereader.h`.
+//!
+//! Strings returned by this module are UTF-8, NUL terminated and owned by the caller, who has to
+//! release them with [`pyevereader_free_string`].
+
+use crate::eve_process::eve_process::EVEProcess;
+use crate::readers;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::io;
+use std::ptr;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+}
+
+fn into_c_string(result: io::Result<String>) -> *mut c_char {
+    match result.and_then(|s| CString::new(s).map_err(io::Error::from)) {
+        Ok(s) => s.into_raw(),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// An attached client, opaque to C.
+pub struct PyEveSession {
+    process: EVEProcess,
+}
+
+/// Opens and initializes the EVE client with the given pid, returns NULL on failure.
+#[no_mangle]
+pub extern "C" fn pyevereader_attach(pid: u32) -> *mut PyEveSession {
+    let attach = || -> io::Result<PyEveSession> {
+        let mut process = EVEProcess::open(pid)?;
+        process
+            .init()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "python type object not found"))?;
+        Ok(PyEveSession { process })
+    };
+    match attach() {
+        Ok(session) => Box::into_raw(Box::new(session)),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Releases a session returned by [`pyevereader_attach`], NULL is ignored.
+///
+/// # Safety
+/// `session` must come from [`pyevereader_attach`] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn pyevereader_detach(session: *mut PyEveSession) {
+    if !session.is_null() {
+        drop(Box::from_raw(session));
+    }
+}
+
+/// Reads the UI tree as JSON (see `export::json`), returns NULL on failure.
+///
+/// # Safety
+/// `session` must be a live session from [`pyevereader_attach`].
+#[no_mangle]
+pub unsafe extern "C" fn pyevereader_read_ui_json(session: *mut PyEveSession) -> *mut c_char {
+    let Some(session) = session.as_mut() else {
+        set_last_error("session is NULL".to_string());
+        return ptr::null_mut();
+    };
+    into_c_string(
+        session
+            .process
+            .ui_tree()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no UI root found"))
+            .and_then(|tree| tree.to_json().map_err(io::Error::from)),
+    )
+}
+
+/// Runs the reader called `reader` (see `readers::READER_NAMES`) and returns its output as JSON,
+/// `null` when it found nothing to read. Returns NULL on failure.
+///
+/// # Safety
+/// `session` must be a live session from [`pyevereader_attach`], `reader` a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pyevereader_read_json(session: *mut PyEveSession, reader: *const c_char) -> *mut c_char {
+    let Some(session) = session.as_mut() else {
+        set_last_error("session is NULL".to_string());
+        return ptr::null_mut();
+    };
+    if reader.is_null() {
+        set_last_error("reader is NULL".to_string());
+        return ptr::null_mut();
+    }
+    let reader = CStr::from_ptr(reader).to_string_lossy();
+    into_c_string(
+        session
+            .process
+            .ui_tree()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no UI root found"))
+            .and_then(|tree| readers::read_json(&tree, &reader))
+            .map(|value| value.unwrap_or_default().to_string()),
+    )
+}
+
+/// Message of the last failure on the calling thread, NULL if there was none.
+#[no_mangle]
+pub extern "C" fn pyevereader_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| last.borrow().clone().map_or(ptr::null_mut(), CString::into_raw))
+}
+
+/// Releases a string returned by this library, NULL is ignored.
+///
+/// # Safety
+/// `s` must come from this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn pyevereader_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A value kept in a [`History`].
+#[derive(Debug, Clone)]
+pub struct Timestamped<T> {
+    /// Seconds since the unix epoch.
+    pub time: f64,
+    pub instant: Instant,
+    pub value: T,
+}
+
+/// Ring buffer of the values of the last `retention`, like UI trees or reader outputs, so that
+/// whoever handles an event can look at what happened right before it.
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    pub retention: Duration,
+    /// Upper bound on the number of values regardless of their age.
+    pub max_len: usize,
+    entries: VecDeque<Timestamped<T>>,
+}
+
+impl<T> History<T> {
+    pub fn new(retention: Duration) -> Self {
+        History {
+            retention,
+            max_len: usize::MAX,
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        let now = Instant::now();
+        self.entries.push_back(Timestamped {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64(),
+            instant: now,
+            value,
+        });
+        while self.entries.len() > self.max_len
+            || self
+                .entries
+                .front()
+                .is_some_and(|oldest| now - oldest.instant > self.retention)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Values of the last `span`, oldest first.
+    pub fn history(&self, span: Duration) -> impl DoubleEndedIterator<Item = &Timestamped<T>> {
+        let now = Instant::now();
+        self.entries.iter().filter(move |entry| now - entry.instant <= span)
+    }
+
+    /// The value that was current `ago` before now.
+    pub fn at(&self, ago: Duration) -> Option<&Timestamped<T>> {
+        let now = Instant::now();
+        self.entries.iter().rev().find(|entry| now - entry.instant >= ago)
+    }
+
+    pub fn latest(&self) -> Option<&Timestamped<T>> {
+        self.entries.back()
+    }
+
+    /// All values, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Timestamped<T>> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,82 @@
+use std::io;
+use std::ptr;
+use std::str::FromStr;
+use winapi::shared::minwindef::UINT;
+use winapi::um::winuser::{
+    GetMessageW, RegisterHotKey, UnregisterHotKey, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, MSG,
+    VK_F1, VK_HOME, VK_INSERT, VK_PAUSE, VK_SNAPSHOT, WM_HOTKEY,
+};
+
+/// A key combination like `Ctrl+Shift+F12`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hotkey {
+    pub modifiers: u32,
+    pub vk: u32,
+}
+
+impl FromStr for Hotkey {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("invalid hotkey `{}`", s));
+        let mut hotkey = Hotkey { modifiers: 0, vk: 0 };
+        for part in s.split('+').map(str::trim) {
+            let upper = part.to_ascii_uppercase();
+            match upper.as_str() {
+                "CTRL" | "CONTROL" => hotkey.modifiers |= MOD_CONTROL as u32,
+                "ALT" => hotkey.modifiers |= MOD_ALT as u32,
+                "SHIFT" => hotkey.modifiers |= MOD_SHIFT as u32,
+                "WIN" => hotkey.modifiers |= MOD_WIN as u32,
+                "PRINTSCREEN" => hotkey.vk = VK_SNAPSHOT as u32,
+                "PAUSE" => hotkey.vk = VK_PAUSE as u32,
+                "INSERT" => hotkey.vk = VK_INSERT as u32,
+                "HOME" => hotkey.vk = VK_HOME as u32,
+                // letters and digits use their ASCII code
+                key if key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                    hotkey.vk = key.as_bytes()[0] as u32
+                }
+                key => match key.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+                    Some(n @ 1..=24) => hotkey.vk = VK_F1 as u32 + n - 1,
+                    _ => return Err(invalid()),
+                },
+            }
+        }
+        if hotkey.vk == 0 {
+            return Err(invalid());
+        }
+        Ok(hotkey)
+    }
+}
+
+/// Registers `hotkeys` system wide and calls `callback` with the index of the one pressed, until
+/// it returns `false`. Blocks the calling thread, which receives the key presses.
+pub fn listen<F: FnMut(usize) -> bool>(hotkeys: &[Hotkey], mut callback: F) -> io::Result<()> {
+    for (id, hotkey) in hotkeys.iter().enumerate() {
+        let ok = unsafe {
+            RegisterHotKey(ptr::null_mut(), id as i32, hotkey.modifiers as UINT | MOD_NOREPEAT as UINT, hotkey.vk)
+        };
+        if ok == 0 {
+            let e = io::Error::last_os_error();
+            unregister(id);
+            return Err(e);
+        }
+    }
+    let mut msg: MSG = unsafe { std::mem::zeroed() };
+    let result = loop {
+        match unsafe { GetMessageW(&mut msg, ptr::null_mut(), 0, 0) } {
+            -1 => break Err(io::Error::last_os_error()),
+            0 => break Ok(()),
+            _ if msg.message == WM_HOTKEY && !callback(msg.wParam) => break Ok(()),
+            _ => {}
+        }
+    };
+    unregister(hotkeys.len());
+    result
+}
+
+/// Unregisters the first `count` hotkeys of this thread.
+fn unregister(count: usize) {
+    for id in 0..count {
+        unsafe { UnregisterHotKey(ptr::null_mut(), id as i32) };
+    }
+}
@@ -1,2 +1,25 @@
+#[cfg(feature = "async")]
+pub mod async_api;
 pub mod eve_process;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod history;
+#[cfg(all(windows, feature = "hotkey"))]
+pub mod hotkey;
+pub mod logging;
 pub mod py_wrapper;
+pub mod readers;
+pub mod recording;
+#[cfg(feature = "repl")]
+pub mod repl;
+pub mod rules;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(feature = "sde")]
+pub mod sde;
+pub mod server;
+#[cfg(feature = "sqlite")]
+pub mod storage;
+#[cfg(feature = "tui")]
+pub mod tui;
\ No newline at end of file
@@ -0,0 +1,82 @@
+use std::io;
+use std::str::FromStr;
+use tracing::level_filters::LevelFilter;
+use tracing::Subscriber;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, Layer};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines.
+    #[default]
+    Text,
+    /// One JSON object per event, for log collectors.
+    Json,
+}
+
+/// Where and how much the crate logs, see [`init`] and [`layer`].
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    /// Level of modules without their own entry in `modules`.
+    pub level: LevelFilter,
+    /// Levels of single modules and their submodules, like `("pyevereader::eve_process", DEBUG)`.
+    pub modules: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            format: LogFormat::Text,
+            level: LevelFilter::INFO,
+            modules: vec![],
+        }
+    }
+}
+
+impl LogConfig {
+    /// Applies comma separated directives like `warn,pyevereader::readers=debug`, where a bare
+    /// level sets `level` and `module=level` adds to `modules`.
+    pub fn with_directives(mut self, directives: &str) -> io::Result<Self> {
+        let invalid = |directive: &str| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid log directive {:?}", directive));
+        for directive in directives.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let level = LevelFilter::from_str(level).map_err(|_| invalid(directive))?;
+                    self.modules.push((module.to_string(), level));
+                }
+                None => self.level = LevelFilter::from_str(directive).map_err(|_| invalid(directive))?,
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn targets(&self) -> Targets {
+        Targets::new()
+            .with_default(self.level)
+            .with_targets(self.modules.iter().cloned())
+    }
+}
+
+/// A stderr layer for embedding applications that compose their own subscriber.
+pub fn layer<S>(config: &LogConfig) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let layer = fmt::layer().with_writer(io::stderr);
+    match config.format {
+        LogFormat::Text => layer.with_filter(config.targets()).boxed(),
+        LogFormat::Json => layer.json().with_filter(config.targets()).boxed(),
+    }
+}
+
+/// Installs [`layer`] as the global subscriber, fails if there already is one.
+pub fn init(config: &LogConfig) -> io::Result<()> {
+    tracing_subscriber::registry()
+        .with(layer(config))
+        .try_init()
+        .map_err(io::Error::other)
+}
@@ -1,27 +1,456 @@
-use timeit::timeit_loops;
-use pyevereader::eve_process::eve_process::EVEProcess;
+use clap::{Args, Parser, Subcommand};
+use pyevereader::eve_process::eve_process::{EVEProcess, StrEncoding};
+use pyevereader::eve_process::scan::{ProgressCallback, ScanProgress};
+use pyevereader::eve_process::screen::ScreenMapping;
+use pyevereader::eve_process::type_stats::TypeSupport;
+use pyevereader::eve_process::ui_tree::UiTree;
+use pyevereader::eve_process::ui_watcher::UiWatcher;
+use pyevereader::export::json::UiNodeDocument;
+use pyevereader::export::jsonl::{JsonLinesWriter, WatchEvent};
+use pyevereader::export::overlay::OverlayFeed;
+use pyevereader::export::pretty::{enable_ansi_colors, TreePrinter};
+use pyevereader::logging::{self, LogConfig, LogFormat};
+use pyevereader::recording::{Playback, Recorder};
+use pyevereader::rules::sinks::LogSink;
+use pyevereader::rules::{load_rules, AlertEngine};
+#[cfg(feature = "daemon")]
+use pyevereader::server::daemon::DaemonConfig;
+#[cfg(feature = "websocket")]
+use pyevereader::server::websocket::PushServer;
+use std::env;
+use std::fs;
 use std::io;
-use timeit::timeit;
-use rayon::prelude::*;
+use std::io::IsTerminal;
+#[cfg(feature = "websocket")]
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Reads the UI of running EVE clients.
+#[derive(Parser)]
+#[command(name = "pyevereader", version)]
+struct Cli {
+    #[command(flatten)]
+    target: Target,
+    /// Threads used for scanning memory.
+    #[arg(long, global = true, default_value_t = 4)]
+    threads: usize,
+    /// Log levels like `warn,pyevereader::readers=debug`.
+    #[arg(long, global = true, default_value = "info")]
+    log: String,
+    /// Log JSON objects instead of text lines.
+    #[arg(long, global = true)]
+    log_json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Global options choosing the client the commands read and how.
+#[derive(Args)]
+struct Target {
+    /// Client to attach to, defaults to the first one found.
+    #[arg(long, global = true)]
+    pid: Option<u32>,
+    /// Read a memory dump written by `dump-memory`, or a Windows minidump ending with `.dmp`,
+    /// instead of a running client.
+    #[arg(long, global = true)]
+    dump: Option<PathBuf>,
+    /// Encoding of byte strings in the client: `auto`, `utf8` or `gbk`.
+    #[arg(long, global = true, default_value = "auto")]
+    encoding: StrEncoding,
+    /// Scan a running client through buffers of this many MiB instead of copying its memory.
+    #[arg(long, global = true)]
+    memory_budget: Option<usize>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List running EVE clients.
+    List,
+    /// Attach to a client and print the python type objects found.
+    Attach,
+    /// Print the UI tree as JSON.
+    DumpUi {
+        /// Print an indented tree instead, colored when writing to a terminal.
+        #[arg(long)]
+        pretty: bool,
+        /// Never color the `--pretty` output.
+        #[arg(long)]
+        no_color: bool,
+    },
+    /// Stream UI changes as JSON lines.
+    Watch {
+        /// Milliseconds between readouts.
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+        /// Emit the whole tree on every readout instead of the changes.
+        #[arg(long)]
+        snapshots: bool,
+    },
+    /// Record the UI tree for playing it back with `replay`, gzip compressed if the file ends with `.gz`.
+    Record {
+        output: PathBuf,
+        /// Milliseconds between readouts.
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+    /// Play back a recording and stream its changes like `watch`.
+    Replay {
+        input: PathBuf,
+        /// Factor applied to the recorded pace, 0 plays without pauses.
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+        /// Emit the whole tree on every frame instead of the changes.
+        #[arg(long)]
+        snapshots: bool,
+    },
+    /// Print the focused window as plain text in reading order, for screen readers.
+    FocusText {
+        /// Keep running and print the window again whenever its text changes.
+        #[arg(long)]
+        watch: bool,
+        /// Milliseconds between readouts with `--watch`.
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+    /// Stream overlay frames with screen coordinates as JSON lines.
+    Overlay {
+        /// Maximum frames per second.
+        #[arg(long, default_value_t = 10)]
+        fps: u64,
+        /// Serve the frames over WebSocket on this address instead, e.g. for OBS browser sources.
+        #[cfg(feature = "websocket")]
+        #[arg(long)]
+        websocket: Option<SocketAddr>,
+    },
+    /// Evaluate alert rules from a JSON file on every readout and print the alerts as JSON lines.
+    Alerts {
+        rules: PathBuf,
+        /// Milliseconds between readouts.
+        #[arg(long, default_value_t = 1000)]
+        interval: u64,
+        /// Also post the alerts to this URL.
+        #[cfg(feature = "webhook")]
+        #[arg(long)]
+        webhook: Option<String>,
+        /// Also play the rules' sounds, or a beep for rules without one.
+        #[cfg(feature = "audio")]
+        #[arg(long)]
+        sound: bool,
+    },
+    /// Print the UI nodes matching a path like `**/OverView/**/OverviewScrollEntry`, one JSON object per line.
+    Query { path: String },
+    /// Print the addresses of the type objects with the given name.
+    FindType { name: String },
+    /// Print all python types with their instance counts and whether the parsers handle them.
+    Types {
+        /// Only list the types the parsers keep by reference.
+        #[arg(long)]
+        unhandled: bool,
+    },
+    /// Keep all clients attached and serve them over gRPC and WebSocket until killed.
+    #[cfg(feature = "daemon")]
+    Daemon {
+        #[arg(long, default_value = "127.0.0.1:50051")]
+        grpc: SocketAddr,
+        #[arg(long, default_value = "127.0.0.1:8765")]
+        websocket: SocketAddr,
+        /// Milliseconds between UI readouts.
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+    /// Explore process memory with an interactive prompt.
+    #[cfg(feature = "repl")]
+    Repl,
+    /// Explore the live UI tree interactively.
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Milliseconds between readouts.
+        #[arg(long, default_value_t = 1000)]
+        interval: u64,
+    },
+    /// Run a rhai script's `on_tick(ui)` on every UI readout.
+    #[cfg(feature = "scripting")]
+    Script {
+        path: PathBuf,
+        /// Milliseconds between readouts.
+        #[arg(long, default_value_t = 1000)]
+        interval: u64,
+    },
+    /// Write a UI tree snapshot whenever a global hotkey is pressed.
+    #[cfg(all(windows, feature = "hotkey"))]
+    Hotkey {
+        #[arg(long, default_value = "Ctrl+Alt+S")]
+        key: String,
+        /// Directory the snapshots are written to.
+        #[arg(long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    /// Write the memory of a client to a file for reading it later with `--dump`, gzip compressed
+    /// if the name ends with `.gz`.
+    DumpMemory { output: PathBuf },
+    /// Write the UI tree to a JSON file.
+    Snapshot {
+        /// Defaults to `ui-<unix time in ms>.json`.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn attach(target: &Target) -> io::Result<EVEProcess> {
+    let open = |pid| match target.memory_budget {
+        Some(mib) => EVEProcess::open_streaming(pid, mib << 20),
+        None => EVEProcess::open(pid),
+    };
+    let mut process = match (target.dump.as_deref(), target.pid) {
+        (Some(dump), _) if dump.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("dmp")) => {
+            EVEProcess::open_minidump(dump)?
+        }
+        (Some(dump), _) => EVEProcess::open_dump(dump)?,
+        (None, Some(pid)) => open(pid)?,
+        (None, None) => {
+            let client = EVEProcess::list_clients()?.into_iter().next();
+            open(client.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no EVE client running"))?.pid)?
+        }
+    };
+    process.str_encoding = target.encoding;
+    let progress = io::stderr().is_terminal();
+    if progress {
+        process.progress = Some(ProgressCallback::new(|progress: ScanProgress| {
+            eprint!("\rscanning {}%", progress.bytes_scanned * 100 / progress.bytes_total.max(1));
+        }));
+    }
+    let found = process.init();
+    if progress {
+        // clears the progress line
+        eprint!("\r{:16}\r", "");
+        process.progress = None;
+    }
+    found.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "python type object not found"))?;
+    Ok(process)
+}
+
+fn ui_tree(process: &mut EVEProcess) -> io::Result<UiTree> {
+    process
+        .ui_tree()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no UI root found"))
+}
+
+/// Writes `tree` to `output`, or to `ui-<unix time>.json` in `dir`.
+fn write_snapshot(tree: &UiTree, output: Option<PathBuf>, dir: &Path) -> io::Result<()> {
+    let output = output.unwrap_or_else(|| {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        dir.join(format!("ui-{}.json", time.as_millis()))
+    });
+    fs::write(&output, tree.to_json_pretty()?)?;
+    eprintln!("wrote {} nodes to {}", tree.node_count(), output.display());
+    Ok(())
+}
 
 #[profiling::function]
 fn main() -> io::Result<()> {
     profiling::scope!("eve");
-    rayon::ThreadPoolBuilder::new().num_threads(4).build_global().unwrap();
-    let mut found: Vec<EVEProcess> = Vec::new();
-    timeit!({
-        found = EVEProcess::list().unwrap();
-    });
-    let mut proc = found.remove(0);
-    timeit!({
-        proc.init();
-    });
-    // let &ui_root_type = proc.search_type("UIRoot", None).get(0).unwrap();
-    println!("type: {}", proc.py_type.upgrade().unwrap().base_addr);
-    println!("UIRoot type: 0x{:X}", proc.ui_root.upgrade().unwrap().base_addr);
-    for ui_root_candidate in proc.search_ui_root(None) {
-        println!("{:?}", ui_root_candidate);
+    let cli = Cli::parse();
+    let format = if cli.log_json { LogFormat::Json } else { LogFormat::Text };
+    logging::init(&LogConfig { format, ..Default::default() }.with_directives(&cli.log)?)?;
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(cli.threads)
+        .build_global()
+        .unwrap();
+    match cli.command {
+        Command::List => {
+            for client in EVEProcess::list_clients()? {
+                println!("{}\t{}\t{}", client.pid, client.title, client.path);
+            }
+        }
+        Command::Attach => {
+            let process = attach(&cli.target)?;
+            println!("type: 0x{:X}", process.py_type.upgrade().unwrap().base_addr);
+            if let Some(ui_root) = process.ui_root.upgrade() {
+                println!("UIRoot type: 0x{:X}", ui_root.base_addr);
+                for ui_root_candidate in process.search_ui_root(None) {
+                    println!("UIRoot: 0x{:X}", ui_root_candidate);
+                }
+            }
+        }
+        Command::DumpUi { pretty, no_color } => {
+            let tree = ui_tree(&mut attach(&cli.target)?)?;
+            if pretty {
+                let stdout = io::stdout();
+                let color =
+                    !no_color && stdout.is_terminal() && env::var_os("NO_COLOR").is_none() && enable_ansi_colors();
+                TreePrinter::new(stdout.lock(), color).write_tree(&tree)?;
+            } else {
+                println!("{}", tree.to_json()?);
+            }
+        }
+        Command::Watch { interval, snapshots } => {
+            let mut out = JsonLinesWriter::new(io::stdout().lock());
+            UiWatcher::new(attach(&cli.target)?, Duration::from_millis(interval)).run(|update| {
+                WatchEvent::from_update(update, snapshots)
+                    .iter()
+                    .all(|event| out.write(event).is_ok())
+            });
+        }
+        Command::Record { output, interval } => {
+            let mut recorder = Recorder::create(output)?;
+            let mut result = Ok(());
+            UiWatcher::new(attach(&cli.target)?, Duration::from_millis(interval)).run(|update| {
+                result = recorder.record(&update.tree);
+                result.is_ok()
+            });
+            result?;
+        }
+        Command::Replay { input, speed, snapshots } => {
+            let mut out = JsonLinesWriter::new(io::stdout().lock());
+            let mut playback = Playback::open(input)?;
+            playback.speed = speed;
+            playback.run(|update| {
+                WatchEvent::from_update(update, snapshots)
+                    .iter()
+                    .all(|event| out.write(event).is_ok())
+            })?;
+        }
+        Command::FocusText { watch, interval } => {
+            let mut process = attach(&cli.target)?;
+            let mut previous = None;
+            loop {
+                let text = process.ui_tree().and_then(|tree| tree.focused_window_text());
+                if text != previous {
+                    // an empty line separates consecutive readouts
+                    println!("{}\n", text.as_deref().unwrap_or("(no window open)"));
+                    previous = text;
+                }
+                if !watch {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(interval));
+            }
+        }
+        Command::Overlay {
+            fps,
+            #[cfg(feature = "websocket")]
+            websocket,
+        } => {
+            let interval = Duration::from_millis(1000 / fps.max(1));
+            let mut feed = OverlayFeed::new(interval);
+            #[cfg(feature = "websocket")]
+            let push = websocket.map(PushServer::bind).transpose()?;
+            let mut out = JsonLinesWriter::new(io::stdout().lock());
+            let mut process = attach(&cli.target)?;
+            loop {
+                let started = Instant::now();
+                let frame = process.ui_tree().and_then(|tree| {
+                    let screen = ScreenMapping::for_tree(&process.process, &tree);
+                    feed.frame(&tree, &screen)
+                });
+                if let Some(frame) = frame {
+                    #[cfg(feature = "websocket")]
+                    match &push {
+                        Some(push) => push.broadcast(&frame).map(|_| ())?,
+                        None => out.write(&frame)?,
+                    }
+                    #[cfg(not(feature = "websocket"))]
+                    out.write(&frame)?;
+                }
+                thread::sleep(interval.saturating_sub(started.elapsed()));
+            }
+        }
+        Command::Alerts {
+            rules,
+            interval,
+            #[cfg(feature = "webhook")]
+            webhook,
+            #[cfg(feature = "audio")]
+            sound,
+        } => {
+            let mut engine = AlertEngine::new(load_rules(rules)?);
+            engine.add_sink(LogSink);
+            engine.add_sink(JsonLinesWriter::new(io::stdout().lock()));
+            #[cfg(feature = "webhook")]
+            if let Some(url) = webhook {
+                engine.add_sink(pyevereader::rules::sinks::WebhookSink { url });
+            }
+            #[cfg(feature = "audio")]
+            if sound {
+                engine.add_sink(pyevereader::rules::sinks::SoundSink::new()?);
+            }
+            UiWatcher::new(attach(&cli.target)?, Duration::from_millis(interval)).run(|update| {
+                engine.evaluate(&update.tree);
+                true
+            });
+        }
+        Command::Query { path } => {
+            let tree = ui_tree(&mut attach(&cli.target)?)?;
+            let mut out = JsonLinesWriter::new(io::stdout().lock());
+            for node in tree.root.query(&path) {
+                out.write(&UiNodeDocument::from(node))?;
+            }
+        }
+        Command::FindType { name } => {
+            for addr in attach(&cli.target)?.search_type(&name, None) {
+                println!("0x{:X}", addr);
+            }
+        }
+        Command::Types { unhandled } => {
+            let mut process = attach(&cli.target)?;
+            let tree = process.ui_tree();
+            for count in process.type_histogram(tree.as_ref()) {
+                if unhandled && count.support != TypeSupport::Unhandled {
+                    continue;
+                }
+                println!("{}\t{:?}\t{}", count.instances, count.support, count.tp_name);
+            }
+        }
+        #[cfg(feature = "daemon")]
+        Command::Daemon { grpc, websocket, interval } => {
+            pyevereader::server::daemon::run(DaemonConfig {
+                grpc: Some(grpc),
+                websocket: Some(websocket),
+                watch_interval: Duration::from_millis(interval),
+                ..Default::default()
+            })?;
+        }
+        #[cfg(feature = "repl")]
+        Command::Repl => pyevereader::repl::run(attach(&cli.target)?)?,
+        #[cfg(feature = "tui")]
+        Command::Tui { interval } => {
+            pyevereader::tui::run(UiWatcher::new(attach(&cli.target)?, Duration::from_millis(interval)))?;
+        }
+        #[cfg(feature = "scripting")]
+        Command::Script { path, interval } => {
+            let mut script = pyevereader::scripting::Script::load(path)?;
+            let mut result = Ok(());
+            UiWatcher::new(attach(&cli.target)?, Duration::from_millis(interval)).run(|update| {
+                result = script.tick(&update.tree);
+                result.is_ok()
+            });
+            result?;
+        }
+        #[cfg(all(windows, feature = "hotkey"))]
+        Command::Hotkey { key, output_dir } => {
+            let mut process = attach(&cli.target)?;
+            eprintln!("press {} to write a snapshot", key);
+            pyevereader::hotkey::listen(&[key.parse()?], |_| {
+                if let Err(e) = ui_tree(&mut process).and_then(|tree| write_snapshot(&tree, None, &output_dir)) {
+                    eprintln!("snapshot failed: {}", e);
+                }
+                true
+            })?;
+        }
+        Command::DumpMemory { output } => {
+            let process = attach(&cli.target)?.process;
+            if output.extension().is_some_and(|ext| ext == "gz") {
+                process.save_snapshot(output)?;
+            } else {
+                process.write_dump(output)?;
+            }
+        }
+        Command::Snapshot { output } => {
+            write_snapshot(&ui_tree(&mut attach(&cli.target)?)?, output, Path::new("."))?;
+        }
     }
     profiling::finish_frame!();
     Ok(())
-}
\ No newline at end of file
+}
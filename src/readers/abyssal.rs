@@ -0,0 +1,47 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::{labels, parse_duration, Reader};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Timer and room progress of an abyssal deadspace run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct AbyssalTimer {
+    pub remaining: Option<Duration>,
+    /// Current room, starting at 1.
+    pub room: Option<u32>,
+    pub room_count: Option<u32>,
+}
+
+/// Parses the room label, `Room 2 of 3` or `2/3`.
+fn parse_room(text: &str) -> Option<(u32, Option<u32>)> {
+    let mut numbers = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .filter_map(|part| part.parse().ok());
+    Some((numbers.next()?, numbers.next()))
+}
+
+impl Reader for AbyssalTimer {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let panel = tree
+            .root
+            .find_first(|node| node.is_visible() && node.py_type.contains("Abyss"))?;
+        let texts = labels(panel);
+        let remaining = texts
+            .iter()
+            .filter(|text| text.contains(':'))
+            .find_map(|text| parse_duration(text));
+        let room = texts
+            .iter()
+            .find(|text| {
+                let lower = text.to_lowercase();
+                lower.contains("room") || lower.contains("pocket")
+            })
+            .and_then(|text| parse_room(text));
+        Some(AbyssalTimer {
+            remaining,
+            room: room.map(|(room, _)| room),
+            room_count: room.and_then(|(_, count)| count),
+        })
+    }
+}
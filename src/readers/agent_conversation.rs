@@ -0,0 +1,74 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::{buttons, find_window, labels, window_caption, Reader, UiButton};
+use serde::Serialize;
+
+/// Where the conversation stands, derived from the offered buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissionOfferState {
+    /// A mission is offered and can be accepted or declined.
+    Offered,
+    /// The mission was accepted and is in progress.
+    Accepted,
+    /// The agent has nothing on offer.
+    NoOffer,
+}
+
+/// Readout of an agent conversation window.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentConversation {
+    pub agent_name: Option<String>,
+    pub mission_name: Option<String>,
+    pub rewards: Vec<String>,
+    pub bonus_rewards: Vec<String>,
+    pub buttons: Vec<UiButton>,
+}
+
+impl AgentConversation {
+    pub fn has_button(&self, label: &str) -> bool {
+        self.buttons.iter().any(|button| button.label.eq_ignore_ascii_case(label))
+    }
+
+    /// Comparing the state of consecutive readouts tells whether an offer was accepted or declined.
+    pub fn offer_state(&self) -> MissionOfferState {
+        if self.has_button("Accept") {
+            MissionOfferState::Offered
+        } else if self.has_button("Complete Mission") || self.has_button("Quit Mission") {
+            MissionOfferState::Accepted
+        } else {
+            MissionOfferState::NoOffer
+        }
+    }
+}
+
+impl Reader for AgentConversation {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["AgentDialogueWindow", "agentDialogueWindow"])?;
+        let mission_name = window
+            .find_first(|node| node.is_visible() && matches!(node.name(), Some("missionTitle" | "missionName")))
+            .and_then(|title| labels(title).into_iter().next());
+        let mut rewards = vec![];
+        let mut bonus_rewards = vec![];
+        let mut section = None;
+        for text in labels(window) {
+            let lower = text.to_lowercase();
+            if lower.starts_with("bonus reward") {
+                section = Some(&mut bonus_rewards);
+            } else if lower.starts_with("reward") {
+                section = Some(&mut rewards);
+            } else if !text.chars().any(|c| c.is_ascii_digit()) {
+                // any other heading ends the reward list
+                section = None;
+            } else if let Some(section) = section.as_mut() {
+                section.push(text);
+            }
+        }
+        Some(AgentConversation {
+            agent_name: window_caption(window),
+            mission_name,
+            rewards,
+            bonus_rewards,
+            buttons: buttons(window),
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::table::read_table;
+use crate::readers::{find_window, labels, parse_number, Reader};
+use serde::Serialize;
+
+const FOLDER_TYPES: [&str; 3] = ["BookmarkFolderGroup", "BookmarkSubfolderGroup", "ListGroup"];
+const BOOKMARK_TYPES: [&str; 2] = ["PlaceEntry", "BookmarkEntry"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Bookmark {
+    pub name: String,
+    /// Folder the bookmark is listed under.
+    pub folder: Option<String>,
+    /// What the bookmark points at, like a station, a structure or a coordinate.
+    pub location_type: Option<String>,
+    pub system: Option<String>,
+    pub jumps: Option<i64>,
+    /// Expiry as shown, only bookmarks created with an expiry (like the ones of mobile depots) have one.
+    pub expires: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BookmarkFolder {
+    pub name: String,
+    /// Whether the folder lists its bookmarks, empty folders always read as collapsed.
+    pub expanded: bool,
+}
+
+/// Places tab of the People & Places window. Only bookmarks of expanded folders are listed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Bookmarks {
+    pub folders: Vec<BookmarkFolder>,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    pub fn find(&self, name: &str) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn in_folder<'a>(&'a self, folder: &'a str) -> impl Iterator<Item = &'a Bookmark> {
+        self.bookmarks.iter().filter(move |bookmark| bookmark.folder.as_deref() == Some(folder))
+    }
+
+    pub fn in_system<'a>(&'a self, system: &'a str) -> impl Iterator<Item = &'a Bookmark> {
+        self.bookmarks
+            .iter()
+            .filter(move |bookmark| bookmark.system.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(system)))
+    }
+}
+
+/// Removes the bookmark count the client appends to folder names (`Folder [12]`).
+fn folder_name(label: &str) -> String {
+    let name = match label.rfind([' ', '\u{a0}']) {
+        Some(i) if label[i + 1..].starts_with(['[', '(']) && parse_number(&label[i + 1..]).is_some() => &label[..i],
+        _ => label,
+    };
+    name.trim().to_string()
+}
+
+impl Reader for Bookmarks {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["AddressBookWindow", "addressbook", "PeopleAndPlacesWindow"])?;
+        let row_types: Vec<&str> = FOLDER_TYPES.iter().chain(&BOOKMARK_TYPES).copied().collect();
+        let mut result = Bookmarks::default();
+        // the scroll is flat, bookmarks follow the header of their folder
+        for row in read_table(window, &row_types) {
+            if FOLDER_TYPES.contains(&row.node.py_type.as_str()) {
+                if let Some(label) = labels(row.node).into_iter().next() {
+                    result.folders.push(BookmarkFolder {
+                        name: folder_name(&label),
+                        expanded: false,
+                    });
+                }
+                continue;
+            }
+            let Some(name) = row.cell(&["Label", "Name", "0"]).map(str::to_string) else {
+                continue;
+            };
+            let folder = result.folders.last_mut().map(|folder| {
+                folder.expanded = true;
+                folder.name.clone()
+            });
+            result.bookmarks.push(Bookmark {
+                name,
+                folder,
+                location_type: row.cell(&["Type"]).map(str::to_string),
+                system: row.cell(&["Sol System", "Solar System", "System", "Location"]).map(str::to_string),
+                jumps: row.cell(&["Jumps"]).and_then(parse_number).map(|jumps| jumps as i64),
+                expires: row.cell(&["Expiry", "Expires", "Expiration"]).map(str::to_string),
+            });
+        }
+        Some(result)
+    }
+}
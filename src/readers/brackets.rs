@@ -0,0 +1,56 @@
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use crate::readers::{labels, parse_distance, strip_tags, Reader};
+use serde::Serialize;
+
+/// A marker the client draws over an object in space.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SpaceBracket {
+    pub name: Option<String>,
+    pub type_name: Option<String>,
+    pub rect: UiRect,
+    /// Distance in meters, only shown for brackets with an open label.
+    pub distance: Option<f64>,
+}
+
+impl SpaceBracket {
+    /// Screen position of the marked object.
+    pub fn position(&self) -> (i32, i32) {
+        self.rect.center()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SpaceBrackets {
+    pub brackets: Vec<SpaceBracket>,
+}
+
+fn read_bracket(bracket: &UiNode) -> SpaceBracket {
+    let texts = labels(bracket);
+    let distance = texts
+        .iter()
+        .filter(|text| text.starts_with(|c: char| c.is_ascii_digit()))
+        .find_map(|text| parse_distance(text));
+    let name = bracket
+        .attr_str("displayName")
+        .map(strip_tags)
+        .or_else(|| texts.iter().find(|text| !text.starts_with(|c: char| c.is_ascii_digit())).cloned())
+        .or_else(|| bracket.hint().map(strip_tags));
+    SpaceBracket {
+        name,
+        type_name: bracket.attr_str("displaySubLabel").map(strip_tags),
+        rect: bracket.rect,
+        distance,
+    }
+}
+
+impl Reader for SpaceBrackets {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let layer = tree.root.find_first(|node| node.name() == Some("l_bracket"))?;
+        let brackets = layer
+            .find_all(|node| node.is_visible() && node.py_type.ends_with("Bracket") && node.py_type != "ActiveTargetOnBracket")
+            .into_iter()
+            .map(read_bracket)
+            .collect();
+        Some(SpaceBrackets { brackets })
+    }
+}
@@ -0,0 +1,78 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::{find_window, labelled_value, labels, parse_number, Reader};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Attributes {
+    pub intelligence: Option<i64>,
+    pub memory: Option<i64>,
+    pub perception: Option<i64>,
+    pub willpower: Option<i64>,
+    pub charisma: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneState {
+    Alpha,
+    Omega,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct JumpClone {
+    pub name: Option<String>,
+    pub location: String,
+}
+
+/// Readout of the character sheet window.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CharacterSheet {
+    pub attributes: Attributes,
+    pub unallocated_sp: Option<i64>,
+    pub clone_state: Option<CloneState>,
+    pub jump_clones: Vec<JumpClone>,
+}
+
+/// Each jump clone entry shows its location first and the optional clone name below.
+pub(crate) fn read_jump_clones(node: &UiNode) -> Vec<JumpClone> {
+    node.find_all(|node| node.is_visible() && node.py_type.contains("JumpClone") && !node.py_type.contains("Window"))
+        .into_iter()
+        .filter_map(|entry| {
+            let mut entry_texts = labels(entry).into_iter();
+            Some(JumpClone {
+                location: entry_texts.next()?,
+                name: entry_texts.next(),
+            })
+        })
+        .collect()
+}
+
+impl Reader for CharacterSheet {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["CharacterSheetWindow", "characterSheet"])?;
+        let texts = labels(window);
+        let number = |label| labelled_value(&texts, label).and_then(parse_number).map(|n| n as i64);
+        let clone_state = labelled_value(&texts, "Clone State").and_then(|state| {
+            let state = state.to_lowercase();
+            if state.contains("omega") {
+                Some(CloneState::Omega)
+            } else if state.contains("alpha") {
+                Some(CloneState::Alpha)
+            } else {
+                None
+            }
+        });
+        Some(CharacterSheet {
+            attributes: Attributes {
+                intelligence: number("Intelligence"),
+                memory: number("Memory"),
+                perception: number("Perception"),
+                willpower: number("Willpower"),
+                charisma: number("Charisma"),
+            },
+            unallocated_sp: number("Unallocated Skill Points"),
+            clone_state,
+            jump_clones: read_jump_clones(window),
+        })
+    }
+}
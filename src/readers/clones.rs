@@ -0,0 +1,47 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::character_sheet::{read_jump_clones, JumpClone};
+use crate::readers::{find_window, labelled_value, labels, parse_duration, Reader};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Readout of the clone bay / jump clone window.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CloneBay {
+    /// Implants plugged into the current clone.
+    pub implants: Vec<String>,
+    pub jump_clones: Vec<JumpClone>,
+    /// Time until the next clone jump, zero when a jump is available.
+    pub next_jump: Option<Duration>,
+}
+
+impl CloneBay {
+    pub fn can_jump(&self) -> bool {
+        self.next_jump == Some(Duration::ZERO)
+    }
+}
+
+impl Reader for CloneBay {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["CloneBayWindow", "JumpCloneWindow", "cloneBay"])?;
+        let implants = window
+            .find_all(|node| node.is_visible() && node.py_type.contains("Implant"))
+            .into_iter()
+            .filter_map(|entry| labels(entry).into_iter().next())
+            .collect();
+        let texts = labels(window);
+        let next_jump = labelled_value(&texts, "Next Clone Jump")
+            .or_else(|| labelled_value(&texts, "Clone Jump Available"))
+            .and_then(|text| {
+                if text.to_lowercase().contains("now") {
+                    Some(Duration::ZERO)
+                } else {
+                    parse_duration(text)
+                }
+            });
+        Some(CloneBay {
+            implants,
+            jump_clones: read_jump_clones(window),
+            next_jump,
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::{contains_word, parse_number, strip_tags, Reader};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CombatMessageKind {
+    Hit,
+    Miss,
+    Ewar,
+    Other,
+}
+
+impl CombatMessageKind {
+    fn classify(text: &str) -> Self {
+        let lower = text.to_lowercase();
+        // hits start with the damage and may name a missile
+        if text.starts_with(|c: char| c.is_ascii_digit()) {
+            CombatMessageKind::Hit
+        } else if contains_word(text, "miss") || contains_word(text, "misses") {
+            CombatMessageKind::Miss
+        } else if ["scrambl", "disrupt", "web", "jam", "neutraliz", "nosferatu", "dampen", "painted"]
+            .iter()
+            .any(|word| lower.contains(word))
+        {
+            CombatMessageKind::Ewar
+        } else {
+            CombatMessageKind::Other
+        }
+    }
+}
+
+/// A message of the floating combat feed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CombatMessage {
+    /// Address of the label, stable while the message is on screen.
+    pub addr: u64,
+    pub text: String,
+    pub kind: CombatMessageKind,
+    pub damage: Option<i64>,
+}
+
+/// Messages currently shown by the combat feed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CombatFeed {
+    pub messages: Vec<CombatMessage>,
+}
+
+impl Reader for CombatFeed {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let feed = tree
+            .root
+            .find_first(|node| node.py_type == "CombatMessageStack" || node.name() == Some("combatMessages"))?;
+        let messages = feed
+            .iter()
+            .filter(|node| node.is_visible())
+            .filter_map(|node| {
+                let text = strip_tags(node.text()?).replace('\n', " ");
+                let kind = CombatMessageKind::classify(&text);
+                Some(CombatMessage {
+                    addr: node.addr,
+                    damage: (kind == CombatMessageKind::Hit)
+                        .then(|| parse_number(&text))
+                        .flatten()
+                        .map(|damage| damage as i64),
+                    kind,
+                    text,
+                })
+            })
+            .collect();
+        Some(CombatFeed { messages })
+    }
+}
+
+/// A combat message with the time it first appeared.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CombatEvent {
+    pub time: SystemTime,
+    pub message: CombatMessage,
+}
+
+/// Turns consecutive [`CombatFeed`] readouts into timestamped events, each message is reported once.
+#[derive(Debug, Default)]
+pub struct CombatFeedTracker {
+    seen: HashMap<(u64, String), SystemTime>,
+}
+
+impl CombatFeedTracker {
+    /// Messages fade out after a few seconds, older entries are forgotten.
+    const RETENTION: Duration = Duration::from_secs(60);
+
+    pub fn update(&mut self, feed: &CombatFeed) -> Vec<CombatEvent> {
+        let now = SystemTime::now();
+        self.seen
+            .retain(|_, time| now.duration_since(*time).unwrap_or_default() < Self::RETENTION);
+        feed.messages
+            .iter()
+            .filter(|message| {
+                // labels get reused for new messages, so the text is part of the identity
+                self.seen.insert((message.addr, message.text.clone()), now).is_none()
+            })
+            .map(|message| CombatEvent {
+                time: now,
+                message: message.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_missile_hits_as_hits() {
+        let text = "312 to Guristas Infiltrator - Scourge Light Missile - Hits";
+        assert_eq!(CombatMessageKind::classify(text), CombatMessageKind::Hit);
+    }
+
+    #[test]
+    fn classifies_turret_hits_as_hits() {
+        let text = "523 from Serpentis Chief Guard - Heavy Pulse Laser II - Smashes";
+        assert_eq!(CombatMessageKind::classify(text), CombatMessageKind::Hit);
+    }
+
+    #[test]
+    fn classifies_misses() {
+        let outgoing = "Your Scourge Light Missile misses Guristas Infiltrator completely";
+        assert_eq!(CombatMessageKind::classify(outgoing), CombatMessageKind::Miss);
+        let incoming = "Guristas Infiltrator misses you completely - Scourge Rocket";
+        assert_eq!(CombatMessageKind::classify(incoming), CombatMessageKind::Miss);
+    }
+
+    #[test]
+    fn classifies_ewar() {
+        let text = "Warp scramble attempt from Guristas Infiltrator";
+        assert_eq!(CombatMessageKind::classify(text), CombatMessageKind::Ewar);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::{labels, window_caption, Reader};
+use serde::Serialize;
+
+/// Phrases of the modal dialogs the client shows after losing the server connection.
+const DISCONNECT_PHRASES: [&str; 5] = [
+    "connection lost",
+    "socket was closed",
+    "socket closed",
+    "连接丢失",
+    "连接已断开",
+];
+
+/// Raised when a "Connection lost" or "Socket closed" dialog is open.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ClientDisconnected {
+    pub caption: Option<String>,
+    pub message: String,
+}
+
+impl Reader for ClientDisconnected {
+    fn read(tree: &UiTree) -> Option<Self> {
+        tree.root
+            .find_all(|node| node.is_visible() && matches!(node.py_type.as_str(), "MessageBox" | "ModalWnd"))
+            .into_iter()
+            .find_map(|dialog| {
+                let message = labels(dialog).join("\n");
+                let lower = message.to_lowercase();
+                DISCONNECT_PHRASES
+                    .iter()
+                    .any(|phrase| lower.contains(phrase))
+                    .then(|| ClientDisconnected {
+                        caption: window_caption(dialog),
+                        message,
+                    })
+            })
+    }
+}
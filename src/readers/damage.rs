@@ -0,0 +1,209 @@
+use crate::readers::combat::{CombatEvent, CombatMessageKind};
+use crate::readers::parse_number;
+use serde::Serialize;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitQuality {
+    Wrecks,
+    Smashes,
+    Penetrates,
+    Hits,
+    GlancesOff,
+    Grazes,
+    BarelyScratches,
+    Miss,
+}
+
+/// A combat message normalized into its parts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DamageEvent {
+    pub time: SystemTime,
+    pub direction: Direction,
+    pub amount: Option<i64>,
+    /// Target of outgoing, source of incoming damage.
+    pub counterpart: Option<String>,
+    pub weapon: Option<String>,
+    pub quality: HitQuality,
+}
+
+/// Words the client uses in combat messages, one table per client language.
+///
+/// Messages read `<amount> <to|from> <counterpart> - <weapon> - <quality>`,
+/// misses name the shooter first, which is `own_prefix` for outgoing ones.
+#[derive(Debug, Clone)]
+pub struct CombatLocale {
+    pub to: Vec<String>,
+    pub from: Vec<String>,
+    pub own_prefix: Vec<String>,
+    pub miss: Vec<String>,
+    pub qualities: Vec<(String, HitQuality)>,
+}
+
+fn strings(words: &[&str]) -> Vec<String> {
+    words.iter().map(|word| word.to_string()).collect()
+}
+
+impl CombatLocale {
+    pub fn english() -> Self {
+        CombatLocale {
+            to: strings(&["to"]),
+            from: strings(&["from"]),
+            own_prefix: strings(&["Your"]),
+            miss: strings(&["misses"]),
+            qualities: vec![
+                ("Wrecks".to_string(), HitQuality::Wrecks),
+                ("Smashes".to_string(), HitQuality::Smashes),
+                ("Penetrates".to_string(), HitQuality::Penetrates),
+                ("Glances Off".to_string(), HitQuality::GlancesOff),
+                ("Barely Scratches".to_string(), HitQuality::BarelyScratches),
+                ("Grazes".to_string(), HitQuality::Grazes),
+                ("Hits".to_string(), HitQuality::Hits),
+            ],
+        }
+    }
+
+    pub fn chinese() -> Self {
+        CombatLocale {
+            to: strings(&["对"]),
+            from: strings(&["来自"]),
+            own_prefix: strings(&["你的"]),
+            miss: strings(&["未命中", "没有击中"]),
+            qualities: vec![
+                ("致命一击".to_string(), HitQuality::Wrecks),
+                ("强力命中".to_string(), HitQuality::Smashes),
+                ("穿透".to_string(), HitQuality::Penetrates),
+                ("擦过".to_string(), HitQuality::GlancesOff),
+                ("勉强擦伤".to_string(), HitQuality::BarelyScratches),
+                ("轻微擦伤".to_string(), HitQuality::Grazes),
+                ("命中".to_string(), HitQuality::Hits),
+            ],
+        }
+    }
+
+    /// Tries all built-in tables.
+    pub fn parse_any(event: &CombatEvent) -> Option<DamageEvent> {
+        [CombatLocale::english(), CombatLocale::chinese()]
+            .iter()
+            .find_map(|locale| locale.parse(event))
+    }
+
+    pub fn parse(&self, event: &CombatEvent) -> Option<DamageEvent> {
+        let text = event.message.text.trim();
+        // hits start with the amount, whatever the kind of the message says
+        let is_hit = text.starts_with(|c: char| c.is_ascii_digit());
+        let is_miss = event.message.kind == CombatMessageKind::Miss
+            || self.miss.iter().any(|word| text.contains(word.as_str()));
+        if is_miss && !is_hit {
+            return self.parse_miss(event.time, text);
+        }
+        let amount = parse_number(text).map(|amount| amount as i64);
+        let rest = text.trim_start_matches(|c: char| c.is_ascii_digit() || c == ',' || c.is_whitespace());
+        let (direction, rest) = self
+            .to
+            .iter()
+            .find_map(|word| rest.strip_prefix(word.as_str()).map(|rest| (Direction::Outgoing, rest)))
+            .or_else(|| {
+                self.from
+                    .iter()
+                    .find_map(|word| rest.strip_prefix(word.as_str()).map(|rest| (Direction::Incoming, rest)))
+            })?;
+        let parts: Vec<_> = rest.split(" - ").map(str::trim).collect();
+        let quality = parts.last().and_then(|part| self.quality(part))?;
+        Some(DamageEvent {
+            time: event.time,
+            direction,
+            amount,
+            counterpart: parts.first().filter(|_| parts.len() > 1).map(|part| part.to_string()),
+            weapon: parts.get(1).filter(|_| parts.len() > 2).map(|part| part.to_string()),
+            quality,
+        })
+    }
+
+    fn quality(&self, text: &str) -> Option<HitQuality> {
+        self.qualities
+            .iter()
+            .find(|(word, _)| text.eq_ignore_ascii_case(word) || text.contains(word.as_str()))
+            .map(|(_, quality)| *quality)
+    }
+
+    fn parse_miss(&self, time: SystemTime, text: &str) -> Option<DamageEvent> {
+        let (shooter, rest) = self
+            .miss
+            .iter()
+            .find_map(|word| text.split_once(word.as_str()))?;
+        let outgoing = self.own_prefix.iter().find_map(|prefix| shooter.trim().strip_prefix(prefix.as_str()));
+        let (direction, counterpart, weapon) = match outgoing {
+            // "Your <weapon> misses <target> completely"
+            Some(weapon) => (
+                Direction::Outgoing,
+                rest.split(" - ").next().map(str::trim),
+                Some(weapon.trim()),
+            ),
+            // "<shooter> misses you completely - <weapon>"
+            None => (
+                Direction::Incoming,
+                Some(shooter.trim()),
+                rest.split(" - ").nth(1).map(str::trim),
+            ),
+        };
+        Some(DamageEvent {
+            time,
+            direction,
+            amount: None,
+            counterpart: counterpart.filter(|s| !s.is_empty()).map(str::to_string),
+            weapon: weapon.filter(|s| !s.is_empty()).map(str::to_string),
+            quality: HitQuality::Miss,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readers::combat::CombatMessage;
+
+    fn event(text: &str, kind: CombatMessageKind) -> CombatEvent {
+        CombatEvent {
+            time: SystemTime::UNIX_EPOCH,
+            message: CombatMessage {
+                addr: 0,
+                text: text.to_string(),
+                kind,
+                damage: None,
+            },
+        }
+    }
+
+    #[test]
+    fn parses_missile_hits_as_damage() {
+        let text = "312 to Guristas Infiltrator - Scourge Light Missile - Hits";
+        // messages classified as misses by the name of the missile
+        for kind in [CombatMessageKind::Hit, CombatMessageKind::Miss] {
+            let damage = CombatLocale::english().parse(&event(text, kind)).unwrap();
+            assert_eq!(damage.direction, Direction::Outgoing);
+            assert_eq!(damage.amount, Some(312));
+            assert_eq!(damage.counterpart.as_deref(), Some("Guristas Infiltrator"));
+            assert_eq!(damage.weapon.as_deref(), Some("Scourge Light Missile"));
+            assert_eq!(damage.quality, HitQuality::Hits);
+        }
+    }
+
+    #[test]
+    fn parses_misses() {
+        let text = "Your Scourge Light Missile misses Guristas Infiltrator completely";
+        let miss = CombatLocale::english().parse(&event(text, CombatMessageKind::Miss)).unwrap();
+        assert_eq!(miss.direction, Direction::Outgoing);
+        assert_eq!(miss.amount, None);
+        assert_eq!(miss.weapon.as_deref(), Some("Scourge Light Missile"));
+        assert_eq!(miss.quality, HitQuality::Miss);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::{labels, parse_duration, strip_tags, Reader};
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectKind {
+    Booster,
+    CommandBurst,
+    Environment,
+    /// Electronic warfare applied to the own ship.
+    Ewar,
+    Other,
+}
+
+impl EffectKind {
+    fn classify(text: &str) -> Self {
+        let lower = text.to_lowercase();
+        let any = |words: &[&str]| words.iter().any(|word| lower.contains(word));
+        if any(&["booster"]) {
+            EffectKind::Booster
+        } else if any(&["burst", "command"]) {
+            EffectKind::CommandBurst
+        } else if any(&["wormhole", "weather", "environment", "abyssal", "storm"]) {
+            EffectKind::Environment
+        } else if any(&["scrambl", "disrupt", "web", "jam", "neutraliz", "dampen", "painted", "tracking"]) {
+            EffectKind::Ewar
+        } else {
+            EffectKind::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActiveEffect {
+    pub kind: EffectKind,
+    pub name: String,
+    pub remaining: Option<Duration>,
+    /// Remaining lines of the tooltip, e.g. the modifiers of an environment.
+    pub details: Vec<String>,
+}
+
+/// Icons of the effect bar above the ship HUD.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ActiveEffects {
+    pub effects: Vec<ActiveEffect>,
+}
+
+impl ActiveEffects {
+    pub fn of_kind(&self, kind: EffectKind) -> impl Iterator<Item = &ActiveEffect> {
+        self.effects.iter().filter(move |effect| effect.kind == kind)
+    }
+}
+
+/// The hint names the effect on its first line, timed effects show the remaining time below.
+fn read_effect(icon: &UiNode) -> Option<ActiveEffect> {
+    let hint = icon.hint().map(strip_tags)?;
+    let mut lines = hint.lines().map(str::trim).filter(|line| !line.is_empty());
+    let name = lines.next()?.to_string();
+    let details: Vec<String> = lines.map(str::to_string).collect();
+    let remaining = details
+        .iter()
+        .filter(|line| line.contains(':') || line.to_lowercase().contains("remaining"))
+        .find_map(|line| parse_duration(line))
+        .or_else(|| labels(icon).iter().find_map(|text| parse_duration(text)));
+    Some(ActiveEffect {
+        kind: EffectKind::classify(&hint),
+        name,
+        remaining,
+        details,
+    })
+}
+
+impl Reader for ActiveEffects {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let ship_ui = tree.root.find_by_type("ShipUI")?;
+        let effects = ship_ui
+            .find_all(|node| {
+                node.is_visible()
+                    && (node.py_type == "BuffBarContainer" || matches!(node.name(), Some("buffBar" | "ewarContainer")))
+            })
+            .into_iter()
+            .flat_map(|bar| bar.find_all(|node| node.is_visible() && node.py_type.ends_with("Icon")))
+            .filter_map(read_effect)
+            .collect();
+        Some(ActiveEffects { effects })
+    }
+}
@@ -0,0 +1,67 @@
+use crate::eve_process::eve_process::EVEProcess;
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::{labels, Reader};
+use serde::Serialize;
+
+/// Where the ship currently is, as far as the UI layers tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlightState {
+    /// Docked with the station or structure interior hidden.
+    Docked,
+    /// Docked and looking at the ship hangar.
+    InHangar,
+    InSpace,
+    Warping,
+    Jumping,
+}
+
+impl FlightState {
+    pub fn is_docked(&self) -> bool {
+        matches!(self, FlightState::Docked | FlightState::InHangar)
+    }
+
+    /// Overview, targets and the ship HUD only exist in space.
+    pub fn in_space(&self) -> bool {
+        !self.is_docked()
+    }
+}
+
+fn visible_layer<'a>(tree: &'a UiTree, name: &str) -> Option<&'a UiNode> {
+    tree.root
+        .find_first(|node| node.name() == Some(name))
+        .filter(|layer| layer.is_visible() && !layer.children.is_empty())
+}
+
+impl Reader for FlightState {
+    fn read(tree: &UiTree) -> Option<Self> {
+        if visible_layer(tree, "l_hangar").is_some() {
+            return Some(FlightState::InHangar);
+        }
+        if visible_layer(tree, "l_station").is_some()
+            || tree.root.find_first(|node| node.is_visible() && node.py_type == "LobbyWnd").is_some()
+        {
+            return Some(FlightState::Docked);
+        }
+        let ship_ui = visible_layer(tree, "l_inflight").and(tree.root.find_by_type("ShipUI"))?;
+        // the indication above the capacitor names the current maneuver
+        let maneuver = ship_ui
+            .find_by_name("indicationContainer")
+            .filter(|indication| indication.is_visible())
+            .map(|indication| labels(indication).join(" ").to_lowercase())
+            .unwrap_or_default();
+        Some(if maneuver.contains("jump") {
+            FlightState::Jumping
+        } else if maneuver.contains("warp") {
+            FlightState::Warping
+        } else {
+            FlightState::InSpace
+        })
+    }
+}
+
+impl EVEProcess {
+    pub fn flight_state(&mut self) -> Option<FlightState> {
+        self.ui_tree()?.read()
+    }
+}
@@ -0,0 +1,59 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::effects::{ActiveEffects, EffectKind};
+use crate::readers::{parse_number, parse_percent, Reader};
+use serde::Serialize;
+
+/// A modifier line of a hazard tooltip, e.g. `Shield HP +58%`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HazardModifier {
+    pub attribute: String,
+    /// Signed change in percent.
+    pub percent: f64,
+}
+
+/// A wormhole effect or abyssal weather affecting the ship.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvironmentalHazard {
+    pub name: String,
+    /// Strength class from the name, e.g. `Pulsar (Class 5)`.
+    pub class: Option<u32>,
+    pub modifiers: Vec<HazardModifier>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EnvironmentalHazards {
+    pub hazards: Vec<EnvironmentalHazard>,
+}
+
+fn parse_modifier(line: &str) -> Option<HazardModifier> {
+    let percent = parse_percent(line)?;
+    let sign_at = line.rfind(['+', '-', '−'])?;
+    let attribute = line[..sign_at].trim().trim_end_matches(':').trim();
+    if attribute.is_empty() {
+        return None;
+    }
+    let negative = !line[sign_at..].starts_with('+');
+    Some(HazardModifier {
+        attribute: attribute.to_string(),
+        percent: if negative { -percent } else { percent },
+    })
+}
+
+impl Reader for EnvironmentalHazards {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let effects = tree.read::<ActiveEffects>()?;
+        let hazards = effects
+            .of_kind(EffectKind::Environment)
+            .map(|effect| EnvironmentalHazard {
+                class: effect
+                    .name
+                    .split_once('(')
+                    .and_then(|(_, class)| parse_number(class))
+                    .map(|class| class as u32),
+                name: effect.name.split('(').next().unwrap_or_default().trim().to_string(),
+                modifiers: effect.details.iter().filter_map(|line| parse_modifier(line)).collect(),
+            })
+            .collect();
+        Some(EnvironmentalHazards { hazards })
+    }
+}
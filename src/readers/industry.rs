@@ -0,0 +1,44 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::table::read_table;
+use crate::readers::{find_window, parse_duration, parse_number, Reader};
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndustryJob {
+    pub blueprint: String,
+    pub activity: Option<String>,
+    pub runs: Option<i64>,
+    pub facility: Option<String>,
+    /// End date as displayed by the client.
+    pub end_time: Option<String>,
+    pub remaining: Option<Duration>,
+    pub status: Option<String>,
+}
+
+/// Job list of the industry window.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndustryJobs {
+    pub jobs: Vec<IndustryJob>,
+}
+
+impl Reader for IndustryJobs {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["Industry", "IndustryWnd", "industryWnd"])?;
+        let jobs = read_table(window, &["JobEntry", "IndustryJobEntry"])
+            .into_iter()
+            .filter_map(|row| {
+                Some(IndustryJob {
+                    blueprint: row.cell(&["Blueprint", "Job", "Product"])?.to_string(),
+                    activity: row.cell(&["Activity"]).map(str::to_string),
+                    runs: row.cell(&["Runs"]).and_then(parse_number).map(|runs| runs as i64),
+                    facility: row.cell(&["Facility", "Location"]).map(str::to_string),
+                    end_time: row.cell(&["End Date", "End Time"]).map(str::to_string),
+                    remaining: row.cell(&["Time Left", "Remaining"]).and_then(parse_duration),
+                    status: row.cell(&["Status", "State"]).map(str::to_string),
+                })
+            })
+            .collect();
+        Some(IndustryJobs { jobs })
+    }
+}
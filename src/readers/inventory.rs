@@ -0,0 +1,115 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::flight_state::FlightState;
+use crate::readers::table::read_table;
+use crate::readers::{find_window, labels, parse_number, type_id, window_caption, Reader};
+use serde::Serialize;
+
+/// Item entry types of the inventory icon and list views.
+const ITEM_ENTRY_TYPES: [&str; 3] = ["InvItem", "ItemEntry", "InventoryItem"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InventoryItem {
+    pub name: String,
+    pub type_id: Option<u32>,
+    pub type_name: Option<String>,
+    pub quantity: Option<i64>,
+    /// Whether the entry is the ship currently boarded.
+    pub is_active_ship: bool,
+}
+
+/// Items listed below `container`, in either view mode of the inventory.
+pub fn read_items(container: &UiNode) -> Vec<InventoryItem> {
+    read_table(container, &ITEM_ENTRY_TYPES)
+        .into_iter()
+        .filter_map(|row| {
+            let texts = labels(row.node);
+            let name = row.cell(&["Name", "0"]).map(str::to_string).or_else(|| texts.first().cloned())?;
+            // the icon view only shows the name and a quantity badge
+            let quantity = row
+                .cell(&["Quantity"])
+                .or_else(|| texts.iter().skip(1).map(String::as_str).find(|text| text.starts_with(|c: char| c.is_ascii_digit())))
+                .and_then(parse_number)
+                .map(|quantity| quantity as i64);
+            let is_active_ship = row.node.attr_bool("isActiveShip").unwrap_or(false)
+                || texts.iter().any(|text| text.to_lowercase().contains("active ship"));
+            Some(InventoryItem {
+                name,
+                type_id: type_id(row.node),
+                type_name: row.cell(&["Type", "Group"]).map(str::to_string),
+                quantity,
+                is_active_ship,
+            })
+        })
+        .collect()
+}
+
+/// Ships in the station or structure ship hangar.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ShipHangar {
+    pub ships: Vec<InventoryItem>,
+}
+
+impl ShipHangar {
+    pub fn active_ship(&self) -> Option<&InventoryItem> {
+        self.ships.iter().find(|ship| ship.is_active_ship)
+    }
+}
+
+impl Reader for ShipHangar {
+    fn read(tree: &UiTree) -> Option<Self> {
+        if !tree.read::<FlightState>()?.is_docked() {
+            return None;
+        }
+        let hangar = find_window(tree, &["StationShips", "StructureShips", "ShipHangar"])?;
+        Some(ShipHangar {
+            ships: read_items(hangar),
+        })
+    }
+}
+
+/// A division of the corporation hangar, only the selected one lists its items.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CorpHangarDivision {
+    pub name: String,
+    pub selected: bool,
+    pub items: Vec<InventoryItem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CorpHangar {
+    pub divisions: Vec<CorpHangarDivision>,
+}
+
+impl CorpHangar {
+    pub fn selected(&self) -> Option<&CorpHangarDivision> {
+        self.divisions.iter().find(|division| division.selected)
+    }
+}
+
+impl Reader for CorpHangar {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let hangar = find_window(tree, &["StationCorpHangar", "StructureCorpHangar", "CorpHangar"])?;
+        let items = read_items(hangar);
+        let tabs = hangar.find_all(|node| node.is_visible() && node.py_type == "Tab");
+        let divisions = if tabs.is_empty() {
+            vec![CorpHangarDivision {
+                name: window_caption(hangar).unwrap_or_default(),
+                selected: true,
+                items,
+            }]
+        } else {
+            let mut items = Some(items);
+            tabs.into_iter()
+                .filter_map(|tab| {
+                    let selected = tab.attr_bool("_selected").unwrap_or(false);
+                    Some(CorpHangarDivision {
+                        name: labels(tab).into_iter().next()?,
+                        selected,
+                        items: if selected { items.take().unwrap_or_default() } else { vec![] },
+                    })
+                })
+                .collect()
+        };
+        Some(CorpHangar { divisions })
+    }
+}
@@ -0,0 +1,94 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::table::read_table;
+use crate::readers::{find_window, labels, Reader};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JournalMission {
+    pub name: String,
+    pub state: Option<String>,
+    pub agent: Option<String>,
+    pub expires: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MissionObjective {
+    pub text: String,
+    pub completed: bool,
+}
+
+/// Mission list of the journal together with the objectives and briefing of the active mission.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MissionJournal {
+    pub missions: Vec<JournalMission>,
+    pub objectives: Vec<MissionObjective>,
+    pub briefing: Option<String>,
+}
+
+impl Reader for MissionJournal {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let journal = find_window(tree, &["JournalWindow", "journal", "AgencyWndNew"]);
+        let tracker = tree.root.find_first(|node| {
+            node.is_visible() && matches!(node.py_type.as_str(), "InfoPanelMissions" | "InfoPanelAgentMissions")
+        });
+        if journal.is_none() && tracker.is_none() {
+            return None;
+        }
+        let missions = journal
+            .map(|journal| {
+                read_table(journal, &["MissionEntry", "AgentMissionEntry"])
+                    .into_iter()
+                    .filter_map(|row| {
+                        Some(JournalMission {
+                            name: row.cell(&["Mission Name", "Name", "Mission"])?.to_string(),
+                            state: row.cell(&["State", "Status"]).map(str::to_string),
+                            agent: row.cell(&["Agent"]).map(str::to_string),
+                            expires: row.cell(&["Expires", "Expiration"]).map(str::to_string),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let objectives = tracker
+            .or(journal)
+            .map(|node| {
+                node.find_all(|node| node.is_visible() && node.py_type.contains("Objective"))
+                    .into_iter()
+                    // objective groups contain the entries again, only keep the innermost ones
+                    .filter(|node| !node.children.iter().any(|child| child.py_type.contains("Objective")))
+                    .filter_map(read_objective)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let briefing = journal
+            .and_then(|journal| {
+                journal.find_first(|node| {
+                    node.is_visible() && node.name().is_some_and(|name| name.to_lowercase().contains("briefing"))
+                })
+            })
+            .map(|briefing| labels(briefing).join("\n"))
+            .filter(|briefing| !briefing.is_empty());
+        Some(MissionJournal {
+            missions,
+            objectives,
+            briefing,
+        })
+    }
+}
+
+fn read_objective(entry: &UiNode) -> Option<MissionObjective> {
+    let text = labels(entry).join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    let completed = entry.attr_bool("completed").unwrap_or(false)
+        || entry
+            .find_first(|node| {
+                node.is_visible()
+                    && node
+                        .attr_str("texturePath")
+                        .is_some_and(|path| path.to_lowercase().contains("checkmark"))
+            })
+            .is_some();
+    Some(MissionObjective { text, completed })
+}
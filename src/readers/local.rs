@@ -0,0 +1,325 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::{labels, parse_number, window_caption, Reader};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Standing of a pilot as shown by the flag icon next to the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Standing {
+    Excellent,
+    Good,
+    Neutral,
+    Bad,
+    Terrible,
+    /// Same fleet, corporation or alliance.
+    Friendly,
+    /// Criminal, suspect or outlaw.
+    Criminal,
+    /// At war with your corporation or alliance.
+    AtWar,
+}
+
+impl Standing {
+    /// Classifies the hint of a flag icon, e.g. `Pilot has Terrible Standing`.
+    pub fn from_hint(hint: &str) -> Self {
+        let lower = hint.to_lowercase();
+        // war hints name your corporation or alliance too
+        if lower.contains("at war") || lower.contains("war target") {
+            Standing::AtWar
+        } else if lower.contains("terrible") {
+            Standing::Terrible
+        } else if lower.contains("bad") {
+            Standing::Bad
+        } else if lower.contains("excellent") {
+            Standing::Excellent
+        } else if lower.contains("good") {
+            Standing::Good
+        } else if ["fleet", "corporation", "alliance"].iter().any(|word| lower.contains(word)) {
+            Standing::Friendly
+        } else if ["criminal", "suspect", "outlaw"].iter().any(|word| lower.contains(word)) {
+            Standing::Criminal
+        } else {
+            Standing::Neutral
+        }
+    }
+
+    pub fn is_hostile(&self) -> bool {
+        matches!(self, Standing::Bad | Standing::Terrible | Standing::Criminal | Standing::AtWar)
+    }
+
+    pub fn threat(&self) -> Threat {
+        match self {
+            Standing::Excellent | Standing::Good | Standing::Friendly => Threat::Friendly,
+            Standing::Neutral => Threat::Neutral,
+            Standing::Bad | Standing::Terrible | Standing::Criminal | Standing::AtWar => Threat::Hostile,
+        }
+    }
+}
+
+/// Threat classification of a pilot, ordered from harmless to hostile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Threat {
+    Friendly,
+    /// Also pilots without standings.
+    #[default]
+    Neutral,
+    Hostile,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalMember {
+    pub name: String,
+    pub standing: Standing,
+}
+
+/// Member list of the Local chat channel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LocalChat {
+    /// Count from the channel caption, larger than `members` when the list is scrolled.
+    pub member_count: Option<usize>,
+    pub members: Vec<LocalMember>,
+}
+
+/// Members that joined or left between two readouts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LocalDiff {
+    pub joined: Vec<LocalMember>,
+    pub left: Vec<LocalMember>,
+}
+
+impl LocalChat {
+    pub fn count(&self) -> usize {
+        self.member_count.unwrap_or(self.members.len())
+    }
+
+    pub fn diff(&self, previous: &LocalChat) -> LocalDiff {
+        let names = |chat: &LocalChat| chat.members.iter().map(|m| m.name.clone()).collect::<HashSet<_>>();
+        let (now, before) = (names(self), names(previous));
+        LocalDiff {
+            joined: self.members.iter().filter(|m| !before.contains(&m.name)).cloned().collect(),
+            left: previous.members.iter().filter(|m| !now.contains(&m.name)).cloned().collect(),
+        }
+    }
+}
+
+/// Standings the user keeps for pilots, like a copy of the contact list, read from a JSON object
+/// like `{"Some Pilot": "terrible"}`. Names are matched case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct StandingsList {
+    standings: HashMap<String, Standing>,
+}
+
+impl StandingsList {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let standings: HashMap<String, Standing> = serde_json::from_slice(&fs::read(path)?)?;
+        Ok(standings.into_iter().collect())
+    }
+
+    pub fn insert(&mut self, name: &str, standing: Standing) {
+        self.standings.insert(name.to_lowercase(), standing);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Standing> {
+        self.standings.get(&name.to_lowercase()).copied()
+    }
+}
+
+impl FromIterator<(String, Standing)> for StandingsList {
+    fn from_iter<I: IntoIterator<Item = (String, Standing)>>(iter: I) -> Self {
+        let mut list = StandingsList::default();
+        for (name, standing) in iter {
+            list.insert(&name, standing);
+        }
+        list
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssessedMember {
+    pub name: String,
+    pub standing: Standing,
+    /// Whether the standing comes from the [`StandingsList`] rather than the flag icon.
+    pub listed: bool,
+    pub threat: Threat,
+}
+
+/// Local members classified by threat, from their flag icons and optionally a [`StandingsList`],
+/// which takes precedence over the icons.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LocalIntel {
+    pub members: Vec<AssessedMember>,
+    pub member_count: usize,
+    pub friendly: usize,
+    pub neutral: usize,
+    pub hostile: usize,
+    /// Members counted in the caption but scrolled out of the list, so not assessed.
+    pub unseen: usize,
+}
+
+impl LocalIntel {
+    pub fn assess(local: &LocalChat, standings: Option<&StandingsList>) -> Self {
+        let members: Vec<AssessedMember> = local
+            .members
+            .iter()
+            .map(|member| {
+                let listed = standings.and_then(|list| list.get(&member.name));
+                let standing = listed.unwrap_or(member.standing);
+                AssessedMember {
+                    name: member.name.clone(),
+                    standing,
+                    listed: listed.is_some(),
+                    threat: standing.threat(),
+                }
+            })
+            .collect();
+        let count = |threat: Threat| members.iter().filter(|member| member.threat == threat).count();
+        LocalIntel {
+            member_count: local.count(),
+            friendly: count(Threat::Friendly),
+            neutral: count(Threat::Neutral),
+            hostile: count(Threat::Hostile),
+            unseen: local.count().saturating_sub(members.len()),
+            members,
+        }
+    }
+
+    /// The highest threat present, neutral for an empty local.
+    pub fn threat(&self) -> Threat {
+        self.members.iter().map(|member| member.threat).max().unwrap_or_default()
+    }
+
+    pub fn hostiles(&self) -> impl Iterator<Item = &AssessedMember> {
+        self.members.iter().filter(|member| member.threat == Threat::Hostile)
+    }
+}
+
+impl Reader for LocalIntel {
+    fn read(tree: &UiTree) -> Option<Self> {
+        tree.read::<LocalChat>().map(|local| LocalIntel::assess(&local, None))
+    }
+}
+
+fn read_member(entry: &UiNode) -> Option<LocalMember> {
+    let name = labels(entry).into_iter().next()?;
+    let standing = entry
+        .find_first(|node| node.is_visible() && node.py_type == "FlagIconWithState")
+        .and_then(UiNode::hint)
+        .map(Standing::from_hint)
+        .unwrap_or(Standing::Neutral);
+    Some(LocalMember { name, standing })
+}
+
+impl Reader for LocalChat {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = tree.root.find_first(|node| {
+            node.is_visible()
+                && node.py_type.contains("ChatWindow")
+                && node.name().is_some_and(|name| name.to_lowercase().starts_with("chatchannel_local"))
+        })?;
+        // caption reads "Local [42]"
+        let member_count = window_caption(window)
+            .and_then(|caption| caption.split_once('[').and_then(|(_, count)| parse_number(count)))
+            .map(|count| count as usize);
+        let members = window
+            .find_all(|node| node.is_visible() && node.py_type.ends_with("UserEntry"))
+            .into_iter()
+            .filter_map(read_member)
+            .collect();
+        Some(LocalChat { member_count, members })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalSpikeConfig {
+    /// Fires once the member count rises above this value.
+    pub max_members: Option<usize>,
+    /// Fires when more pilots than this join between two readouts.
+    pub max_joined: Option<usize>,
+    /// Standings that fire as soon as a pilot with them appears.
+    pub flagged: Vec<Standing>,
+}
+
+impl Default for LocalSpikeConfig {
+    fn default() -> Self {
+        LocalSpikeConfig {
+            max_members: None,
+            max_joined: Some(2),
+            flagged: vec![Standing::Bad, Standing::Terrible, Standing::Criminal, Standing::AtWar],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalSpikeEvent {
+    CountAbove { count: usize, threshold: usize },
+    Spike { joined: Vec<LocalMember>, count: usize },
+    FlaggedMember(LocalMember),
+}
+
+/// Compares consecutive [`LocalChat`] readouts and reports spikes.
+#[derive(Debug, Default)]
+pub struct LocalSpikeDetector {
+    pub config: LocalSpikeConfig,
+    previous: Option<LocalChat>,
+}
+
+impl LocalSpikeDetector {
+    pub fn new(config: LocalSpikeConfig) -> Self {
+        LocalSpikeDetector { config, previous: None }
+    }
+
+    pub fn update(&mut self, local: &LocalChat) -> Vec<LocalSpikeEvent> {
+        let mut events = vec![];
+        let previous = self.previous.take().unwrap_or_default();
+        let diff = local.diff(&previous);
+        let count = local.count();
+        if let Some(threshold) = self.config.max_members {
+            // only fire when crossing the threshold, not on every readout above it
+            if count > threshold && previous.count() <= threshold {
+                events.push(LocalSpikeEvent::CountAbove { count, threshold });
+            }
+        }
+        if let Some(max_joined) = self.config.max_joined {
+            if !previous.members.is_empty() && diff.joined.len() > max_joined {
+                events.push(LocalSpikeEvent::Spike {
+                    joined: diff.joined.clone(),
+                    count,
+                });
+            }
+        }
+        events.extend(
+            diff.joined
+                .into_iter()
+                .filter(|member| self.config.flagged.contains(&member.standing))
+                .map(LocalSpikeEvent::FlaggedMember),
+        );
+        self.previous = Some(local.clone());
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn war_hints_are_hostile() {
+        let standing = Standing::from_hint("Pilot is at war with your corporation");
+        assert_eq!(standing, Standing::AtWar);
+        assert_eq!(standing.threat(), Threat::Hostile);
+        assert_eq!(Standing::from_hint("Pilot is at war with your alliance"), Standing::AtWar);
+    }
+
+    #[test]
+    fn corporation_and_alliance_members_are_friendly() {
+        assert_eq!(Standing::from_hint("Pilot is in your corporation"), Standing::Friendly);
+        assert_eq!(Standing::from_hint("Pilot is in your alliance"), Standing::Friendly);
+        assert_eq!(Standing::from_hint("Pilot has Terrible Standing"), Standing::Terrible);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::flight_state::FlightState;
+use crate::readers::{labels, parse_number, strip_tags, Reader};
+use serde::Serialize;
+
+/// Readout of the location info panel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Location {
+    pub system: Option<String>,
+    pub security_status: Option<f64>,
+    pub constellation: Option<String>,
+    pub region: Option<String>,
+    /// Station or structure, only while docked.
+    pub station: Option<String>,
+}
+
+impl Reader for Location {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let panel = tree
+            .root
+            .find_first(|node| node.is_visible() && node.py_type == "InfoPanelLocationInfo")?;
+        let header = panel
+            .find_by_name("headerLabel")
+            .and_then(|label| label.text())
+            .map(strip_tags)?;
+        // "Jita 0.9 < Kimotoro < The Forge"
+        let mut parts = header.split('<').map(str::trim);
+        let (system, security_status) = match parts.next() {
+            Some(first) => match first.rsplit_once(' ') {
+                Some((name, security)) if parse_number(security).is_some() => {
+                    (Some(name.trim().to_string()), parse_number(security))
+                }
+                _ => (Some(first.to_string()), None),
+            },
+            None => (None, None),
+        };
+        let constellation = parts.next().filter(|part| !part.is_empty()).map(str::to_string);
+        let region = parts.next().filter(|part| !part.is_empty()).map(str::to_string);
+        let station = tree
+            .read::<FlightState>()
+            .filter(FlightState::is_docked)
+            .and_then(|_| panel.find_by_name("nearestLocationInfo"))
+            .and_then(|nearest| labels(nearest).into_iter().next());
+        Some(Location {
+            system,
+            security_status,
+            constellation,
+            region,
+            station,
+        })
+    }
+}
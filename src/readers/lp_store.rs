@@ -0,0 +1,53 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::table::read_table;
+use crate::readers::{find_window, labelled_value, labels, parse_number, Reader};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LpOffer {
+    pub item: String,
+    pub quantity: Option<i64>,
+    pub lp_cost: Option<i64>,
+    pub isk_cost: Option<f64>,
+    /// Required items as displayed, e.g. `10 x Tritanium`.
+    pub required_items: Vec<String>,
+}
+
+/// Offers of the loyalty point store window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LpStore {
+    /// Loyalty points available with the store's corporation.
+    pub balance: Option<i64>,
+    pub offers: Vec<LpOffer>,
+}
+
+impl Reader for LpStore {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["LPStoreWindow", "LPStoreWnd", "lpstore"])?;
+        let integer = |text: &str| parse_number(text).map(|n| n as i64);
+        let offers = read_table(window, &["LPStoreEntry", "LPOfferEntry"])
+            .into_iter()
+            .filter_map(|row| {
+                Some(LpOffer {
+                    item: row.cell(&["Item", "Reward", "Name"])?.to_string(),
+                    quantity: row.cell(&["Qty", "Quantity"]).and_then(integer),
+                    lp_cost: row.cell(&["LP Cost", "LP"]).and_then(integer),
+                    isk_cost: row.cell(&["ISK Cost", "ISK"]).and_then(parse_number),
+                    required_items: row
+                        .cell(&["Required Items", "Requirements"])
+                        .map(|items| {
+                            items
+                                .split(['\n', ';'])
+                                .map(str::trim)
+                                .filter(|item| !item.is_empty())
+                                .map(str::to_string)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+            })
+            .collect();
+        let balance = labelled_value(&labels(window), "Loyalty Points").and_then(integer);
+        Some(LpStore { balance, offers })
+    }
+}
@@ -0,0 +1,80 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::table::read_table;
+use crate::readers::{find_window, parse_distance, parse_number, Reader};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SurveyResult {
+    pub ore: String,
+    pub quantity: Option<i64>,
+    /// Distance in meters.
+    pub distance: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LedgerEntry {
+    pub ore: String,
+    pub quantity: Option<i64>,
+    /// Volume in m³.
+    pub volume: Option<f64>,
+}
+
+/// Survey scanner results and mining ledger entries, whichever of both windows is open.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MiningReadout {
+    pub survey: Vec<SurveyResult>,
+    pub ledger: Vec<LedgerEntry>,
+}
+
+impl MiningReadout {
+    /// Total quantity left in the scanned asteroids of `ore`.
+    pub fn remaining(&self, ore: &str) -> i64 {
+        self.survey
+            .iter()
+            .filter(|result| result.ore == ore)
+            .filter_map(|result| result.quantity)
+            .sum()
+    }
+}
+
+impl Reader for MiningReadout {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let scanner = find_window(tree, &["SurveyScanView", "surveyScanner"]);
+        let ledger = find_window(tree, &["MiningLedgerWindow", "miningLedger"]);
+        if scanner.is_none() && ledger.is_none() {
+            return None;
+        }
+        let quantity = |text: &str| parse_number(text).map(|q| q as i64);
+        let survey = scanner
+            .map(|scanner| {
+                read_table(scanner, &["SurveyScanEntry"])
+                    .into_iter()
+                    .filter_map(|row| {
+                        Some(SurveyResult {
+                            ore: row.cell(&["Ore", "Asteroid", "Type"])?.to_string(),
+                            quantity: row.cell(&["Quantity"]).and_then(quantity),
+                            distance: row.cell(&["Distance"]).and_then(parse_distance),
+                        })
+                    })
+                    // the collapsible group headers have neither quantity nor distance
+                    .filter(|result| result.quantity.is_some() || result.distance.is_some())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let ledger = ledger
+            .map(|ledger| {
+                read_table(ledger, &["MiningLedgerEntry", "LedgerEntry"])
+                    .into_iter()
+                    .filter_map(|row| {
+                        Some(LedgerEntry {
+                            ore: row.cell(&["Ore Type", "Ore", "Type"])?.to_string(),
+                            quantity: row.cell(&["Quantity"]).and_then(quantity),
+                            volume: row.cell(&["Volume"]).and_then(parse_number),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(MiningReadout { survey, ledger })
+    }
+}
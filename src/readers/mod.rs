@@ -0,0 +1,339 @@
+pub mod abyssal;
+pub mod agent_conversation;
+pub mod bookmarks;
+pub mod brackets;
+pub mod character_sheet;
+pub mod clones;
+pub mod combat;
+pub mod connection;
+pub mod damage;
+pub mod effects;
+pub mod flight_state;
+pub mod hazards;
+pub mod industry;
+pub mod inventory;
+pub mod journal;
+pub mod local;
+pub mod location;
+pub mod lp_store;
+pub mod mining;
+pub mod notifications;
+pub mod performance;
+pub mod planetary;
+pub mod reprocessing;
+pub mod route;
+pub mod ship_ui;
+pub mod table;
+pub mod tactical;
+pub mod targets;
+pub mod timers;
+
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use serde::Serialize;
+use std::io;
+use std::time::Duration;
+
+/// A typed readout extracted from a [`UiTree`].
+pub trait Reader: Sized {
+    /// Returns `None` when the window or HUD element the readout comes from is not open.
+    fn read(tree: &UiTree) -> Option<Self>;
+}
+
+impl UiTree {
+    pub fn read<R: Reader>(&self) -> Option<R> {
+        R::read(self)
+    }
+}
+
+macro_rules! named_readers {
+    ($($name:literal => $reader:ty,)*) => {
+        /// Names accepted by [`read_json`], one per [`Reader`].
+        pub const READER_NAMES: &[&str] = &[$($name),*];
+
+        /// Runs the reader called `name` and serializes its output, `None` if it found nothing to read.
+        pub fn read_json(tree: &UiTree, name: &str) -> io::Result<Option<serde_json::Value>> {
+            match name {
+                $($name => Ok(tree.read::<$reader>().map(serde_json::to_value).transpose()?),)*
+                _ => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown reader {:?}", name))),
+            }
+        }
+    };
+}
+
+named_readers! {
+    "abyssal_timer" => abyssal::AbyssalTimer,
+    "active_effects" => effects::ActiveEffects,
+    "agent_conversation" => agent_conversation::AgentConversation,
+    "aggression_timers" => timers::AggressionTimers,
+    "autopilot_state" => route::AutopilotState,
+    "bookmarks" => bookmarks::Bookmarks,
+    "character_sheet" => character_sheet::CharacterSheet,
+    "client_disconnected" => connection::ClientDisconnected,
+    "client_performance" => performance::ClientPerformance,
+    "clone_bay" => clones::CloneBay,
+    "combat_feed" => combat::CombatFeed,
+    "corp_hangar" => inventory::CorpHangar,
+    "environmental_hazards" => hazards::EnvironmentalHazards,
+    "flight_state" => flight_state::FlightState,
+    "industry_jobs" => industry::IndustryJobs,
+    "local_chat" => local::LocalChat,
+    "local_intel" => local::LocalIntel,
+    "location" => location::Location,
+    "lp_store" => lp_store::LpStore,
+    "mining_readout" => mining::MiningReadout,
+    "mission_journal" => journal::MissionJournal,
+    "notifications" => notifications::Notifications,
+    "planetary_industry" => planetary::PlanetaryIndustry,
+    "reprocessing" => reprocessing::Reprocessing,
+    "route" => route::Route,
+    "session_timer" => timers::SessionTimer,
+    "ship_hangar" => inventory::ShipHangar,
+    "ship_modules" => ship_ui::ShipModules,
+    "space_brackets" => brackets::SpaceBrackets,
+    "tactical_overlay" => tactical::TacticalOverlay,
+    "targets" => targets::Targets,
+}
+
+/// Finds an open window by its python type or window name.
+pub fn find_window<'a>(tree: &'a UiTree, ids: &[&str]) -> Option<&'a UiNode> {
+    tree.root.find_first(|node| {
+        node.is_visible()
+            && (ids.contains(&node.py_type.as_str()) || node.name().is_some_and(|name| ids.contains(&name)))
+    })
+}
+
+/// Label texts of the visible part of `node`, in tree order and stripped of markup.
+pub fn labels(node: &UiNode) -> Vec<String> {
+    fn collect(node: &UiNode, result: &mut Vec<String>) {
+        if !node.is_visible() {
+            return;
+        }
+        if let Some(text) = node.text() {
+            let text = strip_tags(text);
+            if !text.is_empty() {
+                result.push(text);
+            }
+        }
+        for child in &node.children {
+            collect(child, result);
+        }
+    }
+    let mut result = vec![];
+    collect(node, &mut result);
+    result
+}
+
+/// A clickable button and its label.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UiButton {
+    pub label: String,
+    pub rect: UiRect,
+}
+
+/// Visible buttons below `node`, in tree order.
+pub fn buttons(node: &UiNode) -> Vec<UiButton> {
+    node.find_all(|node| node.is_visible() && node.py_type.contains("Button"))
+        .into_iter()
+        .filter_map(|button| {
+            Some(UiButton {
+                label: labels(button).into_iter().next()?,
+                rect: button.rect,
+            })
+        })
+        .collect()
+}
+
+/// The `typeID` of the item shown by `node` or one of its icons, if the client keeps it on the node.
+pub fn type_id(node: &UiNode) -> Option<u32> {
+    node.iter()
+        .find_map(|node| node.attr_i64("typeID").or_else(|| node.attr_i64("_typeID")))
+        .and_then(|type_id| u32::try_from(type_id).ok())
+}
+
+/// The caption of a window, without the window kind prefix (`Agent Conversation - `).
+pub fn window_caption(window: &UiNode) -> Option<String> {
+    let caption = window
+        .attr_str("_caption")
+        .map(strip_tags)
+        .or_else(|| window.find_by_name("caption").and_then(|node| labels(node).into_iter().next()))?;
+    Some(match caption.split_once(" - ") {
+        Some((_, subject)) => subject.trim().to_string(),
+        None => caption,
+    })
+}
+
+/// Finds the value shown for `label`, either behind it in the same text (`Label: value`)
+/// or in the label that follows.
+pub fn labelled_value<'a>(labels: &'a [String], label: &str) -> Option<&'a str> {
+    let label = label.to_lowercase();
+    let index = labels
+        .iter()
+        .position(|text| text.to_lowercase().starts_with(&label))?;
+    let text = &labels[index];
+    // the label may be localized to a different byte length than its lowercase form
+    let rest = text
+        .char_indices()
+        .nth(label.chars().count())
+        .map(|(i, _)| &text[i..])
+        .unwrap_or("")
+        .trim_start_matches(|c: char| c == ':' || c.is_whitespace());
+    if !rest.is_empty() {
+        Some(rest)
+    } else {
+        labels.get(index + 1).map(String::as_str)
+    }
+}
+
+/// Removes the client's markup (`<color=...>`, `<b>`, `<br>`, ...) from a label text.
+pub fn strip_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => {
+                let tag = &rest[start + 1..start + end];
+                if tag.eq_ignore_ascii_case("br") || tag.eq_ignore_ascii_case("br/") {
+                    result.push('\n');
+                } else if tag.eq_ignore_ascii_case("t") {
+                    result.push('\t');
+                }
+                rest = &rest[start + end + 1..];
+            }
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Whether `word` appears in `text` as a whole word, ignoring ASCII case.
+pub fn contains_word(text: &str, word: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric()).any(|part| part.eq_ignore_ascii_case(word))
+}
+
+/// Parses the first number of a text, accepting `,`, ` ` and no-break spaces as thousands separators.
+pub fn parse_number(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let negative = text[..start].ends_with('-');
+    let mut digits = String::new();
+    for c in text[start..].chars() {
+        match c {
+            '0'..='9' | '.' => digits.push(c),
+            ',' | ' ' | '\u{a0}' | '\u{202f}' => continue,
+            _ => break,
+        }
+    }
+    let value: f64 = digits.trim_end_matches('.').parse().ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Parses a distance such as `2,500 m`, `12 km` or `3.1 AU` into meters.
+pub fn parse_distance(text: &str) -> Option<f64> {
+    const AU: f64 = 149_597_870_700.0;
+    let value = parse_number(text)?;
+    let unit_start = text.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let unit = text[unit_start..].trim().to_lowercase();
+    let factor = if unit.starts_with("km") {
+        1000.0
+    } else if unit.starts_with("au") {
+        AU
+    } else {
+        1.0
+    };
+    Some(value * factor)
+}
+
+/// Parses the first `NN%` of a text.
+pub fn parse_percent(text: &str) -> Option<f64> {
+    let end = text.find('%')?;
+    let start = text[..end]
+        .rfind(|c: char| !(c.is_ascii_digit() || c == '.' || c == ' '))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    text[start..end].trim().parse().ok()
+}
+
+/// Parses a duration shown as `1d 2h 3m 4s`, `HH:MM:SS` or `MM:SS`.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    // the clock is around the first `:` between digits, not a label like `Time remaining:`
+    let colon = text.char_indices().map(|(i, _)| i).find(|&i| {
+        text[i..].starts_with(':')
+            && text[..i].ends_with(|c: char| c.is_ascii_digit())
+            && text[i + 1..].starts_with(|c: char| c.is_ascii_digit())
+    });
+    if let Some(colon) = colon {
+        let is_clock = |c: char| c.is_ascii_digit() || c == ':';
+        let start = text[..colon].trim_end_matches(is_clock).len();
+        let end = text.len() - text[colon..].trim_start_matches(is_clock).len();
+        let parts: Vec<u64> = text[start..end]
+            .trim_matches(':')
+            .split(':')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let seconds = parts.iter().fold(0, |acc, part| acc * 60 + part);
+        return Some(Duration::from_secs(seconds));
+    }
+    let mut seconds = 0.0;
+    let mut found = false;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'd' => 86400.0,
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            // between a value and its unit, as in `30 s`
+            _ if c.is_whitespace() => continue,
+            _ => {
+                number.clear();
+                continue;
+            }
+        };
+        if let Ok(value) = number.parse::<f64>() {
+            seconds += value * unit;
+            found = true;
+        }
+        number.clear();
+    }
+    found.then(|| Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_clocks_after_labels() {
+        assert_eq!(parse_duration("Time remaining: 04:59"), Some(Duration::from_secs(299)));
+        assert_eq!(parse_duration("01:02:03"), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_duration("剩余时间：12:00"), Some(Duration::from_secs(720)));
+    }
+
+    #[test]
+    fn parses_units_with_and_without_spaces() {
+        assert_eq!(parse_duration("30 s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1d 2h 3m 4s"), Some(Duration::from_secs(93784)));
+        assert_eq!(parse_duration("2 m 30 s"), Some(Duration::from_secs(150)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::from_secs(5400)));
+    }
+
+    #[test]
+    fn finds_no_duration_without_one() {
+        assert_eq!(parse_duration("Time remaining:"), None);
+        assert_eq!(parse_duration("no timer"), None);
+    }
+}
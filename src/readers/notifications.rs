@@ -0,0 +1,111 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::{contains_word, labels, Reader};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    SkillComplete,
+    Contract,
+    War,
+    Other,
+}
+
+impl NotificationKind {
+    fn classify(title: &str) -> Self {
+        let lower = title.to_lowercase();
+        if lower.contains("skill") {
+            NotificationKind::SkillComplete
+        } else if lower.contains("contract") {
+            NotificationKind::Contract
+        } else if ["war", "wars", "wardec"].iter().any(|word| contains_word(title, word)) {
+            NotificationKind::War
+        } else {
+            NotificationKind::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: Option<String>,
+    /// Time as displayed by the client, `YYYY.MM.DD HH:MM`.
+    pub timestamp: Option<String>,
+    pub unread: bool,
+}
+
+/// Entries of the neocom notification feed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Notifications {
+    pub entries: Vec<Notification>,
+}
+
+impl Notifications {
+    pub fn unread(&self) -> impl Iterator<Item = &Notification> {
+        self.entries.iter().filter(|entry| entry.unread)
+    }
+}
+
+fn is_timestamp(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 10 && bytes[..4].iter().all(u8::is_ascii_digit) && matches!(bytes[4], b'.' | b'-')
+}
+
+fn read_notification(entry: &UiNode) -> Option<Notification> {
+    let texts = labels(entry);
+    let timestamp = texts.iter().find(|text| is_timestamp(text)).cloned();
+    let mut content = texts.into_iter().filter(|text| !is_timestamp(text));
+    let title = content.next()?;
+    let unread = entry.attr_bool("isUnread").unwrap_or(false)
+        || entry
+            .find_first(|node| node.is_visible() && node.name().is_some_and(|name| name.contains("unread")))
+            .is_some();
+    Some(Notification {
+        kind: NotificationKind::classify(&title),
+        body: content.next(),
+        title,
+        timestamp,
+        unread,
+    })
+}
+
+impl Reader for Notifications {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let feed = tree.root.find_first(|node| {
+            node.is_visible() && matches!(node.py_type.as_str(), "NotificationCenter" | "NotificationFormsWindow")
+        })?;
+        let entries = feed
+            .find_all(|node| node.is_visible() && node.py_type == "NotificationEntry")
+            .into_iter()
+            .filter_map(read_notification)
+            .collect();
+        Some(Notifications { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_war_notifications() {
+        assert_eq!(NotificationKind::classify("War Declared"), NotificationKind::War);
+        assert_eq!(NotificationKind::classify("Corporation at war: Wardec retracted"), NotificationKind::War);
+        assert_eq!(NotificationKind::classify("Ally joined the war"), NotificationKind::War);
+    }
+
+    #[test]
+    fn does_not_find_war_inside_other_words() {
+        for title in ["Mission reward received", "Structure warning", "Forwarded mail", "Award granted"] {
+            assert_eq!(NotificationKind::classify(title), NotificationKind::Other, "{}", title);
+        }
+    }
+
+    #[test]
+    fn classifies_skills_and_contracts() {
+        assert_eq!(NotificationKind::classify("Skill training complete"), NotificationKind::SkillComplete);
+        assert_eq!(NotificationKind::classify("Contracts: item exchange accepted"), NotificationKind::Contract);
+    }
+}
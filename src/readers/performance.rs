@@ -0,0 +1,31 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::{find_window, labelled_value, labels, parse_number, Reader};
+use serde::Serialize;
+
+/// Values of the in-client FPS / latency monitor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ClientPerformance {
+    pub fps: Option<f64>,
+    /// Frame time in milliseconds.
+    pub frame_time: Option<f64>,
+    /// Server round trip in milliseconds.
+    pub latency: Option<f64>,
+}
+
+impl Reader for ClientPerformance {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["FpsMonitor", "GraphsWindow", "fpsMonitor"])?;
+        let texts = labels(window);
+        let value = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| labelled_value(&texts, name))
+                .and_then(parse_number)
+        };
+        Some(ClientPerformance {
+            fps: value(&["FPS"]),
+            frame_time: value(&["Frame Time", "Frametime"]),
+            latency: value(&["Latency", "Ping"]),
+        })
+    }
+}
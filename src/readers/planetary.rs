@@ -0,0 +1,116 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::{find_window, labelled_value, labels, parse_duration, parse_number, Reader};
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Extractor {
+    pub product: Option<String>,
+    pub cycle_time: Option<Duration>,
+    /// Time until the extraction program expires.
+    pub remaining: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKind {
+    Storage,
+    Launchpad,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StoredItem {
+    pub name: String,
+    pub quantity: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanetStorage {
+    pub kind: StorageKind,
+    /// Used volume in m³.
+    pub used: Option<f64>,
+    pub capacity: Option<f64>,
+    pub contents: Vec<StoredItem>,
+}
+
+impl PlanetStorage {
+    pub fn fill_ratio(&self) -> Option<f64> {
+        Some(self.used? / self.capacity.filter(|capacity| *capacity > 0.0)?)
+    }
+}
+
+/// Pins of the planet view whose info panels are open.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlanetaryIndustry {
+    pub planet: Option<String>,
+    pub extractors: Vec<Extractor>,
+    pub storages: Vec<PlanetStorage>,
+}
+
+impl Reader for PlanetaryIndustry {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let view = find_window(tree, &["l_planet", "PlanetWindow"])?;
+        let planet = view
+            .find_by_name("planetNameLabel")
+            .and_then(|label| labels(label).into_iter().next());
+        let containers = |types: &[&str]| {
+            view.find_all(|node| node.is_visible() && types.contains(&node.py_type.as_str()))
+        };
+        let extractors = containers(&["ExtractorPinContainer", "EcuContainer"])
+            .into_iter()
+            .map(|container| {
+                let texts = labels(container);
+                Extractor {
+                    product: labelled_value(&texts, "Product").map(str::to_string),
+                    cycle_time: labelled_value(&texts, "Cycle Time").and_then(parse_duration),
+                    remaining: labelled_value(&texts, "Expires")
+                        .or_else(|| labelled_value(&texts, "Time Remaining"))
+                        .and_then(parse_duration),
+                }
+            })
+            .collect();
+        let storages = containers(&["StorageFacilityContainer", "StoragePinContainer"])
+            .into_iter()
+            .map(|container| read_storage(container, StorageKind::Storage))
+            .chain(
+                containers(&["LaunchpadContainer", "LaunchPadContainer"])
+                    .into_iter()
+                    .map(|container| read_storage(container, StorageKind::Launchpad)),
+            )
+            .collect();
+        Some(PlanetaryIndustry {
+            planet,
+            extractors,
+            storages,
+        })
+    }
+}
+
+/// Parses a `used/capacity` pair such as `4,000.0/12,000.0 m³`.
+fn parse_fill(text: &str) -> Option<(f64, f64)> {
+    let (used, capacity) = text.split_once('/')?;
+    Some((parse_number(used)?, parse_number(capacity)?))
+}
+
+fn read_storage(container: &UiNode, kind: StorageKind) -> PlanetStorage {
+    let texts = labels(container);
+    let fill = labelled_value(&texts, "Capacity").and_then(parse_fill);
+    let contents = container
+        .find_all(|node| node.is_visible() && node.py_type.contains("ContentEntry"))
+        .into_iter()
+        .filter_map(|entry| {
+            let mut texts = labels(entry).into_iter();
+            let name = texts.next()?;
+            Some(StoredItem {
+                quantity: texts.next().as_deref().and_then(parse_number).map(|q| q as i64),
+                name,
+            })
+        })
+        .collect();
+    PlanetStorage {
+        kind,
+        used: fill.map(|(used, _)| used),
+        capacity: fill.map(|(_, capacity)| capacity),
+        contents,
+    }
+}
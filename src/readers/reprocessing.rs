@@ -0,0 +1,36 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::inventory::{read_items, InventoryItem};
+use crate::readers::{find_window, labelled_value, labels, parse_percent, Reader};
+use serde::Serialize;
+
+/// Preview of the reprocessing window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Reprocessing {
+    pub inputs: Vec<InventoryItem>,
+    /// Expected output after yield and tax.
+    pub outputs: Vec<InventoryItem>,
+    /// Tax in percent.
+    pub tax: Option<f64>,
+    /// Yield in percent.
+    pub efficiency: Option<f64>,
+}
+
+impl Reader for Reprocessing {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let window = find_window(tree, &["ReprocessingWnd", "ReprocessingWindow"])?;
+        let section = |names: &[&str]| {
+            window
+                .find_first(|node| node.name().is_some_and(|name| names.contains(&name)))
+                .map(read_items)
+                .unwrap_or_default()
+        };
+        let texts = labels(window);
+        let percent = |label| labelled_value(&texts, label).and_then(parse_percent);
+        Some(Reprocessing {
+            inputs: section(&["inputContainer", "inputItems"]),
+            outputs: section(&["outputContainer", "outputItems"]),
+            tax: percent("Tax").or_else(|| percent("Station Tax")),
+            efficiency: percent("Reprocessing Efficiency").or_else(|| percent("Yield")),
+        })
+    }
+}
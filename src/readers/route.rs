@@ -0,0 +1,91 @@
+use crate::eve_process::ui_tree::{UiRect, UiTree};
+use crate::readers::{labelled_value, labels, parse_number, strip_tags, Reader};
+use serde::Serialize;
+
+/// A system marker of the route bar.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RouteWaypoint {
+    pub system: Option<String>,
+    pub rect: UiRect,
+}
+
+/// Autopilot route shown in the route info panel.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Route {
+    /// Systems on the route, starting with the next jump.
+    pub waypoints: Vec<RouteWaypoint>,
+    pub jumps_remaining: Option<i64>,
+    pub destination: Option<String>,
+}
+
+impl Reader for Route {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let panel = tree
+            .root
+            .find_first(|node| node.is_visible() && node.py_type == "InfoPanelRoute")?;
+        let mut markers = panel.find_all(|node| node.is_visible() && node.py_type == "AutopilotDestinationIcon");
+        // markers are laid out left to right, top to bottom when the route wraps
+        markers.sort_by_key(|marker| (marker.rect.y, marker.rect.x));
+        let waypoints: Vec<_> = markers
+            .into_iter()
+            .map(|marker| RouteWaypoint {
+                system: marker
+                    .hint()
+                    .map(strip_tags)
+                    .map(|hint| hint.lines().next().unwrap_or_default().trim().to_string()),
+                rect: marker.rect,
+            })
+            .collect();
+        let texts = labels(panel);
+        let jumps_remaining = texts
+            .iter()
+            .find(|text| text.to_lowercase().contains("jump"))
+            .and_then(|text| parse_number(text))
+            .map(|jumps| jumps as i64);
+        let destination = labelled_value(&texts, "Current Destination")
+            .or_else(|| labelled_value(&texts, "Destination"))
+            .map(str::to_string)
+            .or_else(|| waypoints.last().and_then(|waypoint| waypoint.system.clone()));
+        Some(Route {
+            waypoints,
+            jumps_remaining,
+            destination,
+        })
+    }
+}
+
+/// Whether the autopilot is flying the route.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AutopilotState {
+    pub engaged: bool,
+    /// System the next gate on the route leads to.
+    pub next_gate: Option<String>,
+}
+
+impl Reader for AutopilotState {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let ship_ui = tree.root.find_by_type("ShipUI")?;
+        let indicator = ship_ui.find_first(|node| {
+            node.py_type.contains("Autopilot") || node.name().is_some_and(|name| name.eq_ignore_ascii_case("autopilotBtn"))
+        });
+        // the indicator keeps its toggle state, older builds only reflect it in the hint
+        let engaged = indicator
+            .map(|indicator| {
+                ["isActive", "_active", "active"]
+                    .iter()
+                    .find_map(|key| indicator.attr_bool(key))
+                    .or_else(|| {
+                        indicator
+                            .hint()
+                            .map(|hint| hint.to_lowercase().contains("deactivate"))
+                    })
+                    .unwrap_or(false)
+            })
+            .unwrap_or(false);
+        let next_gate = tree
+            .read::<Route>()
+            .and_then(|route| route.waypoints.into_iter().next())
+            .and_then(|waypoint| waypoint.system);
+        Some(AutopilotState { engaged, next_gate })
+    }
+}
@@ -0,0 +1,128 @@
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use crate::readers::{parse_percent, type_id, Reader};
+use serde::Serialize;
+
+/// The rack a module slot belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleRack {
+    High,
+    Medium,
+    Low,
+}
+
+impl ModuleRack {
+    /// Derives the rack from a slot name such as `inFlightHighSlot1`.
+    pub fn from_slot_name(slot_name: &str) -> Option<Self> {
+        if slot_name.contains("High") {
+            Some(ModuleRack::High)
+        } else if slot_name.contains("Medium") {
+            Some(ModuleRack::Medium)
+        } else if slot_name.contains("Low") {
+            Some(ModuleRack::Low)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModuleSlot {
+    pub slot_name: String,
+    pub rack: Option<ModuleRack>,
+    pub type_id: Option<u32>,
+    /// Only known after resolving `type_id`, the HUD does not show module names.
+    pub type_name: Option<String>,
+    pub is_active: bool,
+    /// Overheating is switched on for this module.
+    pub is_overloaded: bool,
+    /// Heat damage taken by the module, in percent of its hitpoints.
+    pub heat_damage: Option<f64>,
+    pub rect: UiRect,
+}
+
+/// Heat level of each rack, in percent of the burnout threshold.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct RackHeat {
+    pub high: Option<f64>,
+    pub medium: Option<f64>,
+    pub low: Option<f64>,
+}
+
+/// Fitted modules shown around the capacitor in the ship HUD.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShipModules {
+    pub slots: Vec<ModuleSlot>,
+    pub heat: RackHeat,
+}
+
+impl ShipModules {
+    pub fn rack(&self, rack: ModuleRack) -> impl Iterator<Item = &ModuleSlot> {
+        self.slots.iter().filter(move |slot| slot.rack == Some(rack))
+    }
+
+    pub fn overloaded(&self) -> impl Iterator<Item = &ModuleSlot> {
+        self.slots.iter().filter(|slot| slot.is_overloaded)
+    }
+}
+
+impl Reader for ShipModules {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let ship_ui = tree.root.find_by_type("ShipUI")?;
+        Some(ShipModules {
+            slots: ship_ui
+                .find_all_by_type("ShipSlot")
+                .into_iter()
+                .filter_map(read_module_slot)
+                .collect(),
+            heat: ship_ui
+                .find_by_type("HeatGauges")
+                .map(read_rack_heat)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Empty slots have no `ModuleButton` and are skipped.
+fn read_module_slot(slot: &UiNode) -> Option<ModuleSlot> {
+    let button = slot.find_by_type("ModuleButton")?;
+    let slot_name = slot.name().unwrap_or_default().to_string();
+    let is_overloaded = slot
+        .find_by_name("overloadBtn")
+        .and_then(|btn| btn.attr_str("texturePath"))
+        .map(|path| path.to_ascii_lowercase().contains("overloadbtnon"))
+        .unwrap_or(false);
+    // the button keeps the damage as a fraction, the indicator only shows it in its hint
+    let heat_damage = button
+        .attr_f64("moduleDamage")
+        .map(|damage| damage * 100.0)
+        .or_else(|| {
+            slot.find_by_name("damageState")
+                .and_then(UiNode::hint)
+                .and_then(parse_percent)
+        });
+    Some(ModuleSlot {
+        rack: ModuleRack::from_slot_name(&slot_name),
+        slot_name,
+        type_id: type_id(button),
+        type_name: None,
+        is_active: button.attr_bool("ramp_active").unwrap_or(false),
+        is_overloaded,
+        heat_damage,
+        rect: slot.rect,
+    })
+}
+
+fn read_rack_heat(gauges: &UiNode) -> RackHeat {
+    let gauge = |names: &[&str]| {
+        let node = gauges.find_first(|node| node.name().is_some_and(|name| names.contains(&name)))?;
+        node.hint()
+            .and_then(parse_percent)
+            .or_else(|| node.attr_f64("_value").map(|value| value * 100.0))
+    };
+    RackHeat {
+        high: gauge(&["heatHigh", "heatHi"]),
+        medium: gauge(&["heatMed"]),
+        low: gauge(&["heatLow"]),
+    }
+}
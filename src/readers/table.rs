@@ -0,0 +1,88 @@
+use crate::eve_process::ui_tree::UiNode;
+use crate::readers::{labels, strip_tags};
+use std::collections::HashMap;
+
+/// A row of a scroll list, with its cells keyed by column header.
+#[derive(Debug, Clone)]
+pub struct TableRow<'a> {
+    pub node: &'a UiNode,
+    pub cells: HashMap<String, String>,
+}
+
+impl TableRow<'_> {
+    /// Looks a cell up by any of the given (case-insensitive) column names.
+    pub fn cell(&self, columns: &[&str]) -> Option<&str> {
+        columns.iter().find_map(|column| {
+            self.cells
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(column))
+                .map(|(_, value)| value.as_str())
+        })
+    }
+}
+
+/// Column headers of a scroll, with their horizontal extent.
+fn read_headers(scroll: &UiNode) -> Vec<(String, i32, i32)> {
+    let mut headers: Vec<_> = scroll
+        .find_by_name("scrollHeaders")
+        .map(|headers| {
+            headers
+                .iter()
+                .filter(|node| node.is_visible())
+                .filter_map(|node| {
+                    let text = strip_tags(node.text()?);
+                    Some((text, node.rect.x, node.rect.x + node.rect.width))
+                })
+                .filter(|(text, _, _)| !text.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    headers.sort_by_key(|(_, left, _)| *left);
+    headers
+}
+
+/// Reads the rows of type `entry_types` below `scroll`. Cells are assigned to the column header
+/// above them, rows rendered as a single tab separated label are split in column order.
+/// Without headers the cells are keyed by their index.
+pub fn read_table<'a>(scroll: &'a UiNode, entry_types: &[&str]) -> Vec<TableRow<'a>> {
+    let headers = read_headers(scroll);
+    let column_name = |index: usize| {
+        headers
+            .get(index)
+            .map(|(name, _, _)| name.clone())
+            .unwrap_or_else(|| index.to_string())
+    };
+    scroll
+        .find_all(|node| node.is_visible() && entry_types.contains(&node.py_type.as_str()))
+        .into_iter()
+        .map(|row| {
+            let mut cells = HashMap::new();
+            let texts = labels(row);
+            if let [single] = texts.as_slice() {
+                if single.contains('\t') {
+                    for (index, cell) in single.split('\t').enumerate() {
+                        cells.insert(column_name(index), cell.trim().to_string());
+                    }
+                    return TableRow { node: row, cells };
+                }
+            }
+            let cell_nodes = row.find_all(|node| node.is_visible() && node.text().is_some());
+            for (index, node) in cell_nodes.into_iter().enumerate() {
+                let text = strip_tags(node.text().unwrap_or_default());
+                let column = headers
+                    .iter()
+                    .find(|(_, left, right)| node.rect.x + 1 >= *left && node.rect.x < *right)
+                    .map(|(name, _, _)| name.clone())
+                    .unwrap_or_else(|| column_name(index));
+                cells
+                    .entry(column)
+                    .and_modify(|cell: &mut String| {
+                        cell.push(' ');
+                        cell.push_str(&text);
+                    })
+                    .or_insert(text);
+            }
+            TableRow { node: row, cells }
+        })
+        .collect()
+}
@@ -0,0 +1,60 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::Reader;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CameraMode {
+    Orbit,
+    Tactical,
+    Pov,
+}
+
+impl CameraMode {
+    fn from_hint(hint: &str) -> Option<Self> {
+        let lower = hint.to_lowercase();
+        if lower.contains("tactical") {
+            Some(CameraMode::Tactical)
+        } else if lower.contains("pov") || lower.contains("point of view") {
+            Some(CameraMode::Pov)
+        } else if lower.contains("orbit") {
+            Some(CameraMode::Orbit)
+        } else {
+            None
+        }
+    }
+}
+
+/// Tactical overlay and camera state, as far as the HUD reflects it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TacticalOverlay {
+    pub enabled: bool,
+    pub camera_mode: Option<CameraMode>,
+    /// Position of the camera zoom slider, `0.0..=1.0`.
+    pub zoom: Option<f64>,
+}
+
+impl Reader for TacticalOverlay {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let ship_ui = tree.root.find_by_type("ShipUI")?;
+        let enabled = tree
+            .root
+            .find_first(|node| node.py_type == "TacticalOverlay" || node.name() == Some("l_tactical"))
+            .is_some_and(|overlay| overlay.is_visible() && !overlay.children.is_empty());
+        // the selected camera button is the only one flagged active
+        let camera_mode = ship_ui
+            .find_all(|node| node.py_type.contains("Camera") && node.py_type.contains("Button"))
+            .into_iter()
+            .filter(|button| button.attr_bool("isActive").or(button.attr_bool("_selected")).unwrap_or(false))
+            .find_map(|button| button.hint().and_then(CameraMode::from_hint));
+        let zoom = tree
+            .root
+            .find_first(|node| node.name().is_some_and(|name| name.to_lowercase().contains("zoomslider")))
+            .and_then(|slider| slider.attr_f64("_value"));
+        Some(TacticalOverlay {
+            enabled,
+            camera_mode,
+            zoom,
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use crate::readers::{labels, parse_distance, Reader};
+use serde::Serialize;
+use std::f64::consts::TAU;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Target {
+    pub name: Option<String>,
+    pub type_name: Option<String>,
+    /// Distance in meters.
+    pub distance: Option<f64>,
+    pub is_active: bool,
+    pub rect: UiRect,
+}
+
+/// A target still being locked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LockInProgress {
+    pub target: Target,
+    /// Completed fraction of the lock, `0.0..=1.0`.
+    pub progress: Option<f64>,
+}
+
+/// Locked and locking targets of the target bar.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Targets {
+    pub locked: Vec<Target>,
+    pub locking: Vec<LockInProgress>,
+}
+
+impl Targets {
+    pub fn active(&self) -> Option<&Target> {
+        self.locked.iter().find(|target| target.is_active)
+    }
+}
+
+fn is_lock_indicator(node: &UiNode) -> bool {
+    node.is_visible() && (node.py_type.contains("Locking") || node.name().is_some_and(|name| name.contains("locking")))
+}
+
+/// The lock indicator is a radial gauge, depending on the build it keeps a fraction or an angle.
+fn lock_progress(indicator: &UiNode) -> Option<f64> {
+    indicator
+        .attr_f64("_value")
+        .or_else(|| indicator.attr_f64("_rotation").map(|angle| angle / TAU))
+        .map(|progress| progress.clamp(0.0, 1.0))
+}
+
+fn read_target(entry: &UiNode) -> Target {
+    let texts = labels(entry);
+    let distance = texts.iter().find_map(|text| {
+        text.chars()
+            .next()
+            .filter(char::is_ascii_digit)
+            .and_then(|_| parse_distance(text))
+    });
+    let mut names = texts.iter().filter(|text| !text.starts_with(|c: char| c.is_ascii_digit()));
+    Target {
+        name: names.next().cloned(),
+        type_name: names.next().cloned(),
+        distance,
+        is_active: entry.find_first(|node| node.is_visible() && node.py_type == "ActiveTargetOnBracket").is_some(),
+        rect: entry.rect,
+    }
+}
+
+impl Reader for Targets {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let mut targets = Targets::default();
+        for entry in tree.root.find_all(|node| node.is_visible() && node.py_type == "TargetInBar") {
+            let target = read_target(entry);
+            match entry.find_first(is_lock_indicator) {
+                Some(indicator) => targets.locking.push(LockInProgress {
+                    target,
+                    progress: lock_progress(indicator),
+                }),
+                None => targets.locked.push(target),
+            }
+        }
+        Some(targets)
+    }
+}
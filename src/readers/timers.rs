@@ -0,0 +1,107 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::{labels, parse_duration, parse_number, Reader};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Remaining time shown on a countdown indicator, plain numbers are seconds.
+fn countdown(indicator: &UiNode) -> Option<Duration> {
+    let text = labels(indicator).into_iter().next()?;
+    parse_duration(&text).or_else(|| parse_number(&text).map(Duration::from_secs_f64))
+}
+
+/// The session change timer, jumping and docking are blocked while it runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SessionTimer {
+    pub active: bool,
+    pub remaining: Option<Duration>,
+}
+
+impl Reader for SessionTimer {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let indicator = tree.root.find_first(|node| {
+            node.is_visible() && (node.py_type == "SessionTimeIndicator" || node.name() == Some("sessionTimer"))
+        });
+        Some(match indicator {
+            Some(indicator) => SessionTimer {
+                active: true,
+                remaining: countdown(indicator),
+            },
+            None => SessionTimer::default(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerKind {
+    Weapons,
+    Pvp,
+    Npc,
+    LimitedEngagement,
+    Criminal,
+    Suspect,
+    Other(String),
+}
+
+impl TimerKind {
+    /// Maps the name of a timer icon (`weaponsTimer`, `pvpTimer`, ...) to its kind.
+    pub fn from_name(name: &str) -> Self {
+        let lower = name.to_lowercase();
+        if lower.contains("weapon") {
+            TimerKind::Weapons
+        } else if lower.contains("pvp") {
+            TimerKind::Pvp
+        } else if lower.contains("npc") {
+            TimerKind::Npc
+        } else if lower.contains("engagement") {
+            TimerKind::LimitedEngagement
+        } else if lower.contains("criminal") {
+            TimerKind::Criminal
+        } else if lower.contains("suspect") {
+            TimerKind::Suspect
+        } else {
+            TimerKind::Other(name.to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HudTimer {
+    pub kind: TimerKind,
+    pub remaining: Option<Duration>,
+}
+
+/// Aggression timers shown in the timer container above the ship HUD.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AggressionTimers {
+    pub timers: Vec<HudTimer>,
+}
+
+impl AggressionTimers {
+    pub fn get(&self, kind: &TimerKind) -> Option<&HudTimer> {
+        self.timers.iter().find(|timer| &timer.kind == kind)
+    }
+}
+
+impl Reader for AggressionTimers {
+    fn read(tree: &UiTree) -> Option<Self> {
+        let ship_ui = tree.root.find_by_type("ShipUI")?;
+        let container = ship_ui.find_first(|node| node.py_type == "TimerContainer" || node.name() == Some("timers"));
+        let timers = container
+            .map(|container| {
+                container
+                    .find_all(|node| {
+                        node.is_visible()
+                            && node.name().is_some_and(|name| name.ends_with("Timer") && name != "sessionTimer")
+                    })
+                    .into_iter()
+                    .map(|timer| HudTimer {
+                        kind: TimerKind::from_name(timer.name().unwrap_or_default()),
+                        remaining: countdown(timer),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(AggressionTimers { timers })
+    }
+}
@@ -0,0 +1,343 @@
+use crate::eve_process::ui_tree::{PyValue, UiNode, UiRect, UiTree};
+use crate::eve_process::ui_watcher::UiUpdate;
+use crate::export::jsonl::JsonLinesWriter;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A UI tree read at `time`.
+///
+/// Unlike the watch output the whole [`UiTree`] with all attributes is kept, so that readers
+/// work on played back trees like on live ones.
+#[derive(Debug, Clone)]
+pub struct RecordedFrame {
+    /// Seconds since the unix epoch.
+    pub time: f64,
+    pub tree: UiTree,
+}
+
+/// A node without its subtree, children are referenced by address.
+#[derive(Debug, Clone, PartialEq)]
+struct FlatNode {
+    py_type: String,
+    rect: UiRect,
+    attrs: BTreeMap<String, PyValue>,
+    children: Vec<u64>,
+}
+
+type FlatTree = BTreeMap<u64, FlatNode>;
+
+fn flatten(tree: &UiTree) -> FlatTree {
+    tree.root
+        .iter()
+        .map(|node| {
+            let flat = FlatNode {
+                py_type: node.py_type.clone(),
+                rect: node.rect,
+                attrs: node.attrs.clone(),
+                children: node.children.iter().map(|child| child.addr).collect(),
+            };
+            (node.addr, flat)
+        })
+        .collect()
+}
+
+fn build(flat: &FlatTree, addr: u64, visited: &mut HashSet<u64>) -> Option<UiNode> {
+    let node = flat.get(&addr).filter(|_| visited.insert(addr))?;
+    Some(UiNode {
+        addr,
+        py_type: node.py_type.clone(),
+        attrs: node.attrs.clone(),
+        rect: node.rect,
+        children: node
+            .children
+            .iter()
+            .filter_map(|child| build(flat, *child, visited))
+            .collect(),
+    })
+}
+
+/// Fields of a node that are new or changed since the previous frame, all of them for new nodes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct NodeDelta {
+    addr: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    py_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rect: Option<UiRect>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attrs: BTreeMap<String, PyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    removed_attrs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<u64>>,
+}
+
+impl NodeDelta {
+    fn new(addr: u64, node: &FlatNode, previous: Option<&FlatNode>) -> Option<Self> {
+        let Some(old) = previous else {
+            return Some(NodeDelta {
+                addr,
+                py_type: Some(node.py_type.clone()),
+                rect: Some(node.rect),
+                attrs: node.attrs.clone(),
+                removed_attrs: vec![],
+                children: Some(node.children.clone()),
+            });
+        };
+        if old == node {
+            return None;
+        }
+        Some(NodeDelta {
+            addr,
+            py_type: (old.py_type != node.py_type).then(|| node.py_type.clone()),
+            rect: (old.rect != node.rect).then_some(node.rect),
+            attrs: node
+                .attrs
+                .iter()
+                .filter(|(key, value)| old.attrs.get(*key) != Some(value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            removed_attrs: old.attrs.keys().filter(|key| !node.attrs.contains_key(*key)).cloned().collect(),
+            children: (old.children != node.children).then(|| node.children.clone()),
+        })
+    }
+
+    fn apply(self, flat: &mut FlatTree) {
+        let node = flat.entry(self.addr).or_insert_with(|| FlatNode {
+            py_type: String::new(),
+            rect: UiRect::default(),
+            attrs: BTreeMap::new(),
+            children: vec![],
+        });
+        if let Some(py_type) = self.py_type {
+            node.py_type = py_type;
+        }
+        if let Some(rect) = self.rect {
+            node.rect = rect;
+        }
+        for key in &self.removed_attrs {
+            node.attrs.remove(key);
+        }
+        node.attrs.extend(self.attrs);
+        if let Some(children) = self.children {
+            node.children = children;
+        }
+    }
+}
+
+/// One line of a recording: a whole tree, or the changes against the frame before.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "frame", rename_all = "snake_case")]
+enum FrameRecord {
+    Snapshot {
+        time: f64,
+        tree: UiTree,
+    },
+    Delta {
+        time: f64,
+        root: u64,
+        #[serde(default)]
+        nodes: Vec<NodeDelta>,
+        /// Nodes that left the tree.
+        #[serde(default)]
+        removed: Vec<u64>,
+    },
+}
+
+/// Writes UI trees with the time they were read to a JSON-lines file.
+///
+/// Frames are stored as deltas against the frame before, with a whole tree every
+/// `snapshot_interval` frames, so that a damaged line only loses the frames up to the next one.
+pub struct Recorder<W: Write> {
+    out: JsonLinesWriter<W>,
+    pub snapshot_interval: usize,
+    frames: usize,
+    previous: Option<FlatTree>,
+}
+
+impl Recorder<Box<dyn Write>> {
+    /// Creates the recording file, gzip compressed if the name ends with `.gz`.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::create(&path)?;
+        let out: Box<dyn Write> = if path.as_ref().extension().is_some_and(|ext| ext == "gz") {
+            Box::new(GzEncoder::new(BufWriter::new(file), Compression::default()))
+        } else {
+            Box::new(BufWriter::new(file))
+        };
+        Ok(Recorder::new(out))
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(out: W) -> Self {
+        Recorder {
+            out: JsonLinesWriter::new(out),
+            snapshot_interval: 600,
+            frames: 0,
+            previous: None,
+        }
+    }
+
+    /// Records `tree` as read now.
+    pub fn record(&mut self, tree: &UiTree) -> io::Result<()> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        self.record_at(time, tree)
+    }
+
+    pub fn record_at(&mut self, time: f64, tree: &UiTree) -> io::Result<()> {
+        let flat = flatten(tree);
+        let record = match &self.previous {
+            Some(previous) if !self.frames.is_multiple_of(self.snapshot_interval.max(1)) => FrameRecord::Delta {
+                time,
+                root: tree.root.addr,
+                nodes: flat
+                    .iter()
+                    .filter_map(|(addr, node)| NodeDelta::new(*addr, node, previous.get(addr)))
+                    .collect(),
+                removed: previous.keys().filter(|addr| !flat.contains_key(addr)).copied().collect(),
+            },
+            _ => FrameRecord::Snapshot {
+                time,
+                tree: tree.clone(),
+            },
+        };
+        self.out.write(&record)?;
+        self.frames += 1;
+        self.previous = Some(flat);
+        Ok(())
+    }
+}
+
+/// Replays a recording with the pauses between the frames, as if the client was read live.
+pub struct Playback<R: BufRead> {
+    lines: Lines<R>,
+    /// Factor applied to the recorded pace, 0 plays the frames without pausing.
+    pub speed: f64,
+    /// Time of the first frame and when it was played.
+    start: Option<(f64, Instant)>,
+    /// The last frame, deltas apply to it.
+    current: Option<FlatTree>,
+    previous: Option<UiTree>,
+}
+
+impl Playback<BufReader<Box<dyn Read>>> {
+    /// Opens a recording, decompressing it if the name ends with `.gz`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        let input: Box<dyn Read> = if path.as_ref().extension().is_some_and(|ext| ext == "gz") {
+            Box::new(MultiGzDecoder::new(BufReader::new(file)))
+        } else {
+            Box::new(file)
+        };
+        Ok(Playback::new(BufReader::new(input)))
+    }
+}
+
+impl<R: BufRead> Playback<R> {
+    pub fn new(input: R) -> Self {
+        Playback {
+            lines: input.lines(),
+            speed: 1.0,
+            start: None,
+            current: None,
+            previous: None,
+        }
+    }
+
+    /// Reads the next frame without waiting for it, `None` at the end of the recording.
+    ///
+    /// Deltas before the first whole tree are skipped, as are damaged lines and the deltas after
+    /// them up to the next whole tree.
+    pub fn next_frame(&mut self) -> io::Result<Option<RecordedFrame>> {
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                // recordings of a recorder that was killed end without the gzip trailer
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = match serde_json::from_str(&line) {
+                Ok(record) => record,
+                // the deltas after a damaged line would apply to the wrong tree
+                Err(_) => {
+                    self.current = None;
+                    continue;
+                }
+            };
+            match record {
+                FrameRecord::Snapshot { time, tree } => {
+                    self.current = Some(flatten(&tree));
+                    return Ok(Some(RecordedFrame { time, tree }));
+                }
+                FrameRecord::Delta {
+                    time,
+                    root,
+                    nodes,
+                    removed,
+                } => {
+                    let Some(flat) = &mut self.current else { continue };
+                    for addr in removed {
+                        flat.remove(&addr);
+                    }
+                    for delta in nodes {
+                        delta.apply(flat);
+                    }
+                    let root = build(flat, root, &mut HashSet::new()).unwrap_or_default();
+                    return Ok(Some(RecordedFrame {
+                        time,
+                        tree: UiTree { root },
+                    }));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Waits until the next frame is due and returns it with the changes since the previous one,
+    /// like [`UiWatcher::poll`](crate::eve_process::ui_watcher::UiWatcher::poll).
+    pub fn poll(&mut self) -> io::Result<Option<UiUpdate>> {
+        let Some(frame) = self.next_frame()? else {
+            return Ok(None);
+        };
+        let (first_time, started) = *self.start.get_or_insert((frame.time, Instant::now()));
+        if self.speed > 0.0 {
+            let due = Duration::from_secs_f64(((frame.time - first_time) / self.speed).max(0.0));
+            thread::sleep(due.saturating_sub(started.elapsed()));
+        }
+        let changes = self
+            .previous
+            .as_ref()
+            .map(|previous| frame.tree.diff(previous))
+            .unwrap_or_default();
+        self.previous = Some(frame.tree.clone());
+        Ok(Some(UiUpdate {
+            tree: frame.tree,
+            changes,
+        }))
+    }
+
+    /// Plays the recording until it ends or `callback` returns `false`.
+    pub fn run<F: FnMut(&UiUpdate) -> bool>(&mut self, mut callback: F) -> io::Result<()> {
+        while let Some(update) = self.poll()? {
+            if !callback(&update) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::eve_process::annotations::{annotate, annotation};
+use crate::eve_process::eve_process::EVEProcess;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::io;
+
+const HELP: &str = "\
+follow(<obj>, \"<path>\")     follow an attribute path, e.g. follow(ui_root, \"children[2]._name\")
+materialize(<obj>, <depth>) print an object with its attributes expanded to <depth> levels
+find_type <name>            addresses of the type objects called <name>
+ui_roots                    addresses of all UIRoot instances
+annotate(<obj>, \"<label>\")  label an address in the output from now on, also in later runs
+help, quit
+
+<obj> is an address (0x.. or decimal), `ui_root` for the live UI root or `_` for the last result.";
+
+/// Formats `addr` in hex with its annotation, if any.
+fn describe(addr: u64) -> String {
+    match annotation(addr) {
+        Some(label) => format!("0x{:X} «{}»", addr, label),
+        None => format!("0x{:X}", addr),
+    }
+}
+
+/// Splits `name(arg, arg)` or `name arg arg` into the name and unquoted arguments.
+fn parse_command(line: &str) -> (&str, Vec<&str>) {
+    let line = line.trim();
+    let end = line.find(|c: char| c == '(' || c.is_whitespace()).unwrap_or(line.len());
+    let (name, args) = line.split_at(end);
+    let args = args.trim().trim_start_matches('(').trim_end_matches(')');
+    let separator = if args.contains(',') { ',' } else { ' ' };
+    let args = args
+        .split(separator)
+        .map(|arg| arg.trim().trim_matches('"'))
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    (name, args)
+}
+
+struct Repl {
+    process: EVEProcess,
+    /// Address of the last object printed, `_` in commands.
+    last: Option<u64>,
+}
+
+impl Repl {
+    fn resolve(&mut self, obj: &str) -> io::Result<u64> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("unknown object `{}`", obj));
+        match obj {
+            "_" => self.last.ok_or_else(invalid),
+            "ui_root" => self
+                .process
+                .ui_tree()
+                .map(|tree| tree.root.addr)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no UI root found")),
+            _ => match obj.strip_prefix("0x").or_else(|| obj.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16).map_err(|_| invalid()),
+                None => obj.parse().map_err(|_| invalid()),
+            },
+        }
+    }
+
+    fn execute(&mut self, line: &str) -> io::Result<bool> {
+        let (name, args) = parse_command(line);
+        match (name, args.as_slice()) {
+            ("", _) => {}
+            ("quit" | "exit", _) => return Ok(false),
+            ("help", _) => println!("{}", HELP),
+            ("follow", [obj, path]) => {
+                let addr = self.resolve(obj)?;
+                let node = self.process.follow(addr, path)?;
+                let value = self.process.materialize(node.base_addr, 0)?;
+                println!("{} {} {}", describe(node.base_addr), node.tp_name, serde_json::to_string(&value)?);
+                self.last = Some(node.base_addr);
+            }
+            ("materialize", [obj, depth]) => {
+                let addr = self.resolve(obj)?;
+                let depth = depth
+                    .parse()
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "depth must be a number"))?;
+                println!("{}", serde_json::to_string_pretty(&self.process.materialize(addr, depth)?)?);
+                self.last = Some(addr);
+            }
+            ("find_type", [tp_name]) => {
+                for addr in self.process.search_type(tp_name, None) {
+                    println!("{}", describe(addr));
+                }
+            }
+            ("ui_roots", []) => {
+                for addr in self.process.search_ui_root(None) {
+                    println!("{}", describe(addr));
+                }
+            }
+            ("annotate", [obj, label]) => {
+                let addr = self.resolve(obj)?;
+                annotate(addr, label)?;
+                self.last = Some(addr);
+            }
+            _ => println!("cannot parse `{}`, try `help`", line.trim()),
+        }
+        Ok(true)
+    }
+}
+
+/// Reads commands against an initialized process from the terminal until `quit` or EOF.
+pub fn run(process: EVEProcess) -> io::Result<()> {
+    let mut editor = DefaultEditor::new().map_err(io::Error::other)?;
+    let mut repl = Repl { process, last: None };
+    println!("attached to {} ({}), type `help` for commands", repl.process.process.pid, repl.process.process.title);
+    loop {
+        let line = match editor.readline("eve> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(io::Error::other(e)),
+        };
+        let _ = editor.add_history_entry(line.as_str());
+        match repl.execute(&line) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+}
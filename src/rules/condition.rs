@@ -0,0 +1,371 @@
+use crate::eve_process::ui_tree::UiTree;
+use crate::readers::{read_json, READER_NAMES};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Literal(Value),
+    /// A reader name followed by fields, array indexes or `len`.
+    Path(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    /// True unless null, false, zero or empty.
+    Truthy(Operand),
+    Compare(Operand, Comparison, Operand),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+const OPERATORS: &[&str] = &["&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")"];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut rest = source.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        } else if c == '"' || c == '\'' {
+            let end = rest[1..].find(c).ok_or("unterminated string")?;
+            tokens.push(Token::Str(rest[1..end + 1].to_string()));
+            rest = &rest[end + 2..];
+        } else if c.is_ascii_digit() || c == '-' {
+            let end = rest[1..]
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .map_or(rest.len(), |end| end + 1);
+            let number = rest[..end].parse().map_err(|_| format!("invalid number `{}`", &rest[..end]))?;
+            tokens.push(Token::Number(number));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '.')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            return Err(format!("unexpected `{}`", c));
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over `||`, `&&`, `!`, parentheses and comparisons, in that precedence.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, op: &'static str) -> bool {
+        let found = self.peek() == Some(&Token::Op(op));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.eat("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.eat("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.or()?;
+            return if self.eat(")") { Ok(expr) } else { Err("missing `)`".into()) };
+        }
+        let left = self.operand()?;
+        let comparison = match self.peek() {
+            Some(Token::Op("==")) => Comparison::Eq,
+            Some(Token::Op("!=")) => Comparison::Ne,
+            Some(Token::Op("<")) => Comparison::Lt,
+            Some(Token::Op("<=")) => Comparison::Le,
+            Some(Token::Op(">")) => Comparison::Gt,
+            Some(Token::Op(">=")) => Comparison::Ge,
+            _ => return Ok(Expr::Truthy(left)),
+        };
+        self.pos += 1;
+        Ok(Expr::Compare(left, comparison, self.operand()?))
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        let token = self.peek().cloned().ok_or("unexpected end")?;
+        self.pos += 1;
+        Ok(match token {
+            Token::Number(number) => Operand::Literal(number.into()),
+            Token::Str(text) => Operand::Literal(text.into()),
+            Token::Ident(ident) => match ident.as_str() {
+                "true" => Operand::Literal(true.into()),
+                "false" => Operand::Literal(false.into()),
+                "null" => Operand::Literal(Value::Null),
+                _ => {
+                    let path: Vec<String> = ident.split('.').map(str::to_string).collect();
+                    if !READER_NAMES.contains(&path[0].as_str()) {
+                        return Err(format!("unknown reader `{}`", path[0]));
+                    }
+                    Operand::Path(path)
+                }
+            },
+            Token::Op(op) => return Err(format!("unexpected `{}`", op)),
+        })
+    }
+}
+
+/// Reader outputs of one UI tree, each read on first use and `null` if the reader found nothing.
+pub struct Readouts<'a> {
+    tree: &'a UiTree,
+    cache: HashMap<String, Value>,
+}
+
+impl<'a> Readouts<'a> {
+    pub fn new(tree: &'a UiTree) -> Self {
+        Readouts {
+            tree,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, reader: &str) -> &Value {
+        let tree = self.tree;
+        self.cache
+            .entry(reader.to_string())
+            .or_insert_with(|| read_json(tree, reader).ok().flatten().unwrap_or(Value::Null))
+    }
+
+    fn resolve(&mut self, operand: &Operand) -> Value {
+        let path = match operand {
+            Operand::Literal(value) => return value.clone(),
+            Operand::Path(path) => path,
+        };
+        let mut value = self.get(&path[0]);
+        for field in &path[1..] {
+            value = match value {
+                Value::Object(map) if map.contains_key(field) => &map[field],
+                Value::Array(items) if field == "len" => return items.len().into(),
+                Value::Array(items) => match field.parse::<usize>().ok().and_then(|i| items.get(i)) {
+                    Some(item) => item,
+                    None => return Value::Null,
+                },
+                Value::String(text) if field == "len" => return text.chars().count().into(),
+                _ => return Value::Null,
+            };
+        }
+        value.clone()
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn compare(left: &Value, comparison: Comparison, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ if left == right => Some(Ordering::Equal),
+        _ => None,
+    };
+    match comparison {
+        Comparison::Eq => ordering == Some(Ordering::Equal),
+        Comparison::Ne => ordering != Some(Ordering::Equal),
+        Comparison::Lt => ordering == Some(Ordering::Less),
+        Comparison::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        Comparison::Gt => ordering == Some(Ordering::Greater),
+        Comparison::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn evaluate(expr: &Expr, readouts: &mut Readouts) -> bool {
+    match expr {
+        Expr::Truthy(operand) => is_truthy(&readouts.resolve(operand)),
+        Expr::Compare(left, comparison, right) => {
+            compare(&readouts.resolve(left), *comparison, &readouts.resolve(right))
+        }
+        Expr::Not(expr) => !evaluate(expr, readouts),
+        Expr::And(left, right) => evaluate(left, readouts) && evaluate(right, readouts),
+        Expr::Or(left, right) => evaluate(left, readouts) || evaluate(right, readouts),
+    }
+}
+
+/// A condition over reader outputs, like
+/// `flight_state == "in_space" && local_chat.member_count > 5`.
+///
+/// Paths start with a name from [`READER_NAMES`] and continue with fields, array indexes and
+/// `len` for the length of arrays and strings. Anything the readers did not find is `null`,
+/// which only equals `null` and is false on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    source: String,
+    expr: Expr,
+}
+
+impl Condition {
+    pub fn evaluate(&self, readouts: &mut Readouts) -> bool {
+        evaluate(&self.expr, readouts)
+    }
+}
+
+impl FromStr for Condition {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid condition `{}`: {}", s, e));
+        let mut parser = Parser {
+            tokens: tokenize(s).map_err(invalid)?,
+            pos: 0,
+        };
+        let expr = parser.or().map_err(invalid)?;
+        if let Some(token) = parser.peek() {
+            return Err(invalid(format!("unexpected {:?}", token)));
+        }
+        Ok(Condition {
+            source: s.trim().to_string(),
+            expr,
+        })
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn eval(condition: &str, values: &[(&str, Value)]) -> bool {
+        let tree = UiTree::default();
+        let mut readouts = Readouts::new(&tree);
+        for (reader, value) in values {
+            readouts.cache.insert(reader.to_string(), value.clone());
+        }
+        condition.parse::<Condition>().unwrap().evaluate(&mut readouts)
+    }
+
+    fn error(condition: &str) -> String {
+        condition.parse::<Condition>().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert!(eval("true || false && false", &[]));
+        assert!(!eval("(true || false) && false", &[]));
+        assert!(eval("false && false || true", &[]));
+    }
+
+    #[test]
+    fn not_applies_to_the_next_operand() {
+        assert!(!eval("!false && false", &[]));
+        assert!(eval("!(false && false)", &[]));
+        assert!(eval("!!true", &[]));
+        assert!(eval("!location", &[("location", Value::Null)]));
+    }
+
+    #[test]
+    fn compares_numbers_and_strings() {
+        let values = [("flight_state", json!("in_space")), ("local_chat", json!({ "count": 7 }))];
+        assert!(eval("flight_state == \"in_space\" && local_chat.count > 5", &values));
+        assert!(eval("local_chat.count >= 7 && local_chat.count <= 7.0", &values));
+        assert!(eval("local_chat.count != -7 && 'a' < 'b'", &values));
+        assert!(!eval("local_chat.count < 7", &values));
+    }
+
+    #[test]
+    fn len_counts_array_items_and_characters() {
+        let values = [
+            ("local_chat", json!({ "members": [{ "name": "a" }, { "name": "bc" }] })),
+            ("location", json!({ "system": "星战前夜" })),
+        ];
+        assert!(eval("local_chat.members.len == 2", &values));
+        assert!(eval("local_chat.members.1.name.len == 2", &values));
+        assert!(eval("location.system.len == 4", &values));
+        assert!(eval("local_chat.members.2 == null", &values));
+    }
+
+    #[test]
+    fn missing_values_only_equal_null() {
+        let values = [("location", Value::Null)];
+        assert!(eval("location == null", &values));
+        assert!(eval("location.system == null", &values));
+        assert!(!eval("location != null", &values));
+        assert!(!eval("location.system == \"Jita\"", &values));
+        assert!(eval("location.system != \"Jita\"", &values));
+        assert!(!eval("location.security_status < 0.5", &values));
+        assert!(!eval("location.security_status >= 0.5", &values));
+        assert!(!eval("location", &values));
+    }
+
+    #[test]
+    fn reports_what_is_wrong() {
+        assert_eq!(error("nope > 1"), "invalid condition `nope > 1`: unknown reader `nope`");
+        assert_eq!(error("(true"), "invalid condition `(true`: missing `)`");
+        assert_eq!(error("location == 'Jita"), "invalid condition `location == 'Jita`: unterminated string");
+        assert_eq!(error("location =="), "invalid condition `location ==`: unexpected end");
+        assert_eq!(error("&& true"), "invalid condition `&& true`: unexpected `&&`");
+        assert_eq!(error("true false"), "invalid condition `true false`: unexpected Ident(\"false\")");
+        assert_eq!(error("location # 1"), "invalid condition `location # 1`: unexpected `#`");
+        assert_eq!(error("1.2.3"), "invalid condition `1.2.3`: invalid number `1.2.3`");
+    }
+}
@@ -0,0 +1,156 @@
+pub mod condition;
+pub mod sinks;
+
+use crate::eve_process::ui_tree::UiTree;
+use crate::history::History;
+use crate::rules::condition::{Condition, Readouts};
+use crate::rules::sinks::AlertSink;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// An alert condition, loadable from JSON like
+/// `{"name": "neutral in local", "when": "local_chat.member_count > 5", "debounce_ms": 2000}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub when: Condition,
+    /// Sent with the alert, defaults to the condition.
+    #[serde(default)]
+    pub message: Option<String>,
+    /// How long the condition has to hold before the rule fires, to ride out flickering readouts.
+    #[serde(default)]
+    pub debounce_ms: u64,
+    /// Minimum time between two alerts of the rule.
+    #[serde(default)]
+    pub cooldown_ms: u64,
+    /// Sound file for sinks that play sounds.
+    #[serde(default)]
+    pub sound: Option<PathBuf>,
+}
+
+impl Rule {
+    pub fn new(name: &str, when: &str) -> io::Result<Self> {
+        Ok(Rule {
+            name: name.to_string(),
+            when: when.parse()?,
+            message: None,
+            debounce_ms: 0,
+            cooldown_ms: 0,
+            sound: None,
+        })
+    }
+}
+
+/// Reads a JSON array of [`Rule`]s.
+pub fn load_rules<P: AsRef<Path>>(path: P) -> io::Result<Vec<Rule>> {
+    Ok(serde_json::from_slice(&fs::read(path)?)?)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub message: String,
+    /// Seconds since the unix epoch.
+    pub time: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+struct RuleState {
+    rule: Rule,
+    /// When the condition started to hold.
+    since: Option<Instant>,
+    /// Whether the rule fired since the condition started to hold, it fires once per streak.
+    fired: bool,
+    last_fired: Option<Instant>,
+}
+
+/// Evaluates [`Rule`]s against UI trees and sends the alerts to its sinks.
+///
+/// A rule fires once when its condition starts to hold, and again only after the condition was
+/// false in between.
+pub struct AlertEngine {
+    rules: Vec<RuleState>,
+    sinks: Vec<Box<dyn AlertSink>>,
+    history: Option<History<UiTree>>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        AlertEngine {
+            rules: rules
+                .into_iter()
+                .map(|rule| RuleState {
+                    rule,
+                    since: None,
+                    fired: false,
+                    last_fired: None,
+                })
+                .collect(),
+            sinks: vec![],
+            history: None,
+        }
+    }
+
+    /// Keeps the trees evaluated during the last `retention`, see [`AlertEngine::history`].
+    pub fn keep_history(&mut self, retention: Duration) {
+        self.history = Some(History::new(retention));
+    }
+
+    /// The recently evaluated trees, including the one the latest alerts fired on.
+    pub fn history(&self) -> Option<&History<UiTree>> {
+        self.history.as_ref()
+    }
+
+    pub fn add_sink<S: AlertSink + 'static>(&mut self, sink: S) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    /// Evaluates all rules against `tree` and returns the alerts fired, after sending them.
+    pub fn evaluate(&mut self, tree: &UiTree) -> Vec<Alert> {
+        if let Some(history) = &mut self.history {
+            history.push(tree.clone());
+        }
+        let now = Instant::now();
+        let mut readouts = Readouts::new(tree);
+        let mut alerts = vec![];
+        for state in &mut self.rules {
+            if !state.rule.when.evaluate(&mut readouts) {
+                state.since = None;
+                state.fired = false;
+                continue;
+            }
+            let since = *state.since.get_or_insert(now);
+            let cooling_down = state
+                .last_fired
+                .is_some_and(|last| now - last < Duration::from_millis(state.rule.cooldown_ms));
+            if state.fired || now - since < Duration::from_millis(state.rule.debounce_ms) || cooling_down {
+                continue;
+            }
+            state.fired = true;
+            state.last_fired = Some(now);
+            alerts.push(Alert {
+                rule: state.rule.name.clone(),
+                message: state.rule.message.clone().unwrap_or_else(|| state.rule.when.to_string()),
+                time: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64(),
+                sound: state.rule.sound.clone(),
+            });
+        }
+        for alert in &alerts {
+            for sink in &mut self.sinks {
+                if let Err(e) = sink.send(alert) {
+                    warn!("failed to send alert {}: {}", alert.rule, e);
+                }
+            }
+        }
+        alerts
+    }
+}
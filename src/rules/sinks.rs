@@ -0,0 +1,108 @@
+use crate::export::jsonl::JsonLinesWriter;
+use crate::rules::Alert;
+#[cfg(feature = "audio")]
+use rodio::source::{SineWave, Source};
+#[cfg(feature = "audio")]
+use rodio::{Decoder, OutputStream, OutputStreamHandle};
+#[cfg(feature = "audio")]
+use std::fs::File;
+use std::io;
+#[cfg(feature = "audio")]
+use std::io::BufReader;
+use std::io::Write;
+#[cfg(feature = "audio")]
+use std::path::PathBuf;
+#[cfg(feature = "audio")]
+use std::time::Duration;
+use tracing::warn;
+
+/// Where an [`AlertEngine`](crate::rules::AlertEngine) sends its alerts.
+pub trait AlertSink {
+    fn send(&mut self, alert: &Alert) -> io::Result<()>;
+}
+
+/// Logs alerts as warnings.
+#[derive(Debug, Default)]
+pub struct LogSink;
+
+impl AlertSink for LogSink {
+    fn send(&mut self, alert: &Alert) -> io::Result<()> {
+        warn!("{}: {}", alert.rule, alert.message);
+        Ok(())
+    }
+}
+
+/// Callbacks are sinks.
+impl<F: FnMut(&Alert)> AlertSink for F {
+    fn send(&mut self, alert: &Alert) -> io::Result<()> {
+        self(alert);
+        Ok(())
+    }
+}
+
+impl<W: Write> AlertSink for JsonLinesWriter<W> {
+    fn send(&mut self, alert: &Alert) -> io::Result<()> {
+        self.write(alert)
+    }
+}
+
+/// Posts alerts as JSON to a URL.
+#[cfg(feature = "webhook")]
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    pub url: String,
+}
+
+#[cfg(feature = "webhook")]
+impl AlertSink for WebhookSink {
+    fn send(&mut self, alert: &Alert) -> io::Result<()> {
+        ureq::post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&serde_json::to_string(alert)?)
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+/// Plays the sound of the alert's rule on the default output device, or `default_sound`, or a
+/// beep. Sounds play in the background and overlap.
+#[cfg(feature = "audio")]
+pub struct SoundSink {
+    pub default_sound: Option<PathBuf>,
+    /// Factor applied to all sounds.
+    pub volume: f32,
+    /// Playback stops when the stream is dropped.
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+}
+
+#[cfg(feature = "audio")]
+impl SoundSink {
+    pub fn new() -> io::Result<Self> {
+        let (stream, handle) = OutputStream::try_default().map_err(io::Error::other)?;
+        Ok(SoundSink {
+            default_sound: None,
+            volume: 1.0,
+            _stream: stream,
+            handle,
+        })
+    }
+}
+
+#[cfg(feature = "audio")]
+impl AlertSink for SoundSink {
+    fn send(&mut self, alert: &Alert) -> io::Result<()> {
+        let played = match alert.sound.as_ref().or(self.default_sound.as_ref()) {
+            Some(path) => {
+                let sound = Decoder::new(BufReader::new(File::open(path)?))
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                self.handle.play_raw(sound.amplify(self.volume).convert_samples())
+            }
+            None => {
+                let beep = SineWave::new(880.0).take_duration(Duration::from_millis(300)).amplify(0.2 * self.volume);
+                self.handle.play_raw(beep)
+            }
+        };
+        played.map_err(io::Error::other)
+    }
+}
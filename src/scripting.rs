@@ -0,0 +1,105 @@
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::readers::read_json;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+use tracing::{debug, info};
+
+/// The UI tree of the current tick, passed to `on_tick` as `ui`.
+#[derive(Clone)]
+struct ScriptUi(Rc<UiTree>);
+
+impl ScriptUi {
+    fn read(&mut self, name: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        match read_json(&self.0, name).map_err(|e| e.to_string())? {
+            Some(value) => rhai::serde::to_dynamic(value),
+            None => Ok(Dynamic::UNIT),
+        }
+    }
+
+    fn query(&mut self, path: &str) -> Array {
+        self.0.root.query(path).into_iter().map(node_map).map(Dynamic::from_map).collect()
+    }
+
+    fn focused_text(&mut self) -> Dynamic {
+        self.0.focused_window_text().map_or(Dynamic::UNIT, Dynamic::from)
+    }
+}
+
+fn optional(text: Option<&str>) -> Dynamic {
+    text.map_or(Dynamic::UNIT, |text| text.into())
+}
+
+/// A node without its children, as scripts see it.
+fn node_map(node: &UiNode) -> Map {
+    let mut map = Map::new();
+    map.insert("addr".into(), (node.addr as i64).into());
+    map.insert("type".into(), node.py_type.clone().into());
+    map.insert("name".into(), optional(node.name()));
+    map.insert("text".into(), optional(node.text()));
+    map.insert("visible".into(), node.is_visible().into());
+    map.insert("x".into(), (node.rect.x as i64).into());
+    map.insert("y".into(), (node.rect.y as i64).into());
+    map.insert("width".into(), (node.rect.width as i64).into());
+    map.insert("height".into(), (node.rect.height as i64).into());
+    map
+}
+
+fn script_error(e: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// A [rhai](https://rhai.rs) script run on every poll tick.
+///
+/// The script defines `fn on_tick(ui)`, where `ui.read("local_chat")` runs a reader (see
+/// [`READER_NAMES`](crate::readers::READER_NAMES)) and returns its output as a map or `()`,
+/// `ui.query("**/OverviewScrollEntry")` returns the matching nodes and `ui.focused_text()` the
+/// text of the focused window. `this` is a map kept between ticks for the script's own state.
+/// `print` and `debug` go to the log.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    state: Dynamic,
+}
+
+impl Script {
+    /// Compiles `source` and runs its top level statements once.
+    pub fn compile(source: &str) -> io::Result<Self> {
+        let mut engine = Engine::new();
+        engine
+            .register_type_with_name::<ScriptUi>("Ui")
+            .register_fn("read", ScriptUi::read)
+            .register_fn("query", ScriptUi::query)
+            .register_fn("focused_text", ScriptUi::focused_text)
+            .on_print(|text| info!("{}", text))
+            .on_debug(|text, _, position| debug!("{} {}", position, text));
+        let ast = engine.compile(source).map_err(script_error)?;
+        engine.run_ast(&ast).map_err(script_error)?;
+        Ok(Script {
+            engine,
+            ast,
+            state: Dynamic::from_map(Map::new()),
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::compile(&fs::read_to_string(path)?)
+    }
+
+    /// Calls the script's `on_tick` with `tree`.
+    pub fn tick(&mut self, tree: &UiTree) -> io::Result<()> {
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut self.state);
+        self.engine
+            .call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                &self.ast,
+                "on_tick",
+                (ScriptUi(Rc::new(tree.clone())),),
+            )
+            .map(|_| ())
+            .map_err(script_error)
+    }
+}
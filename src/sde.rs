@@ -0,0 +1,161 @@
+use crate::readers::inventory::{CorpHangar, InventoryItem, ShipHangar};
+use crate::readers::ship_ui::ShipModules;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Static data of an item type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeInfo {
+    pub type_id: u32,
+    pub name: String,
+    pub group_id: Option<u32>,
+    /// Cubic meters.
+    pub volume: Option<f64>,
+    /// Kilograms.
+    pub mass: Option<f64>,
+    /// Cargo capacity in cubic meters.
+    pub capacity: Option<f64>,
+}
+
+/// Item types of the EVE Static Data Export, for resolving the typeIDs found in memory.
+#[derive(Debug, Clone, Default)]
+pub struct Sde {
+    types: HashMap<u32, TypeInfo>,
+    by_name: HashMap<String, u32>,
+}
+
+/// An entry of the SDE's `types.yaml`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YamlType {
+    name: HashMap<String, String>,
+    #[serde(rename = "groupID")]
+    group_id: Option<u32>,
+    volume: Option<f64>,
+    mass: Option<f64>,
+    capacity: Option<f64>,
+}
+
+impl Sde {
+    fn from_types(types: impl IntoIterator<Item = TypeInfo>) -> Self {
+        let types: HashMap<_, _> = types.into_iter().map(|info| (info.type_id, info)).collect();
+        let by_name = types.values().map(|info| (info.name.to_lowercase(), info.type_id)).collect();
+        Sde { types, by_name }
+    }
+
+    /// Loads the `invTypes` table of an SDE conversion to SQLite.
+    pub fn from_sqlite<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let conn = Connection::open(path).map_err(io::Error::other)?;
+        let mut query = conn
+            .prepare("SELECT typeID, typeName, groupID, volume, mass, capacity FROM invTypes")
+            .map_err(io::Error::other)?;
+        let types = query
+            .query_map([], |row| {
+                Ok(TypeInfo {
+                    type_id: row.get(0)?,
+                    name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                    group_id: row.get(2)?,
+                    volume: row.get(3)?,
+                    mass: row.get(4)?,
+                    capacity: row.get(5)?,
+                })
+            })
+            .map_err(io::Error::other)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(io::Error::other)?;
+        Ok(Self::from_types(types))
+    }
+
+    /// Loads `fsd/types.yaml` of the official SDE, with names in `language` (`en`, `zh`, ...)
+    /// where available and in English otherwise.
+    pub fn from_yaml<P: AsRef<Path>>(path: P, language: &str) -> io::Result<Self> {
+        let types: HashMap<u32, YamlType> = serde_yaml::from_reader(BufReader::new(File::open(path)?))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self::from_types(types.into_iter().map(|(type_id, yaml)| TypeInfo {
+            type_id,
+            name: yaml
+                .name
+                .get(language)
+                .or_else(|| yaml.name.get("en"))
+                .cloned()
+                .unwrap_or_default(),
+            group_id: yaml.group_id,
+            volume: yaml.volume,
+            mass: yaml.mass,
+            capacity: yaml.capacity,
+        })))
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub fn get(&self, type_id: u32) -> Option<&TypeInfo> {
+        self.types.get(&type_id)
+    }
+
+    pub fn type_name(&self, type_id: u32) -> Option<&str> {
+        self.get(type_id).map(|info| info.name.as_str())
+    }
+
+    /// Looks a type up by its name, ignoring case.
+    pub fn find_by_name(&self, name: &str) -> Option<&TypeInfo> {
+        self.by_name.get(&name.to_lowercase()).and_then(|type_id| self.get(*type_id))
+    }
+}
+
+/// Reader outputs with type ids that can be filled in from the SDE.
+pub trait ResolveTypes {
+    /// Fills in type names from type ids and the other way around, keeping what the UI showed.
+    fn resolve_types(&mut self, sde: &Sde);
+}
+
+impl ResolveTypes for InventoryItem {
+    fn resolve_types(&mut self, sde: &Sde) {
+        if self.type_id.is_none() {
+            // named items such as ships show their own name, the type name is tried first
+            self.type_id = self
+                .type_name
+                .as_deref()
+                .and_then(|name| sde.find_by_name(name))
+                .or_else(|| sde.find_by_name(&self.name))
+                .map(|info| info.type_id);
+        }
+        if self.type_name.is_none() {
+            self.type_name = self.type_id.and_then(|type_id| sde.type_name(type_id)).map(str::to_string);
+        }
+    }
+}
+
+impl ResolveTypes for ShipHangar {
+    fn resolve_types(&mut self, sde: &Sde) {
+        self.ships.iter_mut().for_each(|ship| ship.resolve_types(sde));
+    }
+}
+
+impl ResolveTypes for CorpHangar {
+    fn resolve_types(&mut self, sde: &Sde) {
+        for division in &mut self.divisions {
+            division.items.iter_mut().for_each(|item| item.resolve_types(sde));
+        }
+    }
+}
+
+impl ResolveTypes for ShipModules {
+    fn resolve_types(&mut self, sde: &Sde) {
+        for slot in &mut self.slots {
+            if slot.type_name.is_none() {
+                slot.type_name = slot.type_id.and_then(|type_id| sde.type_name(type_id)).map(str::to_string);
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::eve_process::session::EVESessionManager;
+use crate::eve_process::ui_tree::UiTree;
+use crate::eve_process::ui_watcher::UiUpdate;
+use crate::export::jsonl::WatchEvent;
+use crate::server::grpc::{self, EveReaderService};
+use crate::server::websocket::PushServer;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone)]
+pub struct DaemonConfig {
+    pub grpc: Option<SocketAddr>,
+    pub websocket: Option<SocketAddr>,
+    /// How often to look for clients that started or exited.
+    pub scan_interval: Duration,
+    /// How often to read the UI of attached clients and push the changes.
+    pub watch_interval: Duration,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        DaemonConfig {
+            grpc: Some(SocketAddr::from(([127, 0, 0, 1], 50051))),
+            websocket: Some(SocketAddr::from(([127, 0, 0, 1], 8765))),
+            scan_interval: Duration::from_secs(10),
+            watch_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// A watch event tagged with the client it came from.
+#[derive(Serialize)]
+struct ClientEvent<'a> {
+    pid: u32,
+    #[serde(flatten)]
+    event: &'a WatchEvent,
+}
+
+struct Daemon {
+    config: DaemonConfig,
+    clients: EVESessionManager,
+    previous: HashMap<u32, UiTree>,
+    push: Option<PushServer>,
+    /// WebSocket clients at the last read.
+    listeners: usize,
+}
+
+impl Daemon {
+    /// Attaches clients that appeared and forgets the ones that exited.
+    fn scan(&mut self) {
+        match self.clients.remove_exited() {
+            Ok(exited) => {
+                for pid in exited {
+                    self.previous.remove(&pid);
+                }
+            }
+            Err(e) => {
+                warn!("failed to list clients: {}", e);
+                return;
+            }
+        }
+        // clients still loading are retried on the next scan
+        if let Err(e) = self.clients.init_all() {
+            warn!("failed to list clients: {}", e);
+        }
+    }
+
+    /// Reads the UI of all attached clients and pushes what changed.
+    fn watch(&mut self) {
+        let Some(push) = &self.push else { return };
+        let listeners = push.client_count();
+        if listeners > self.listeners {
+            // start over with snapshots so that new listeners get the whole trees
+            self.previous.clear();
+        }
+        self.listeners = listeners;
+        if listeners == 0 {
+            return;
+        }
+        for (&pid, process) in &mut self.clients.sessions {
+            let Some(tree) = process.ui_tree() else { continue };
+            let update = UiUpdate {
+                changes: self.previous.get(&pid).map(|previous| tree.diff(previous)).unwrap_or_default(),
+                tree,
+            };
+            let snapshot = !self.previous.contains_key(&pid);
+            for event in WatchEvent::from_update(&update, snapshot) {
+                if let Err(e) = push.broadcast(&ClientEvent { pid, event: &event }) {
+                    warn!("failed to push update of client {}: {}", pid, e);
+                }
+            }
+            self.previous.insert(pid, update.tree);
+        }
+    }
+}
+
+/// Runs until the process is killed: keeps every EVE client on this machine attached and serves
+/// them over gRPC and WebSocket, as configured.
+///
+/// Requests from gRPC clients run on this thread between the scans and reads.
+pub fn run(config: DaemonConfig) -> io::Result<()> {
+    let (service, jobs) = EveReaderService::with_queue();
+    // without gRPC the unused service is kept around so that the queue stays connected
+    let _service = match config.grpc {
+        Some(addr) => {
+            info!("serving gRPC on {}", addr);
+            thread::spawn(move || {
+                if let Err(e) = grpc::serve_service(service, addr) {
+                    error!("gRPC server stopped: {}", e);
+                }
+            });
+            None
+        }
+        None => Some(service),
+    };
+    let push = config.websocket.map(PushServer::bind).transpose()?;
+    let mut daemon = Daemon {
+        config,
+        clients: EVESessionManager::new(),
+        previous: HashMap::new(),
+        push,
+        listeners: 0,
+    };
+    let mut next_scan = Instant::now();
+    let mut next_watch = Instant::now();
+    loop {
+        let now = Instant::now();
+        if now >= next_scan {
+            daemon.scan();
+            next_scan = now + daemon.config.scan_interval;
+        }
+        if now >= next_watch {
+            daemon.watch();
+            next_watch = now + daemon.config.watch_interval;
+        }
+        match jobs.recv_timeout(next_scan.min(next_watch).saturating_duration_since(Instant::now())) {
+            Ok(job) => job(&mut daemon.clients),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => thread::sleep(daemon.config.watch_interval),
+        }
+    }
+}
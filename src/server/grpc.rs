@@ -0,0 +1,336 @@
+use crate::eve_process::session::EVESessionManager;
+use crate::eve_process::ui_tree::{UiNode, UiRect, UiTree};
+use crate::readers;
+use crate::readers::flight_state::FlightState;
+use crate::readers::local::{LocalChat, LocalMember, Standing};
+use crate::readers::location::Location;
+use crate::readers::targets::{LockInProgress, Target, Targets};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::mpsc;
+use std::thread;
+use tokio::sync::oneshot;
+use tonic::{Request, Response, Status};
+
+pub mod proto {
+    tonic::include_proto!("pyevereader");
+}
+
+use proto::eve_reader_server::{EveReader, EveReaderServer};
+
+/// A request to run on the thread owning the attached clients.
+pub type Job = Box<dyn FnOnce(&mut EVESessionManager) + Send>;
+
+/// Implementation of the `EveReader` service in `proto/pyevereader.proto`.
+///
+/// Attached clients are owned by a single thread that runs the requests one at a time, see
+/// [`EveReaderService::with_queue`].
+pub struct EveReaderService {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl Default for EveReaderService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EveReaderService {
+    /// Creates the service with its own thread owning the attached clients.
+    pub fn new() -> Self {
+        let (service, queue) = Self::with_queue();
+        thread::spawn(move || {
+            let mut clients = EVESessionManager::new();
+            for job in queue {
+                job(&mut clients);
+            }
+        });
+        service
+    }
+
+    /// Creates the service without a thread, the caller has to run the received jobs against
+    /// the clients it owns.
+    pub fn with_queue() -> (Self, mpsc::Receiver<Job>) {
+        let (jobs, queue) = mpsc::channel();
+        (EveReaderService { jobs }, queue)
+    }
+
+    async fn run<T, F>(&self, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut EVESessionManager) -> io::Result<T> + Send + 'static,
+    {
+        let (result, receiver) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |clients| {
+                let _ = result.send(f(clients));
+            }))
+            .map_err(|_| Status::unavailable("reader thread stopped"))?;
+        receiver
+            .await
+            .map_err(|_| Status::internal("reader thread panicked"))?
+            .map_err(Status::from)
+    }
+
+    /// Runs `f` on a fresh UI tree of an attached client.
+    async fn with_tree<T, F>(&self, pid: u32, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(&UiTree) -> io::Result<T> + Send + 'static,
+    {
+        self.run(move |clients| {
+            let process = clients
+                .get_mut(pid)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotConnected, format!("client {} is not attached", pid)))?;
+            let tree = process
+                .ui_tree()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no UI root found"))?;
+            f(&tree)
+        })
+        .await
+    }
+}
+
+#[tonic::async_trait]
+impl EveReader for EveReaderService {
+    async fn list_clients(
+        &self,
+        _request: Request<proto::ListClientsRequest>,
+    ) -> Result<Response<proto::ListClientsResponse>, Status> {
+        let clients = self
+            .run(|attached| {
+                Ok(EVESessionManager::running_clients()?
+                    .into_iter()
+                    .map(|process| proto::Client {
+                        attached: attached.is_attached(process.pid),
+                        pid: process.pid,
+                        path: process.path,
+                        title: process.title,
+                    })
+                    .collect())
+            })
+            .await?;
+        Ok(Response::new(proto::ListClientsResponse { clients }))
+    }
+
+    async fn attach(&self, request: Request<proto::ClientRequest>) -> Result<Response<proto::AttachResponse>, Status> {
+        let pid = request.into_inner().pid;
+        let response = self
+            .run(move |clients| {
+                let process = clients.attach(pid)?;
+                let type_object = process.py_type.upgrade().map_or(0, |py_type| py_type.base_addr);
+                let ui_root_type = process.ui_root.upgrade().map(|ui_root| ui_root.base_addr);
+                Ok(proto::AttachResponse {
+                    type_object,
+                    ui_root_type,
+                })
+            })
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn detach(&self, request: Request<proto::ClientRequest>) -> Result<Response<proto::DetachResponse>, Status> {
+        let pid = request.into_inner().pid;
+        self.run(move |clients| {
+            clients.detach(pid);
+            Ok(())
+        })
+        .await?;
+        Ok(Response::new(proto::DetachResponse {}))
+    }
+
+    async fn get_ui_tree(&self, request: Request<proto::ClientRequest>) -> Result<Response<proto::UiTree>, Status> {
+        let tree = self
+            .with_tree(request.into_inner().pid, |tree| {
+                Ok(proto::UiTree {
+                    root: Some((&tree.root).into()),
+                })
+            })
+            .await?;
+        Ok(Response::new(tree))
+    }
+
+    async fn get_local_chat(&self, request: Request<proto::ClientRequest>) -> Result<Response<proto::LocalChat>, Status> {
+        let local = self
+            .with_tree(request.into_inner().pid, |tree| {
+                tree.read::<LocalChat>()
+                    .map(|local| (&local).into())
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "local chat is not open"))
+            })
+            .await?;
+        Ok(Response::new(local))
+    }
+
+    async fn get_location(&self, request: Request<proto::ClientRequest>) -> Result<Response<proto::Location>, Status> {
+        let location = self
+            .with_tree(request.into_inner().pid, |tree| {
+                tree.read::<Location>()
+                    .map(proto::Location::from)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "location is not shown"))
+            })
+            .await?;
+        Ok(Response::new(location))
+    }
+
+    async fn get_targets(&self, request: Request<proto::ClientRequest>) -> Result<Response<proto::Targets>, Status> {
+        let targets = self
+            .with_tree(request.into_inner().pid, |tree| {
+                Ok(tree.read::<Targets>().map(|targets| (&targets).into()).unwrap_or_default())
+            })
+            .await?;
+        Ok(Response::new(targets))
+    }
+
+    async fn get_flight_state(
+        &self,
+        request: Request<proto::ClientRequest>,
+    ) -> Result<Response<proto::FlightStateResponse>, Status> {
+        let state = self
+            .with_tree(request.into_inner().pid, |tree| {
+                Ok(tree.read::<FlightState>().map_or(proto::FlightState::Unknown, Into::into))
+            })
+            .await?;
+        Ok(Response::new(proto::FlightStateResponse { state: state.into() }))
+    }
+
+    async fn read(&self, request: Request<proto::ReadRequest>) -> Result<Response<proto::ReadResponse>, Status> {
+        let request = request.into_inner();
+        let json = self
+            .with_tree(request.pid, move |tree| {
+                Ok(readers::read_json(tree, &request.reader)?.map(|value| value.to_string()))
+            })
+            .await?;
+        Ok(Response::new(proto::ReadResponse { json }))
+    }
+}
+
+/// Serves the `EveReader` service on `addr`, blocks until the server fails.
+pub fn serve(addr: SocketAddr) -> io::Result<()> {
+    serve_service(EveReaderService::new(), addr)
+}
+
+pub fn serve_service(service: EveReaderService, addr: SocketAddr) -> io::Result<()> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(async {
+            tonic::transport::Server::builder()
+                .add_service(EveReaderServer::new(service))
+                .serve(addr)
+                .await
+                .map_err(io::Error::other)
+        })
+}
+
+impl From<UiRect> for proto::Rect {
+    fn from(rect: UiRect) -> Self {
+        proto::Rect {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+impl From<&UiNode> for proto::UiNode {
+    fn from(node: &UiNode) -> Self {
+        proto::UiNode {
+            addr: node.addr,
+            r#type: node.py_type.clone(),
+            name: node.name().map(str::to_string),
+            rect: Some(node.rect.into()),
+            visible: node.is_visible(),
+            text: node.text().map(str::to_string),
+            hint: node.hint().map(str::to_string),
+            children: node.children.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Standing> for proto::Standing {
+    fn from(standing: Standing) -> Self {
+        match standing {
+            Standing::Excellent => proto::Standing::Excellent,
+            Standing::Good => proto::Standing::Good,
+            Standing::Neutral => proto::Standing::Neutral,
+            Standing::Bad => proto::Standing::Bad,
+            Standing::Terrible => proto::Standing::Terrible,
+            Standing::Friendly => proto::Standing::Friendly,
+            Standing::Criminal => proto::Standing::Criminal,
+            Standing::AtWar => proto::Standing::AtWar,
+        }
+    }
+}
+
+impl From<&LocalMember> for proto::LocalMember {
+    fn from(member: &LocalMember) -> Self {
+        proto::LocalMember {
+            name: member.name.clone(),
+            standing: proto::Standing::from(member.standing).into(),
+        }
+    }
+}
+
+impl From<&LocalChat> for proto::LocalChat {
+    fn from(local: &LocalChat) -> Self {
+        proto::LocalChat {
+            count: local.count() as u32,
+            members: local.members.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Location> for proto::Location {
+    fn from(location: Location) -> Self {
+        proto::Location {
+            system: location.system,
+            security_status: location.security_status,
+            constellation: location.constellation,
+            region: location.region,
+            station: location.station,
+        }
+    }
+}
+
+impl From<&Target> for proto::Target {
+    fn from(target: &Target) -> Self {
+        proto::Target {
+            name: target.name.clone(),
+            type_name: target.type_name.clone(),
+            distance: target.distance,
+            is_active: target.is_active,
+            rect: Some(target.rect.into()),
+        }
+    }
+}
+
+impl From<&LockInProgress> for proto::LockInProgress {
+    fn from(lock: &LockInProgress) -> Self {
+        proto::LockInProgress {
+            target: Some((&lock.target).into()),
+            progress: lock.progress,
+        }
+    }
+}
+
+impl From<&Targets> for proto::Targets {
+    fn from(targets: &Targets) -> Self {
+        proto::Targets {
+            locked: targets.locked.iter().map(Into::into).collect(),
+            locking: targets.locking.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<FlightState> for proto::FlightState {
+    fn from(state: FlightState) -> Self {
+        match state {
+            FlightState::Docked => proto::FlightState::Docked,
+            FlightState::InHangar => proto::FlightState::InHangar,
+            FlightState::InSpace => proto::FlightState::InSpace,
+            FlightState::Warping => proto::FlightState::Warping,
+            FlightState::Jumping => proto::FlightState::Jumping,
+        }
+    }
+}
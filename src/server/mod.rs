@@ -0,0 +1,6 @@
+#[cfg(feature = "daemon")]
+pub mod daemon;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "websocket")]
+pub mod websocket;
\ No newline at end of file
@@ -0,0 +1,117 @@
+use crate::eve_process::ui_watcher::UiUpdate;
+use crate::export::jsonl::WatchEvent;
+use serde::Serialize;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info};
+use tungstenite::Message;
+
+/// Time a client gets to complete the handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time a single message may take to be written to a client.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Messages queued for a client before it counts as too slow and is dropped.
+const QUEUE_LEN: usize = 256;
+
+/// Pushes [`WatchEvent`]s and other records as JSON text messages to every connected WebSocket client.
+///
+/// Clients only listen, anything they send is ignored. Every client is written to by its own
+/// thread, clients whose connection fails or that fall [`QUEUE_LEN`] messages behind are dropped
+/// on the next broadcast.
+#[derive(Clone)]
+pub struct PushServer {
+    local_addr: SocketAddr,
+    clients: Arc<Mutex<Vec<SyncSender<Arc<str>>>>>,
+}
+
+/// Completes the handshake with a new client and writes the messages queued for it until the
+/// connection fails or the server drops it.
+fn serve_client(stream: TcpStream, clients: &Mutex<Vec<SyncSender<Arc<str>>>>) {
+    let peer = stream.peer_addr().ok();
+    let timeouts = stream
+        .set_read_timeout(Some(HANDSHAKE_TIMEOUT))
+        .and_then(|()| stream.set_write_timeout(Some(WRITE_TIMEOUT)));
+    if let Err(e) = timeouts {
+        debug!("websocket client {:?} not accepted: {}", peer, e);
+        return;
+    }
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(e) => {
+            debug!("websocket handshake with {:?} failed: {}", peer, e);
+            return;
+        }
+    };
+    info!("websocket client {:?} connected", peer);
+    let (queue, messages) = mpsc::sync_channel::<Arc<str>>(QUEUE_LEN);
+    clients.lock().unwrap().push(queue);
+    for text in messages {
+        if let Err(e) = socket.send(Message::text(&*text)) {
+            debug!("dropped websocket client {:?}: {}", peer, e);
+            return;
+        }
+    }
+    let _ = socket.close(None);
+}
+
+impl PushServer {
+    /// Binds `addr` and accepts clients on a background thread.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let server = PushServer {
+            local_addr: listener.local_addr()?,
+            clients: Arc::new(Mutex::new(vec![])),
+        };
+        let clients = server.clients.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let clients = clients.clone();
+                thread::spawn(move || serve_client(stream, &clients));
+            }
+        });
+        info!("websocket push server listening on {}", server.local_addr);
+        Ok(server)
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    /// Queues `record` for all clients without waiting for them and returns how many it was
+    /// queued for.
+    pub fn broadcast<T: Serialize>(&self, record: &T) -> io::Result<usize> {
+        let text: Arc<str> = serde_json::to_string(record)?.into();
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|queue| match queue.try_send(text.clone()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                debug!("dropped websocket client that is {} messages behind", QUEUE_LEN);
+                false
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+        Ok(clients.len())
+    }
+
+    /// Pushes a watcher update, as one snapshot or one message per change.
+    pub fn push_update(&self, update: &UiUpdate, snapshots: bool) -> io::Result<()> {
+        for event in WatchEvent::from_update(update, snapshots) {
+            self.broadcast(&event)?;
+        }
+        Ok(())
+    }
+
+    /// Pushes the output of a reader, e.g. `push_reading("local", &local_chat)`.
+    pub fn push_reading<T: Serialize>(&self, reader: &str, value: &T) -> io::Result<()> {
+        self.broadcast(&WatchEvent::reading(reader, value)?).map(|_| ())
+    }
+}
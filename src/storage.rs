@@ -0,0 +1,123 @@
+use crate::readers::local::LocalChat;
+use crate::readers::lp_store::LpStore;
+use crate::readers::performance::ClientPerformance;
+use crate::readers::ship_ui::ShipModules;
+use rusqlite::{params, Connection};
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Numeric values of a readout that are worth keeping over time.
+pub trait Metrics {
+    /// Pairs of metric name and value, values the client did not show are left out.
+    fn metrics(&self) -> Vec<(&'static str, f64)>;
+}
+
+fn sql_error(e: rusqlite::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+/// Appends timestamped metrics to a SQLite database, `time` is in seconds since the unix epoch.
+pub struct Storage {
+    conn: Connection,
+}
+
+impl Storage {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::with_connection(Connection::open(path).map_err(sql_error)?)
+    }
+
+    pub fn in_memory() -> io::Result<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(sql_error)?)
+    }
+
+    fn with_connection(conn: Connection) -> io::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS samples (
+                time REAL NOT NULL,
+                metric TEXT NOT NULL,
+                value REAL NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS samples_metric_time ON samples (metric, time);",
+        )
+        .map_err(sql_error)?;
+        Ok(Storage { conn })
+    }
+
+    pub fn record(&self, time: f64, metric: &str, value: f64) -> io::Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO samples (time, metric, value) VALUES (?1, ?2, ?3)",
+                params![time, metric, value],
+            )
+            .map(|_| ())
+            .map_err(sql_error)
+    }
+
+    /// Stores all metrics of `readout` at the current time in one transaction.
+    pub fn append<M: Metrics>(&mut self, readout: &M) -> io::Result<usize> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let metrics = readout.metrics();
+        let tx = self.conn.transaction().map_err(sql_error)?;
+        {
+            let mut insert = tx
+                .prepare_cached("INSERT INTO samples (time, metric, value) VALUES (?1, ?2, ?3)")
+                .map_err(sql_error)?;
+            for (metric, value) in &metrics {
+                insert.execute(params![time, metric, value]).map_err(sql_error)?;
+            }
+        }
+        tx.commit().map_err(sql_error)?;
+        Ok(metrics.len())
+    }
+
+    /// All samples of `metric` as `(time, value)` pairs, oldest first.
+    pub fn series(&self, metric: &str) -> io::Result<Vec<(f64, f64)>> {
+        let mut query = self
+            .conn
+            .prepare_cached("SELECT time, value FROM samples WHERE metric = ?1 ORDER BY time")
+            .map_err(sql_error)?;
+        let rows = query
+            .query_map(params![metric], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(sql_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sql_error)
+    }
+}
+
+impl Metrics for LocalChat {
+    fn metrics(&self) -> Vec<(&'static str, f64)> {
+        vec![("local_count", self.count() as f64)]
+    }
+}
+
+impl Metrics for LpStore {
+    fn metrics(&self) -> Vec<(&'static str, f64)> {
+        self.balance.map(|balance| ("lp_balance", balance as f64)).into_iter().collect()
+    }
+}
+
+impl Metrics for ShipModules {
+    fn metrics(&self) -> Vec<(&'static str, f64)> {
+        [
+            ("heat_high", self.heat.high),
+            ("heat_medium", self.heat.medium),
+            ("heat_low", self.heat.low),
+        ]
+        .into_iter()
+        .filter_map(|(metric, value)| Some((metric, value?)))
+        .chain([("overloaded_modules", self.overloaded().count() as f64)])
+        .collect()
+    }
+}
+
+impl Metrics for ClientPerformance {
+    fn metrics(&self) -> Vec<(&'static str, f64)> {
+        [("fps", self.fps), ("frame_time_ms", self.frame_time), ("latency_ms", self.latency)]
+            .into_iter()
+            .filter_map(|(metric, value)| Some((metric, value?)))
+            .collect()
+    }
+}
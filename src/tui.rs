@@ -0,0 +1,270 @@
+use crate::eve_process::annotations::{annotate, annotation};
+use crate::eve_process::ui_tree::{UiNode, UiTree};
+use crate::eve_process::ui_watcher::UiWatcher;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::io;
+use std::time::Instant;
+
+const HELP: &str = "↑↓ move  → expand  ← collapse  / search  n next  a annotate  q quit";
+
+/// Addresses from the root down to the node at `addr`.
+fn path_to(node: &UiNode, addr: u64) -> Option<Vec<u64>> {
+    if node.addr == addr {
+        return Some(vec![addr]);
+    }
+    node.children.iter().find_map(|child| {
+        let mut path = path_to(child, addr)?;
+        path.insert(0, node.addr);
+        Some(path)
+    })
+}
+
+fn describe(node: &UiNode) -> String {
+    let mut label = node.py_type.clone();
+    if let Some(name) = node.name() {
+        label += &format!(" {}", name);
+    }
+    if let Some(text) = node.text() {
+        label += &format!(" {:?}", text);
+    }
+    if let Some(annotation) = annotation(node.addr) {
+        label += &format!(" «{}»", annotation);
+    }
+    label
+}
+
+/// What the text typed at the status line is for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    Search,
+    Annotation,
+}
+
+struct Explorer {
+    tree: UiTree,
+    expanded: HashSet<u64>,
+    selected: u64,
+    /// Text being typed after `/` or `a`.
+    input: Option<(Prompt, String)>,
+    query: String,
+    status: String,
+}
+
+impl Explorer {
+    fn new(tree: UiTree) -> Self {
+        Explorer {
+            expanded: HashSet::from([tree.root.addr]),
+            selected: tree.root.addr,
+            tree,
+            input: None,
+            query: String::new(),
+            status: HELP.to_string(),
+        }
+    }
+
+    /// Nodes shown in the tree view with their depth, in tree order.
+    fn rows(&self) -> Vec<(usize, &UiNode)> {
+        fn visit<'a>(node: &'a UiNode, depth: usize, expanded: &HashSet<u64>, rows: &mut Vec<(usize, &'a UiNode)>) {
+            rows.push((depth, node));
+            if expanded.contains(&node.addr) {
+                for child in &node.children {
+                    visit(child, depth + 1, expanded, rows);
+                }
+            }
+        }
+        let mut rows = vec![];
+        visit(&self.tree.root, 0, &self.expanded, &mut rows);
+        rows
+    }
+
+    fn selected_index(&self, rows: &[(usize, &UiNode)]) -> usize {
+        rows.iter().position(|(_, node)| node.addr == self.selected).unwrap_or(0)
+    }
+
+    fn selected_node(&self) -> Option<&UiNode> {
+        self.tree.root.iter().find(|node| node.addr == self.selected)
+    }
+
+    fn move_by(&mut self, offset: isize) {
+        let rows = self.rows();
+        let index = self.selected_index(&rows).saturating_add_signed(offset).min(rows.len() - 1);
+        self.selected = rows[index].1.addr;
+    }
+
+    fn collapse(&mut self) {
+        if !self.expanded.remove(&self.selected) {
+            // already collapsed, go up to the parent instead
+            if let Some(path) = path_to(&self.tree.root, self.selected) {
+                if path.len() > 1 {
+                    self.selected = path[path.len() - 2];
+                }
+            }
+        }
+    }
+
+    /// Selects the next node after the current one whose description contains the query.
+    fn find_next(&mut self) {
+        if self.query.is_empty() {
+            return;
+        }
+        let query = self.query.to_lowercase();
+        let nodes: Vec<_> = self.tree.root.iter().collect();
+        let start = nodes.iter().position(|node| node.addr == self.selected).unwrap_or(0);
+        let found = (1..=nodes.len())
+            .map(|offset| nodes[(start + offset) % nodes.len()])
+            .find(|node| describe(node).to_lowercase().contains(&query))
+            .map(|node| node.addr);
+        match found {
+            Some(addr) => {
+                let path = path_to(&self.tree.root, addr).unwrap_or_default();
+                self.expanded.extend(&path[..path.len().saturating_sub(1)]);
+                self.selected = addr;
+                self.status = HELP.to_string();
+            }
+            None => self.status = format!("{:?} not found", self.query),
+        }
+    }
+
+    /// Returns `false` when the explorer should quit.
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Some((_, input)) = &mut self.input {
+            match key {
+                KeyCode::Enter => {
+                    let (prompt, input) = self.input.take().unwrap_or((Prompt::Search, String::new()));
+                    if prompt == Prompt::Search {
+                        self.query = input;
+                        self.find_next();
+                    } else if let Err(e) = annotate(self.selected, input.trim()) {
+                        self.status = format!("failed to save annotation: {}", e);
+                    }
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::PageUp => self.move_by(-20),
+            KeyCode::PageDown => self.move_by(20),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => {
+                self.expanded.insert(self.selected);
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Char('/') => self.input = Some((Prompt::Search, String::new())),
+            KeyCode::Char('a') => {
+                let current = annotation(self.selected).unwrap_or_default();
+                self.input = Some((Prompt::Annotation, current));
+            }
+            KeyCode::Char('n') => self.find_next(),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [tree_area, attrs_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main);
+
+        let rows = self.rows();
+        let items: Vec<_> = rows
+            .iter()
+            .map(|&(depth, node)| {
+                let marker = match (node.children.is_empty(), self.expanded.contains(&node.addr)) {
+                    (true, _) => "  ",
+                    (false, true) => "▾ ",
+                    (false, false) => "▸ ",
+                };
+                let style = if node.is_visible() {
+                    Style::default()
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw("  ".repeat(depth)),
+                    Span::raw(marker),
+                    Span::styled(describe(node), style),
+                ]))
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(self.selected_index(&rows)));
+        let list = List::new(items)
+            .block(Block::bordered().title(format!("UI tree ({} nodes)", self.tree.node_count())))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, tree_area, &mut state);
+
+        let mut lines = vec![];
+        if let Some(node) = self.selected_node() {
+            let rect = node.rect;
+            lines.push(Line::from(format!("addr    0x{:X}", node.addr)));
+            if let Some(annotation) = annotation(node.addr) {
+                lines.push(Line::from(format!("label   {}", annotation)));
+            }
+            lines.push(Line::from(format!("type    {}", node.py_type)));
+            lines.push(Line::from(format!("rect    {},{} {}x{}", rect.x, rect.y, rect.width, rect.height)));
+            lines.push(Line::from(format!("visible {}", node.is_visible())));
+            lines.push(Line::from(""));
+            for (key, value) in &node.attrs {
+                let value = serde_json::to_string(value).unwrap_or_default();
+                lines.push(Line::from(vec![
+                    Span::styled(key.clone(), Style::default().fg(Color::Cyan)),
+                    Span::raw(" = "),
+                    Span::raw(value),
+                ]));
+            }
+        }
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("attributes")), attrs_area);
+
+        let status_line = match &self.input {
+            Some((Prompt::Search, input)) => format!("/{}", input),
+            Some((Prompt::Annotation, input)) => format!("label: {}", input),
+            None => self.status.clone(),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal, watcher: &mut UiWatcher) -> io::Result<()> {
+        let mut last_poll = Instant::now();
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if event::poll(watcher.interval.saturating_sub(last_poll.elapsed()))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key.code) {
+                        return Ok(());
+                    }
+                }
+            } else {
+                if let Some(update) = watcher.poll() {
+                    self.tree = update.tree;
+                }
+                last_poll = Instant::now();
+            }
+        }
+    }
+}
+
+/// Runs the interactive tree explorer until `q` is pressed, re-reading the tree at the watcher interval.
+pub fn run(mut watcher: UiWatcher) -> io::Result<()> {
+    let tree = watcher
+        .poll()
+        .map(|update| update.tree)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no UI root found"))?;
+    let mut explorer = Explorer::new(tree);
+    let mut terminal = ratatui::init();
+    let result = explorer.run(&mut terminal, &mut watcher);
+    ratatui::restore();
+    result
+}